@@ -0,0 +1,26 @@
+create table public.abandoned_cart_reminders (
+  id uuid not null default gen_random_uuid (),
+  cart_id uuid not null,
+  user_id uuid not null,
+  restore_token text not null,
+  cart_last_activity timestamp with time zone not null,
+  items jsonb not null default '[]'::jsonb,
+  email_id uuid null,
+  created_at timestamp with time zone not null default now(),
+  restored_at timestamp with time zone null,
+  recovered_at timestamp with time zone null,
+  recovered_order_id uuid null,
+  constraint abandoned_cart_reminders_pkey primary key (id),
+  constraint abandoned_cart_reminders_restore_token_key unique (restore_token),
+  constraint abandoned_cart_reminders_cart_activity_key unique (cart_id, cart_last_activity),
+  constraint abandoned_cart_reminders_cart_id_fkey foreign KEY (cart_id) references carts (id) on delete CASCADE,
+  constraint abandoned_cart_reminders_user_id_fkey foreign KEY (user_id) references users (id) on delete CASCADE,
+  constraint abandoned_cart_reminders_email_id_fkey foreign KEY (email_id) references email_outbox (id) on delete set null,
+  constraint abandoned_cart_reminders_recovered_order_id_fkey foreign KEY (recovered_order_id) references orders (id) on delete set null
+) TABLESPACE pg_default;
+
+create index IF not exists idx_abandoned_cart_reminders_user_id on public.abandoned_cart_reminders using btree (user_id) TABLESPACE pg_default
+where
+  (recovered_at is null);
+
+create index IF not exists idx_abandoned_cart_reminders_created_at on public.abandoned_cart_reminders using btree (created_at desc) TABLESPACE pg_default;
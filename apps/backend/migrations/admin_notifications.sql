@@ -0,0 +1,20 @@
+create table public.admin_notifications (
+  id uuid not null default gen_random_uuid (),
+  kind text not null,
+  title text not null,
+  message text not null,
+  product_id uuid null,
+  created_at timestamp with time zone not null default now(),
+  read_at timestamp with time zone null,
+  read_by uuid null,
+  constraint admin_notifications_pkey primary key (id),
+  constraint admin_notifications_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint admin_notifications_read_by_fkey foreign KEY (read_by) references users (id) on delete set null,
+  constraint admin_notifications_kind_check check ((kind = any (array['low_stock'::text])))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_admin_notifications_created_at on public.admin_notifications using btree (created_at desc) TABLESPACE pg_default;
+
+create index IF not exists idx_admin_notifications_unread on public.admin_notifications using btree (created_at desc) TABLESPACE pg_default
+where
+  (read_at is null);
@@ -0,0 +1,55 @@
+create table public.bouquet_components (
+  id uuid not null default gen_random_uuid (),
+  bouquet_id uuid not null,
+  component_id uuid not null,
+  quantity numeric not null,
+  created_at timestamp with time zone not null default now(),
+  constraint bouquet_components_pkey primary key (id),
+  constraint bouquet_components_bouquet_component_key unique (bouquet_id, component_id),
+  constraint bouquet_components_bouquet_id_fkey foreign KEY (bouquet_id) references products (id) on delete CASCADE,
+  constraint bouquet_components_component_id_fkey foreign KEY (component_id) references products (id),
+  constraint bouquet_components_quantity_check check ((quantity > (0)::numeric)),
+  constraint bouquet_components_not_self_check check ((bouquet_id <> component_id))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_bouquet_components_component_id on public.bouquet_components using btree (component_id) TABLESPACE pg_default;
+
+-- Sellable stock per product. A bouquet with a recipe has no stock of its own: it can be
+-- made as often as its scarcest component allows. Expired units that have not been
+-- written off yet are not sellable and left out.
+create or replace view public.product_stock as
+with
+  stock as (
+    select
+      inv.product_id,
+      inv.quantity_on_hand - coalesce((
+        select sum(sb.quantity_remaining - sb.quantity_reserved)
+        from stock_batches sb
+        where sb.product_id = inv.product_id
+          and sb.expires_on < current_date
+          and sb.quantity_remaining > sb.quantity_reserved
+      ), 0) as quantity_on_hand,
+      inv.quantity_reserved,
+      inv.updated_at
+    from
+      inventory inv
+  )
+select
+  i.product_id,
+  coalesce(b.quantity_on_hand, i.quantity_on_hand) as quantity_on_hand,
+  coalesce(b.quantity_on_hand - b.quantity_available, i.quantity_reserved) as quantity_reserved,
+  coalesce(b.updated_at, i.updated_at) as updated_at
+from
+  stock i
+  left join (
+    select
+      bc.bouquet_id,
+      min(floor(coalesce(c.quantity_on_hand, 0) / bc.quantity)) as quantity_on_hand,
+      min(floor(coalesce(c.quantity_on_hand - c.quantity_reserved, 0) / bc.quantity)) as quantity_available,
+      max(c.updated_at) as updated_at
+    from
+      bouquet_components bc
+      left join stock c on c.product_id = bc.component_id
+    group by
+      bc.bouquet_id
+  ) b on b.bouquet_id = i.product_id;
@@ -0,0 +1,29 @@
+create table public.categories (
+  id uuid not null default gen_random_uuid (),
+  parent_id uuid null,
+  name text not null,
+  description text null,
+  sort_order integer not null default 0,
+  is_active boolean not null default true,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint categories_pkey primary key (id),
+  constraint categories_parent_id_fkey foreign KEY (parent_id) references categories (id),
+  constraint check_not_own_parent check ((parent_id <> id))
+) TABLESPACE pg_default;
+
+-- Names are unique among siblings
+create unique index IF not exists idx_categories_parent_name on public.categories using btree (
+  coalesce(parent_id, '00000000-0000-0000-0000-000000000000'::uuid),
+  lower(name)
+) TABLESPACE pg_default;
+
+create table public.product_categories (
+  product_id uuid not null,
+  category_id uuid not null,
+  constraint product_categories_pkey primary key (product_id, category_id),
+  constraint product_categories_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint product_categories_category_id_fkey foreign KEY (category_id) references categories (id) on delete CASCADE
+) TABLESPACE pg_default;
+
+create index IF not exists idx_product_categories_category_id on public.product_categories using btree (category_id) TABLESPACE pg_default;
@@ -0,0 +1,20 @@
+create table public.email_outbox (
+  id uuid not null default gen_random_uuid (),
+  recipient text not null,
+  subject text not null,
+  body text not null,
+  template text not null,
+  status text not null default 'pending'::text,
+  attempts integer not null default 0,
+  last_error text null,
+  created_at timestamp with time zone not null default now(),
+  sent_at timestamp with time zone null,
+  constraint email_outbox_pkey primary key (id),
+  constraint email_outbox_status_check check (
+    (status = any (array['pending'::text, 'sent'::text, 'failed'::text]))
+  )
+) TABLESPACE pg_default;
+
+create index IF not exists idx_email_outbox_pending on public.email_outbox using btree (created_at) TABLESPACE pg_default
+where
+  (status = 'pending'::text);
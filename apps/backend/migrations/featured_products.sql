@@ -1,7 +1,8 @@
 create table public.featured_products (
   id uuid not null default gen_random_uuid (),
   product_id uuid not null,
-  featured_type character varying(50) null default 'default'::character varying,
+  featured_type character varying(50) null default 'homepage'::character varying,
+  position integer not null default 0,
   start_date timestamp with time zone null,
   end_date timestamp with time zone null,
   created_at timestamp with time zone null default now(),
@@ -10,3 +11,62 @@ create table public.featured_products (
   constraint featured_products_product_id_featured_type_key unique (product_id, featured_type),
   constraint featured_products_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE
 ) TABLESPACE pg_default;
+
+alter table public.featured_products add column if not exists position integer not null default 0;
+
+-- Placements from before featured types were introduced used 'default' (or nothing) and
+-- were shown on the homepage. Keep one per product, preferring an existing homepage one.
+delete from public.featured_products fp
+where (fp.featured_type is null or fp.featured_type = 'default')
+  and exists (
+    select 1 from public.featured_products other
+    where other.product_id = fp.product_id
+      and other.id <> fp.id
+      and (
+        other.featured_type = 'homepage'
+        or (
+          (other.featured_type is null or other.featured_type = 'default')
+          and (coalesce(other.created_at, '-infinity'), other.id)
+            < (coalesce(fp.created_at, '-infinity'), fp.id)
+        )
+      )
+  );
+
+update public.featured_products
+set featured_type = 'homepage'
+where featured_type is null or featured_type = 'default';
+
+update public.featured_products set created_at = now() where created_at is null;
+update public.featured_products set updated_at = created_at where updated_at is null;
+
+alter table public.featured_products
+  alter column featured_type set default 'homepage'::character varying,
+  alter column featured_type set not null,
+  alter column created_at set not null,
+  alter column updated_at set not null;
+
+alter table public.featured_products drop constraint if exists featured_products_featured_type_check;
+alter table public.featured_products add constraint featured_products_featured_type_check check (
+  (
+    (featured_type)::text = any (
+      (
+        array[
+          'homepage'::character varying,
+          'seasonal'::character varying,
+          'banner'::character varying
+        ]
+      )::text[]
+    )
+  )
+);
+
+alter table public.featured_products drop constraint if exists featured_products_window_check;
+alter table public.featured_products add constraint featured_products_window_check check (
+  (
+    (start_date is null)
+    or (end_date is null)
+    or (end_date > start_date)
+  )
+);
+
+create index IF not exists idx_featured_products_type_position on public.featured_products using btree (featured_type, "position") TABLESPACE pg_default;
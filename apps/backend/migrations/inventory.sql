@@ -3,8 +3,18 @@ create table public.inventory (
   quantity_on_hand numeric not null default '0'::numeric,
   quantity_reserved numeric not null default '0'::numeric,
   updated_at timestamp with time zone not null default now(),
+  reorder_threshold numeric null,
+  backorder_mode text null,
+  backorder_limit numeric null,
+  expected_available_on date null,
   constraint inventory_pkey primary key (product_id),
-  constraint inventory_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE
+  constraint inventory_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint inventory_backorder_mode_check check (
+    (
+      backorder_mode = any (array['backorder'::text, 'preorder'::text])
+    )
+  ),
+  constraint inventory_backorder_limit_check check ((backorder_limit >= (0)::numeric))
 ) TABLESPACE pg_default;
 
 create index IF not exists idx_inventory_updated_at on public.inventory using btree (updated_at) TABLESPACE pg_default;
@@ -0,0 +1,10 @@
+create table public.low_stock_alerts (
+  product_id uuid not null,
+  available_quantity numeric not null,
+  reorder_threshold numeric not null,
+  notification_id uuid null,
+  alerted_at timestamp with time zone not null default now(),
+  constraint low_stock_alerts_pkey primary key (product_id),
+  constraint low_stock_alerts_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint low_stock_alerts_notification_id_fkey foreign KEY (notification_id) references admin_notifications (id) on delete set null
+) TABLESPACE pg_default;
@@ -0,0 +1,25 @@
+create table public.occasions (
+  id uuid not null default gen_random_uuid (),
+  name text not null,
+  created_at timestamp with time zone not null default now(),
+  constraint occasions_pkey primary key (id)
+) TABLESPACE pg_default;
+
+create unique index IF not exists idx_occasions_name on public.occasions using btree (lower(name)) TABLESPACE pg_default;
+
+create table public.product_occasions (
+  product_id uuid not null,
+  occasion_id uuid not null,
+  constraint product_occasions_pkey primary key (product_id, occasion_id),
+  constraint product_occasions_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint product_occasions_occasion_id_fkey foreign KEY (occasion_id) references occasions (id) on delete CASCADE
+) TABLESPACE pg_default;
+
+create index IF not exists idx_product_occasions_occasion_id on public.product_occasions using btree (occasion_id) TABLESPACE pg_default;
+
+insert into public.occasions (name)
+values
+  ('Verjaardag'),
+  ('Rouw'),
+  ('Bruiloft'),
+  ('Beterschap');
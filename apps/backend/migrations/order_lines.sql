@@ -6,6 +6,13 @@ create table public.order_line (
   quantity numeric not null,
   unit_price numeric not null,
   discount_amount numeric not null,
+  product_name text null,
+  product_sku text null,
+  product_size text null,
+  product_colors text[] null,
+  product_image_url text null,
+  vat_rate numeric(5, 4) null,
+  applied_promotion_id uuid null,
   constraint order_line_pkey primary key (id),
   constraint order_line_order_id_fkey foreign KEY (order_id) references orders (id) on delete CASCADE,
   constraint order_line_product_id_fkey foreign KEY (product_id) references products (id)
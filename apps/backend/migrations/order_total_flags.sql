@@ -0,0 +1,18 @@
+create table public.order_total_flags (
+  id uuid not null default gen_random_uuid (),
+  order_id uuid not null,
+  mismatches jsonb not null,
+  detected_at timestamp with time zone not null default now(),
+  last_checked_at timestamp with time zone not null default now(),
+  reviewed_at timestamp with time zone null,
+  reviewed_by uuid null,
+  review_note text null,
+  constraint order_total_flags_pkey primary key (id),
+  constraint order_total_flags_order_id_key unique (order_id),
+  constraint order_total_flags_order_id_fkey foreign KEY (order_id) references orders (id) on delete CASCADE,
+  constraint order_total_flags_reviewed_by_fkey foreign KEY (reviewed_by) references users (id) on delete set null
+) TABLESPACE pg_default;
+
+create index IF not exists idx_order_total_flags_open on public.order_total_flags using btree (detected_at desc) TABLESPACE pg_default
+where
+  (reviewed_at is null);
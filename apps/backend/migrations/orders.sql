@@ -13,8 +13,15 @@ create table public.orders (
   notes text null,
   shipping_address jsonb not null,
   billing_address jsonb not null,
+  delivery_date date null,
+  awaiting_stock boolean not null default false,
+  expected_ship_date date null,
+  pickup_location_id uuid null,
+  stock_location_id uuid null,
   constraint orders_pkey primary key (id),
-  constraint orders_user_id_fkey foreign KEY (user_id) references users (id)
+  constraint orders_user_id_fkey foreign KEY (user_id) references users (id),
+  constraint orders_pickup_location_id_fkey foreign KEY (pickup_location_id) references stock_locations (id),
+  constraint orders_stock_location_id_fkey foreign KEY (stock_location_id) references stock_locations (id)
 ) TABLESPACE pg_default;
 
 create index IF not exists idx_orders_created_at on public.orders using btree (created_at desc) TABLESPACE pg_default;
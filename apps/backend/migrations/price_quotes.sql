@@ -0,0 +1,22 @@
+create table public.price_quotes (
+  id uuid not null default gen_random_uuid (),
+  user_id uuid not null,
+  products jsonb not null,
+  subtotal_before_discount numeric not null,
+  total_discount_amount numeric not null,
+  final_total numeric not null,
+  total_with_tax numeric not null,
+  expires_at timestamp with time zone not null,
+  created_at timestamp with time zone not null default now(),
+  used_at timestamp with time zone null,
+  order_id uuid null,
+  constraint price_quotes_pkey primary key (id),
+  constraint price_quotes_user_id_fkey foreign KEY (user_id) references users (id) on delete CASCADE,
+  constraint price_quotes_order_id_fkey foreign KEY (order_id) references orders (id) on delete set null
+) TABLESPACE pg_default;
+
+create index IF not exists idx_price_quotes_user_id on public.price_quotes using btree (user_id) TABLESPACE pg_default;
+
+create index IF not exists idx_price_quotes_expires_at on public.price_quotes using btree (expires_at) TABLESPACE pg_default
+where
+  (used_at is null);
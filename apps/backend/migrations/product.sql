@@ -12,7 +12,11 @@ create table public.products (
   product_type text not null default ''::text,
   tax numeric(10, 2) not null default 0.00,
   subtotal numeric(10, 2) not null default 0.00,
+  parent_id uuid null,
+  slug text null,
   constraint products_pkey primary key (id),
+  constraint products_parent_id_fkey foreign KEY (parent_id) references products (id),
+  constraint check_not_own_parent check ((parent_id <> id)),
   constraint check_price_equals_subtotal_plus_tax check ((abs((price - (subtotal + tax))) < 0.01))
 ) TABLESPACE pg_default;
 
@@ -38,6 +42,67 @@ create index IF not exists idx_products_active_only on public.products using btr
 where
   (is_active = true);
 
+create index IF not exists idx_products_parent_id on public.products using btree (parent_id) TABLESPACE pg_default
+where
+  (parent_id is not null);
+
 create index IF not exists idx_products_tax on public.products using btree (tax) TABLESPACE pg_default;
 
 create index IF not exists idx_products_subtotal on public.products using btree (subtotal) TABLESPACE pg_default;
+
+create unique index IF not exists idx_products_sku_unique on public.products using btree (lower(sku)) TABLESPACE pg_default;
+
+-- Products added before slugs existed get one from their name, folded the same way as
+-- utils::slug and numbered -2, -3, ... when taken; only then is the column required
+alter table public.products add column if not exists slug text null;
+
+do $$
+declare
+  product record;
+  base text;
+  candidate text;
+  n integer;
+begin
+  for product in
+    select id, name from public.products where slug is null order by created_at asc, id asc
+  loop
+    base := lower(product.name);
+    base := replace(replace(replace(replace(base, 'æ', 'ae'), 'ĳ', 'ij'), 'œ', 'oe'), 'ß', 'ss');
+    base := replace(base, '&', 'en');
+    base := translate(base, 'àáâãäåçèéêëìíîïñòóôõöøùúûüýÿ', 'aaaaaaceeeeiiiinoooooouuuuyy');
+    base := trim(both '-' from regexp_replace(base, '[^a-z0-9]+', '-', 'g'));
+    if length(base) > 80 then
+      base := left(base, 80);
+      if position('-' in base) > 0 then
+        base := regexp_replace(base, '-[^-]*$', '');
+      end if;
+    end if;
+    if base = '' then
+      base := 'product';
+    end if;
+
+    candidate := base;
+    n := 1;
+    while exists (select 1 from public.products where slug = candidate) loop
+      n := n + 1;
+      candidate := base || '-' || n;
+    end loop;
+
+    update public.products set slug = candidate where id = product.id;
+  end loop;
+end $$;
+
+alter table public.products alter column slug set not null;
+
+create unique index IF not exists idx_products_slug on public.products using btree (slug) TABLESPACE pg_default;
+
+-- Slugs a product was reachable under before it was renamed
+create table public.product_slug_redirects (
+  slug text not null,
+  product_id uuid not null,
+  created_at timestamp with time zone not null default now(),
+  constraint product_slug_redirects_pkey primary key (slug),
+  constraint product_slug_redirects_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE
+) TABLESPACE pg_default;
+
+create index IF not exists idx_product_slug_redirects_product_id on public.product_slug_redirects using btree (product_id) TABLESPACE pg_default;
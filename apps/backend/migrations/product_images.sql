@@ -4,6 +4,10 @@ create table public.product_images (
   alt_text text not null,
   is_primary boolean not null default false,
   id uuid not null default gen_random_uuid (),
+  sort_order integer not null default 0,
+  renditions jsonb not null default '[]'::jsonb,
+  storage_keys text[] not null default '{}'::text[],
+  created_at timestamp with time zone not null default now(),
   constraint product_images_pkey primary key (id),
   constraint product_images_id_key unique (id),
   constraint product_images_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE
@@ -14,3 +18,38 @@ create index IF not exists idx_product_images_product_primary on public.product_
 create index IF not exists idx_product_images_product_id on public.product_images using btree (product_id) TABLESPACE pg_default;
 
 create index IF not exists idx_fk_product_images_product_id on public.product_images using btree (product_id) TABLESPACE pg_default;
+
+create index IF not exists idx_product_images_product_sort on public.product_images using btree (product_id, sort_order) TABLESPACE pg_default;
+
+alter table public.product_images
+  add column if not exists sort_order integer not null default 0,
+  add column if not exists renditions jsonb not null default '[]'::jsonb,
+  add column if not exists storage_keys text[] not null default '{}'::text[],
+  add column if not exists created_at timestamp with time zone not null default now();
+
+-- Keep only the first primary image of products that have several
+update public.product_images pi
+set is_primary = false
+from (
+  select id, row_number() over (partition by product_id order by sort_order, created_at, id) as rank
+  from public.product_images
+  where is_primary
+) ranked
+where ranked.id = pi.id
+  and ranked.rank > 1;
+
+-- Products with images but no primary one get their first image as primary
+update public.product_images pi
+set is_primary = true
+from (
+  select distinct on (product_id) id
+  from public.product_images
+  where product_id not in (select product_id from public.product_images where is_primary)
+  order by product_id, sort_order, created_at, id
+) first_image
+where first_image.id = pi.id;
+
+-- At most one primary image per product; the service keeps it at exactly one
+create unique index IF not exists idx_product_images_one_primary on public.product_images using btree (product_id) TABLESPACE pg_default
+where
+  is_primary;
@@ -0,0 +1,60 @@
+create table public.scheduled_price_changes (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  new_price numeric not null,
+  effective_at timestamp with time zone not null,
+  status text not null default 'pending'::text,
+  created_by uuid null,
+  created_at timestamp with time zone not null default now(),
+  applied_at timestamp with time zone null,
+  cancelled_at timestamp with time zone null,
+  constraint scheduled_price_changes_pkey primary key (id),
+  constraint scheduled_price_changes_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint scheduled_price_changes_created_by_fkey foreign KEY (created_by) references users (id) on delete set null,
+  constraint scheduled_price_changes_new_price_check check ((new_price > (0)::numeric)),
+  constraint scheduled_price_changes_status_check check (
+    (
+      status = any (
+        array[
+          'pending'::text,
+          'applied'::text,
+          'cancelled'::text
+        ]
+      )
+    )
+  )
+) TABLESPACE pg_default;
+
+create index IF not exists idx_scheduled_price_changes_due on public.scheduled_price_changes using btree (effective_at) TABLESPACE pg_default
+where
+  (status = 'pending'::text);
+
+create index IF not exists idx_scheduled_price_changes_product on public.scheduled_price_changes using btree (product_id, effective_at) TABLESPACE pg_default;
+
+create table public.product_price_history (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  old_price numeric null,
+  new_price numeric not null,
+  source text not null,
+  changed_by uuid null,
+  scheduled_change_id uuid null,
+  changed_at timestamp with time zone not null default now(),
+  constraint product_price_history_pkey primary key (id),
+  constraint product_price_history_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint product_price_history_changed_by_fkey foreign KEY (changed_by) references users (id) on delete set null,
+  constraint product_price_history_scheduled_change_id_fkey foreign KEY (scheduled_change_id) references scheduled_price_changes (id) on delete set null,
+  constraint product_price_history_source_check check (
+    (
+      source = any (
+        array[
+          'created'::text,
+          'manual'::text,
+          'scheduled'::text
+        ]
+      )
+    )
+  )
+) TABLESPACE pg_default;
+
+create index IF not exists idx_product_price_history_product on public.product_price_history using btree (product_id, changed_at desc) TABLESPACE pg_default;
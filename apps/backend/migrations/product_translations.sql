@@ -0,0 +1,13 @@
+create table public.product_translations (
+  product_id uuid not null,
+  locale text not null,
+  name text not null,
+  description text not null,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint product_translations_pkey primary key (product_id, locale),
+  constraint product_translations_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint product_translations_locale_check check ((locale = 'en'::text))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_product_translations_locale on public.product_translations using btree (locale, product_id) TABLESPACE pg_default;
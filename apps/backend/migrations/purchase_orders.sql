@@ -0,0 +1,56 @@
+create table public.purchase_orders (
+  id uuid not null default gen_random_uuid (),
+  reference text not null,
+  supplier_id uuid not null,
+  status text not null default 'draft'::text,
+  expected_on date null,
+  notes text null,
+  created_by uuid null,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  sent_at timestamp with time zone null,
+  received_at timestamp with time zone null,
+  constraint purchase_orders_pkey primary key (id),
+  constraint purchase_orders_reference_key unique (reference),
+  constraint purchase_orders_supplier_id_fkey foreign KEY (supplier_id) references suppliers (id),
+  constraint purchase_orders_created_by_fkey foreign KEY (created_by) references users (id) on delete set null,
+  constraint purchase_orders_status_check check (
+    (
+      status = any (
+        array[
+          'draft'::text,
+          'sent'::text,
+          'partially_received'::text,
+          'received'::text
+        ]
+      )
+    )
+  )
+) TABLESPACE pg_default;
+
+create index IF not exists idx_purchase_orders_status on public.purchase_orders using btree (status, created_at desc) TABLESPACE pg_default;
+
+create index IF not exists idx_purchase_orders_supplier_id on public.purchase_orders using btree (supplier_id) TABLESPACE pg_default;
+
+create table public.purchase_order_lines (
+  id uuid not null default gen_random_uuid (),
+  purchase_order_id uuid not null,
+  product_id uuid not null,
+  quantity numeric not null,
+  quantity_received numeric not null default '0'::numeric,
+  cost_price numeric(10, 2) not null,
+  constraint purchase_order_lines_pkey primary key (id),
+  constraint purchase_order_lines_order_product_key unique (purchase_order_id, product_id),
+  constraint purchase_order_lines_purchase_order_id_fkey foreign KEY (purchase_order_id) references purchase_orders (id) on delete CASCADE,
+  constraint purchase_order_lines_product_id_fkey foreign KEY (product_id) references products (id),
+  constraint purchase_order_lines_quantity_check check ((quantity > (0)::numeric)),
+  constraint purchase_order_lines_received_check check (
+    (
+      (quantity_received >= (0)::numeric)
+      and (quantity_received <= quantity)
+    )
+  ),
+  constraint purchase_order_lines_cost_price_check check ((cost_price >= (0)::numeric))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_purchase_order_lines_product_id on public.purchase_order_lines using btree (product_id) TABLESPACE pg_default;
@@ -0,0 +1,13 @@
+create table public.related_products (
+  product_id uuid not null,
+  related_product_id uuid not null,
+  score integer not null,
+  rank integer not null,
+  computed_at timestamp with time zone not null default now(),
+  constraint related_products_pkey primary key (product_id, related_product_id),
+  constraint related_products_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint related_products_related_product_id_fkey foreign KEY (related_product_id) references products (id) on delete CASCADE,
+  constraint check_not_related_to_self check ((product_id <> related_product_id))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_related_products_rank on public.related_products using btree (product_id, rank) TABLESPACE pg_default;
@@ -0,0 +1,59 @@
+create table public.reviews (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  user_id uuid not null,
+  order_id uuid null,
+  rating smallint not null,
+  title text null,
+  body text not null,
+  status text not null default 'pending'::text,
+  moderation_note text null,
+  moderated_by uuid null,
+  moderated_at timestamp with time zone null,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint reviews_pkey primary key (id),
+  constraint reviews_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint reviews_user_id_fkey foreign KEY (user_id) references users (id) on delete CASCADE,
+  constraint reviews_order_id_fkey foreign KEY (order_id) references orders (id) on delete set null,
+  constraint reviews_moderated_by_fkey foreign KEY (moderated_by) references users (id) on delete set null,
+  constraint reviews_rating_check check (
+    (
+      (rating >= 1)
+      and (rating <= 5)
+    )
+  ),
+  constraint reviews_status_check check (
+    (
+      status = any (
+        array[
+          'pending'::text,
+          'approved'::text,
+          'rejected'::text
+        ]
+      )
+    )
+  )
+) TABLESPACE pg_default;
+
+-- One review per customer per product family; variants are reviewed through their parent
+create unique INDEX IF not exists idx_reviews_product_user on public.reviews using btree (product_id, user_id) TABLESPACE pg_default;
+
+create index IF not exists idx_reviews_product_status on public.reviews using btree (product_id, status, created_at desc) TABLESPACE pg_default;
+
+create index IF not exists idx_reviews_pending on public.reviews using btree (created_at) TABLESPACE pg_default
+where
+  (status = 'pending'::text);
+
+-- Rating shown on products, over approved reviews only
+create or replace view public.product_review_stats as
+select
+  product_id,
+  round(avg(rating), 2) as average_rating,
+  count(*) as review_count
+from
+  public.reviews
+where
+  status = 'approved'::text
+group by
+  product_id;
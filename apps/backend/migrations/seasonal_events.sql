@@ -0,0 +1,26 @@
+create table public.seasonal_events (
+  id uuid not null default gen_random_uuid (),
+  name text not null,
+  starts_on date not null,
+  ends_on date not null,
+  multiplier numeric(5, 2) not null,
+  created_at timestamp with time zone not null default now(),
+  constraint seasonal_events_pkey primary key (id),
+  constraint seasonal_events_dates_check check (ends_on >= starts_on),
+  constraint seasonal_events_multiplier_check check (multiplier > (0)::numeric)
+) TABLESPACE pg_default;
+
+create index IF not exists idx_seasonal_events_dates on public.seasonal_events using btree (starts_on, ends_on) TABLESPACE pg_default;
+
+-- Most flowers are ordered in the days leading up to these events
+insert into public.seasonal_events (name, starts_on, ends_on, multiplier)
+values
+  ('Valentijnsdag', '2025-02-12', '2025-02-14', 3.00),
+  ('Moederdag', '2025-05-08', '2025-05-11', 2.50),
+  ('Kerst', '2025-12-19', '2025-12-24', 1.80),
+  ('Valentijnsdag', '2026-02-12', '2026-02-14', 3.00),
+  ('Moederdag', '2026-05-07', '2026-05-10', 2.50),
+  ('Kerst', '2026-12-19', '2026-12-24', 1.80),
+  ('Valentijnsdag', '2027-02-12', '2027-02-14', 3.00),
+  ('Moederdag', '2027-05-06', '2027-05-09', 2.50),
+  ('Kerst', '2027-12-19', '2027-12-24', 1.80);
@@ -0,0 +1,48 @@
+create table public.stock_batches (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  location_id uuid null,
+  quantity_received numeric not null,
+  quantity_remaining numeric not null,
+  quantity_reserved numeric not null default '0'::numeric,
+  received_at timestamp with time zone not null default now(),
+  expires_on date null,
+  written_off_at timestamp with time zone null,
+  note text null,
+  constraint stock_batches_pkey primary key (id),
+  constraint stock_batches_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint stock_batches_location_id_fkey foreign KEY (location_id) references stock_locations (id),
+  constraint stock_batches_quantities_check check (
+    (
+      (quantity_reserved >= (0)::numeric)
+      and (quantity_remaining >= quantity_reserved)
+    )
+  )
+) TABLESPACE pg_default;
+
+-- First-expiring-first allocation; batches without an expiry date go last
+create index IF not exists idx_stock_batches_fefo on public.stock_batches using btree (location_id, product_id, expires_on nulls last, received_at) TABLESPACE pg_default
+where
+  (quantity_remaining > (0)::numeric);
+
+create index IF not exists idx_stock_batches_expires_on on public.stock_batches using btree (expires_on) TABLESPACE pg_default
+where
+  (quantity_remaining > (0)::numeric);
+
+-- Stock that existed before batches were introduced has no known expiry date
+insert into public.stock_batches (
+  product_id, quantity_received, quantity_remaining, quantity_reserved, note
+)
+select
+  i.product_id,
+  i.quantity_on_hand,
+  i.quantity_on_hand,
+  least(i.quantity_reserved, i.quantity_on_hand),
+  'Stock at the time batches were introduced'
+from
+  public.inventory i
+where
+  i.quantity_on_hand > 0
+  and not exists (
+    select 1 from public.stock_batches b where b.product_id = i.product_id
+  );
@@ -0,0 +1,59 @@
+create table public.stock_locations (
+  id uuid not null default gen_random_uuid (),
+  name text not null,
+  is_pickup_point boolean not null default false,
+  delivery_postal_prefixes text[] not null default '{}'::text[],
+  priority integer not null default 0,
+  is_active boolean not null default true,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint stock_locations_pkey primary key (id)
+) TABLESPACE pg_default;
+
+create unique index IF not exists idx_stock_locations_name on public.stock_locations using btree (lower(name)) TABLESPACE pg_default;
+
+-- Stock of a product at one location. The inventory row of a product holds the sum over
+-- all locations.
+create table public.location_inventory (
+  location_id uuid not null,
+  product_id uuid not null,
+  quantity_on_hand numeric not null default '0'::numeric,
+  quantity_reserved numeric not null default '0'::numeric,
+  updated_at timestamp with time zone not null default now(),
+  constraint location_inventory_pkey primary key (location_id, product_id),
+  constraint location_inventory_location_id_fkey foreign KEY (location_id) references stock_locations (id),
+  constraint location_inventory_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE
+) TABLESPACE pg_default;
+
+create index IF not exists idx_location_inventory_product_id on public.location_inventory using btree (product_id) TABLESPACE pg_default;
+
+-- All stock that existed before locations were introduced is in the shop
+insert into public.stock_locations (name, is_pickup_point, priority)
+select 'Winkel', true, 0
+where not exists (select 1 from public.stock_locations);
+
+insert into public.location_inventory (location_id, product_id, quantity_on_hand, quantity_reserved)
+select
+  l.id,
+  i.product_id,
+  i.quantity_on_hand,
+  i.quantity_reserved
+from
+  public.inventory i
+  cross join (
+    select id from public.stock_locations order by priority asc, created_at asc limit 1
+  ) l
+on conflict (location_id, product_id) do nothing;
+
+update public.stock_batches
+set location_id = (
+  select id from public.stock_locations order by priority asc, created_at asc limit 1
+)
+where location_id is null;
+
+update public.orders
+set stock_location_id = (
+  select id from public.stock_locations order by priority asc, created_at asc limit 1
+)
+where stock_location_id is null
+and status in ('pending', 'processing');
@@ -0,0 +1,67 @@
+create table public.stock_movements (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  location_id uuid null,
+  on_hand_change numeric not null default '0'::numeric,
+  reserved_change numeric not null default '0'::numeric,
+  reason text not null,
+  order_id uuid null,
+  user_id uuid null,
+  note text null,
+  created_at timestamp with time zone not null default now(),
+  constraint stock_movements_pkey primary key (id),
+  constraint stock_movements_product_id_fkey foreign KEY (product_id) references products (id),
+  constraint stock_movements_location_id_fkey foreign KEY (location_id) references stock_locations (id),
+  constraint stock_movements_user_id_fkey foreign KEY (user_id) references users (id) on delete set null,
+  constraint stock_movements_reason_check check (
+    (
+      reason = any (
+        array[
+          'opening_balance'::text,
+          'initial_stock'::text,
+          'restock'::text,
+          'reservation'::text,
+          'reservation_release'::text,
+          'fulfillment'::text,
+          'adjustment'::text,
+          'stock_count'::text,
+          'write_off'::text,
+          'transfer'::text
+        ]
+      )
+    )
+  )
+) TABLESPACE pg_default;
+
+create index IF not exists idx_stock_movements_product_created on public.stock_movements using btree (product_id, created_at desc) TABLESPACE pg_default;
+
+create index IF not exists idx_stock_movements_order_id on public.stock_movements using btree (order_id) TABLESPACE pg_default
+where
+  (order_id is not null);
+
+-- The ledger is append-only: corrections are recorded as new movements
+create or replace function prevent_stock_movement_changes () RETURNS trigger as $$
+begin
+  raise exception 'stock_movements is append-only';
+end;
+$$ LANGUAGE plpgsql;
+
+create trigger trigger_stock_movements_append_only BEFORE
+update
+or delete on stock_movements for EACH row
+execute FUNCTION prevent_stock_movement_changes ();
+
+-- Stock that existed before the ledger was introduced
+insert into public.stock_movements (product_id, on_hand_change, reserved_change, reason, note)
+select
+  i.product_id,
+  i.quantity_on_hand,
+  i.quantity_reserved,
+  'opening_balance',
+  'Stock at the time the ledger was introduced'
+from
+  public.inventory i
+where
+  not exists (
+    select 1 from public.stock_movements m where m.product_id = i.product_id
+  );
@@ -0,0 +1,20 @@
+create table public.stock_notifications (
+  id uuid not null default gen_random_uuid (),
+  product_id uuid not null,
+  user_id uuid null,
+  email text not null,
+  verification_token text not null,
+  verified_at timestamp with time zone null,
+  verification_sent_at timestamp with time zone null,
+  created_at timestamp with time zone not null default now(),
+  notified_at timestamp with time zone null,
+  constraint stock_notifications_pkey primary key (id),
+  constraint stock_notifications_verification_token_key unique (verification_token),
+  constraint stock_notifications_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint stock_notifications_user_id_fkey foreign KEY (user_id) references users (id) on delete CASCADE
+) TABLESPACE pg_default;
+
+-- One open subscription per address and product
+create unique index IF not exists idx_stock_notifications_open on public.stock_notifications using btree (product_id, lower(email)) TABLESPACE pg_default
+where
+  (notified_at is null);
@@ -0,0 +1,14 @@
+create table public.suppliers (
+  id uuid not null default gen_random_uuid (),
+  name text not null,
+  contact_name text null,
+  email text null,
+  phone text null,
+  notes text null,
+  is_active boolean not null default true,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint suppliers_pkey primary key (id)
+) TABLESPACE pg_default;
+
+create unique index IF not exists idx_suppliers_name on public.suppliers using btree (lower(name)) TABLESPACE pg_default;
@@ -12,6 +12,7 @@ create table public.users (
   first_name text not null default ''::text,
   last_name text not null default ''::text,
   preposition text null,
+  cart_reminders_opt_out boolean not null default false,
   constraint users_pkey primary key (id),
   constraint users_email_key unique (email),
   constraint email_format check (
@@ -0,0 +1,17 @@
+create table public.wishlist_items (
+  id uuid not null default gen_random_uuid (),
+  wishlist_id uuid not null,
+  product_id uuid not null,
+  quantity integer not null default 1,
+  saved_price numeric not null,
+  created_at timestamp with time zone not null default now(),
+  constraint wishlist_items_pkey primary key (id),
+  constraint wishlist_items_wishlist_product_key unique (wishlist_id, product_id),
+  constraint wishlist_items_wishlist_id_fkey foreign KEY (wishlist_id) references wishlists (id) on delete CASCADE,
+  constraint wishlist_items_product_id_fkey foreign KEY (product_id) references products (id) on delete CASCADE,
+  constraint wishlist_items_quantity_check check ((quantity > 0))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_wishlist_items_wishlist_id on public.wishlist_items using btree (wishlist_id) TABLESPACE pg_default;
+
+create index IF not exists idx_wishlist_items_product_id on public.wishlist_items using btree (product_id) TABLESPACE pg_default;
@@ -0,0 +1,20 @@
+create table public.wishlists (
+  id uuid not null default gen_random_uuid (),
+  user_id uuid not null,
+  name text not null,
+  occasion_date date null,
+  share_token text null,
+  created_at timestamp with time zone not null default now(),
+  updated_at timestamp with time zone not null default now(),
+  constraint wishlists_pkey primary key (id),
+  constraint wishlists_user_id_name_key unique (user_id, name),
+  constraint wishlists_share_token_key unique (share_token),
+  constraint wishlists_user_id_fkey foreign KEY (user_id) references users (id) on delete CASCADE,
+  constraint wishlists_name_check check ((length(trim(both from name)) > 0))
+) TABLESPACE pg_default;
+
+create index IF not exists idx_wishlists_user_id on public.wishlists using btree (user_id) TABLESPACE pg_default;
+
+create trigger trigger_wishlists_updated_at BEFORE
+update on wishlists for EACH row
+execute FUNCTION update_updated_at_column ();
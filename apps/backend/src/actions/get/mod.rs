@@ -8,8 +8,10 @@ pub use discount::{
     get_active_discounts_for_products, get_all_active_discounts, get_all_discounts,
     get_best_discount_for_product, get_discount_by_id, get_upcoming_discounts,
 };
-pub use order::{get_all_orders, get_order_by_id, get_order_by_id_and_user, get_orders_by_user};
-pub use order_line::get_order_lines;
+pub use order::{
+    get_all_orders, get_order_by_id, get_order_by_id_and_user, get_orders_by_user, get_orders_page,
+};
+pub use order_line::{get_order_lines, get_order_lines_for_orders};
 pub use product::{get_all_featured_products, get_all_products, get_product_by_id};
 pub use search::{
     get_popular_searches, get_search_suggestions, search_products, search_with_corrections,
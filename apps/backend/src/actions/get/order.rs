@@ -1,5 +1,6 @@
 use crate::pool::connect::pool;
 use crate::structs::order::Order;
+use chrono::{DateTime, Utc};
 use sqlx::{Error as SqlxError, Row};
 use uuid::Uuid;
 
@@ -21,6 +22,11 @@ pub async fn get_all_orders() -> Result<Vec<Order>, SqlxError> {
             notes,
             shipping_address,
             billing_address,
+            delivery_date,
+            awaiting_stock,
+            expected_ship_date,
+            pickup_location_id,
+            stock_location_id,
             created_at,
             updated_at
         FROM orders
@@ -49,6 +55,84 @@ pub async fn get_all_orders() -> Result<Vec<Order>, SqlxError> {
                 .map_err(|e| SqlxError::Decode(Box::new(e)))?,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            delivery_date: row.get("delivery_date"),
+            awaiting_stock: row.get("awaiting_stock"),
+            expected_ship_date: row.get("expected_ship_date"),
+            pickup_location_id: row.get("pickup_location_id"),
+            stock_location_id: row.get("stock_location_id"),
+        };
+        orders.push(order);
+    }
+
+    Ok(orders)
+}
+
+/// A page of orders, oldest first, starting after the given (created_at, id) position.
+/// Used to walk through all orders in batches.
+pub async fn get_orders_page(
+    after: Option<(DateTime<Utc>, Uuid)>,
+    limit: i64,
+) -> Result<Vec<Order>, SqlxError> {
+    let pool = pool();
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            id,
+            user_id,
+            order_number,
+            status,
+            subtotal,
+            tax_amount,
+            shipping_cost,
+            discount_amount,
+            total_amount,
+            notes,
+            shipping_address,
+            billing_address,
+            delivery_date,
+            awaiting_stock,
+            expected_ship_date,
+            pickup_location_id,
+            stock_location_id,
+            created_at,
+            updated_at
+        FROM orders
+        WHERE $1::timestamptz IS NULL OR (created_at, id) > ($1, $2)
+        ORDER BY created_at ASC, id ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(after.map(|(created_at, _)| created_at))
+    .bind(after.map(|(_, id)| id))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    let mut orders = Vec::new();
+    for row in rows {
+        let order = Order {
+            id: row.try_get("id")?,
+            user_id: row.get("user_id"),
+            order_number: row.get("order_number"),
+            status: row.get("status"),
+            subtotal: row.get("subtotal"),
+            tax_amount: row.get("tax_amount"),
+            shipping_cost: row.get("shipping_cost"),
+            discount_amount: row.get("discount_amount"),
+            total_amount: row.get("total_amount"),
+            notes: row.get("notes"),
+            shipping_address: serde_json::from_value(row.try_get("shipping_address")?)
+                .map_err(|e| SqlxError::Decode(Box::new(e)))?,
+            billing_address: serde_json::from_value(row.try_get("billing_address")?)
+                .map_err(|e| SqlxError::Decode(Box::new(e)))?,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            delivery_date: row.get("delivery_date"),
+            awaiting_stock: row.get("awaiting_stock"),
+            expected_ship_date: row.get("expected_ship_date"),
+            pickup_location_id: row.get("pickup_location_id"),
+            stock_location_id: row.get("stock_location_id"),
         };
         orders.push(order);
     }
@@ -74,6 +158,11 @@ pub async fn get_orders_by_user(user_id: Uuid) -> Result<Vec<Order>, SqlxError>
             notes,
             shipping_address,
             billing_address,
+            delivery_date,
+            awaiting_stock,
+            expected_ship_date,
+            pickup_location_id,
+            stock_location_id,
             created_at,
             updated_at
         FROM orders
@@ -104,6 +193,11 @@ pub async fn get_orders_by_user(user_id: Uuid) -> Result<Vec<Order>, SqlxError>
                 .map_err(|e| SqlxError::Decode(Box::new(e)))?,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            delivery_date: row.get("delivery_date"),
+            awaiting_stock: row.get("awaiting_stock"),
+            expected_ship_date: row.get("expected_ship_date"),
+            pickup_location_id: row.get("pickup_location_id"),
+            stock_location_id: row.get("stock_location_id"),
         };
         orders.push(order);
     }
@@ -129,6 +223,11 @@ pub async fn get_order_by_id(id: Uuid) -> Result<Option<Order>, SqlxError> {
             notes,
             shipping_address,
             billing_address,
+            delivery_date,
+            awaiting_stock,
+            expected_ship_date,
+            pickup_location_id,
+            stock_location_id,
             created_at,
             updated_at
         FROM orders
@@ -157,6 +256,11 @@ pub async fn get_order_by_id(id: Uuid) -> Result<Option<Order>, SqlxError> {
                 .map_err(|e| SqlxError::Decode(Box::new(e)))?,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            delivery_date: row.get("delivery_date"),
+            awaiting_stock: row.get("awaiting_stock"),
+            expected_ship_date: row.get("expected_ship_date"),
+            pickup_location_id: row.get("pickup_location_id"),
+            stock_location_id: row.get("stock_location_id"),
         };
         Ok(Some(order))
     } else {
@@ -185,6 +289,11 @@ pub async fn get_order_by_id_and_user(
             notes,
             shipping_address,
             billing_address,
+            delivery_date,
+            awaiting_stock,
+            expected_ship_date,
+            pickup_location_id,
+            stock_location_id,
             created_at,
             updated_at
         FROM orders
@@ -214,6 +323,11 @@ pub async fn get_order_by_id_and_user(
                 .map_err(|e| SqlxError::Decode(Box::new(e)))?,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            delivery_date: row.get("delivery_date"),
+            awaiting_stock: row.get("awaiting_stock"),
+            expected_ship_date: row.get("expected_ship_date"),
+            pickup_location_id: row.get("pickup_location_id"),
+            stock_location_id: row.get("stock_location_id"),
         };
         Ok(Some(order))
     } else {
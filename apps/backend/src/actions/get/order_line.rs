@@ -1,36 +1,55 @@
 use crate::pool::connect::pool;
 use crate::structs::order::OrderLine;
-use sqlx::{Error as SqlxError, Row};
+use crate::utils::tax::Tax;
+use sqlx::Error as SqlxError;
 use uuid::Uuid;
 
+/// Order line columns. Product details come from the snapshot taken at order time;
+/// lines placed before snapshots were recorded fall back to the current product.
+const ORDER_LINE_SELECT: &str = r#"
+        SELECT
+            ol.id, ol.created_at, ol.order_id, ol.product_id, ol.quantity, ol.unit_price,
+            ol.discount_amount,
+            COALESCE(ol.product_name, p.name) AS product_name,
+            COALESCE(ol.product_sku, p.sku) AS product_sku,
+            COALESCE(ol.product_size, NULLIF(p.size, '')) AS product_size,
+            COALESCE(ol.product_colors, p.colors) AS product_colors,
+            COALESCE(ol.product_image_url, (
+                SELECT pi.url FROM product_images pi
+                WHERE pi.product_id = ol.product_id
+                ORDER BY pi.is_primary DESC
+                LIMIT 1
+            )) AS product_image_url,
+            COALESCE(ol.vat_rate, $2) AS vat_rate,
+            ol.applied_promotion_id
+        FROM order_line ol
+        LEFT JOIN products p ON p.id = ol.product_id
+"#;
+
 /// Get order lines for a specific order
 pub async fn get_order_lines(order_id: Uuid) -> Result<Vec<OrderLine>, SqlxError> {
     let pool = pool();
 
-    let rows = sqlx::query(
-        r#"
-        SELECT id, created_at, order_id, product_id, quantity, unit_price, discount_amount
-        FROM order_line
-        WHERE order_id = $1
-        ORDER BY created_at ASC
-        "#,
-    )
+    sqlx::query_as::<_, OrderLine>(&format!(
+        "{} WHERE ol.order_id = $1 ORDER BY ol.created_at ASC",
+        ORDER_LINE_SELECT
+    ))
     .bind(order_id)
+    .bind(Tax::RATE)
     .fetch_all(pool)
-    .await?;
+    .await
+}
 
-    let order_lines = rows
-        .into_iter()
-        .map(|row| OrderLine {
-            id: row.get("id"),
-            created_at: row.get("created_at"),
-            order_id: row.get("order_id"),
-            product_id: row.get("product_id"),
-            quantity: row.get("quantity"),
-            unit_price: row.get("unit_price"),
-            discount_amount: row.get("discount_amount"),
-        })
-        .collect();
+/// Get the order lines of several orders at once
+pub async fn get_order_lines_for_orders(order_ids: &[Uuid]) -> Result<Vec<OrderLine>, SqlxError> {
+    let pool = pool();
 
-    Ok(order_lines)
+    sqlx::query_as::<_, OrderLine>(&format!(
+        "{} WHERE ol.order_id = ANY($1) ORDER BY ol.order_id, ol.created_at ASC",
+        ORDER_LINE_SELECT
+    ))
+    .bind(order_ids)
+    .bind(Tax::RATE)
+    .fetch_all(pool)
+    .await
 }
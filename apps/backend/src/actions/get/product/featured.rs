@@ -1,11 +1,21 @@
+use crate::actions::get::product::attach_variants;
 use crate::pool::connect::pool;
+use crate::structs::featured::FeaturedType;
 use crate::structs::product::{Product, ProductImage};
 use rust_decimal::Decimal;
 use sqlx::{Error as SqlxError, Row};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
+/// How many featured products are shown at most
+const MAX_FEATURED: i64 = 8;
+
+/// Active products placed in a featured spot whose schedule includes now, by position.
+/// Without a type, placements of every type count and a product is shown once. Variants
+/// are never listed on their own, only with their parent.
+pub async fn get_all_featured_products(
+    featured_type: Option<FeaturedType>,
+) -> Result<Vec<Product>, SqlxError> {
     let pool = pool();
 
     // Fetch products with images, inventory, and highest active discount
@@ -25,10 +35,17 @@ pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
             p.colors,
             p.size,
             p.product_type,
+            p.parent_id,
+            p.slug,
+            rs.average_rating,
+            COALESCE(rs.review_count, 0) AS review_count,
             pi.product_id AS image_product_id,
             pi.url,
             pi.alt_text,
             pi.is_primary,
+            pi.id AS image_id,
+            pi.sort_order AS image_sort_order,
+            pi.renditions AS image_renditions,
             i.quantity_on_hand,
             i.quantity_reserved,
             -- Calculate highest active discount
@@ -51,13 +68,28 @@ pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
             ), p.price) AS discounted_price
         FROM products p
         LEFT JOIN product_images pi ON p.id = pi.product_id
-        JOIN featured_products fp ON p.id = fp.product_id
-        JOIN inventory i ON p.id = i.product_id
-        WHERE p.is_active = true
-        ORDER BY p.created_at DESC, pi.is_primary DESC
-        LIMIT 8
+        JOIN (
+            -- A featured variant features its product, which lists the variants
+            SELECT COALESCE(fpp.parent_id, fpp.id) AS product_id, MIN(f.position) AS position
+            FROM featured_products f
+            JOIN products fpp ON fpp.id = f.product_id AND fpp.is_active = true
+            JOIN products family ON family.id = COALESCE(fpp.parent_id, fpp.id)
+                AND family.is_active = true
+            WHERE ($1::text IS NULL OR f.featured_type = $1)
+              AND (f.start_date IS NULL OR f.start_date <= now())
+              AND (f.end_date IS NULL OR f.end_date > now())
+            GROUP BY COALESCE(fpp.parent_id, fpp.id)
+            ORDER BY MIN(f.position), COALESCE(fpp.parent_id, fpp.id)
+            LIMIT $2
+        ) fp ON p.id = fp.product_id
+        JOIN product_stock i ON p.id = i.product_id
+        LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
+        WHERE p.is_active = true AND p.parent_id IS NULL
+        ORDER BY fp.position ASC, p.created_at DESC, pi.is_primary DESC, pi.sort_order ASC
         "#
     )
+    .bind(featured_type)
+    .bind(MAX_FEATURED)
     .fetch_all(pool)
     .await?;
 
@@ -93,6 +125,11 @@ pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
             size: row.get("size"),
             product_type: row.get("product_type"),
             images: Some(Vec::new()),
+            parent_id: row.get("parent_id"),
+            slug: row.get("slug"),
+            average_rating: row.get("average_rating"),
+            review_count: row.get("review_count"),
+            variants: None,
         });
 
         // Add images
@@ -103,10 +140,13 @@ pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
                     .any(|img| img.url == row.get::<String, _>("url"))
                 {
                     images.push(ProductImage {
+                        id: row.get("image_id"),
                         product_id: image_product_id,
                         url: row.get("url"),
                         alt_text: row.get("alt_text"),
                         is_primary: row.get("is_primary"),
+                        sort_order: row.get("image_sort_order"),
+                        renditions: row.get("image_renditions"),
                     });
                 }
             }
@@ -114,10 +154,12 @@ pub async fn get_all_featured_products() -> Result<Vec<Product>, SqlxError> {
     }
 
     // Convert HashMap to Vec maintaining SQL order
-    let products: Vec<Product> = product_order
+    let mut products: Vec<Product> = product_order
         .into_iter()
         .filter_map(|id| products_map.remove(&id))
         .collect();
 
+    attach_variants(&mut products, false).await?;
+
     Ok(products)
 }
@@ -1,7 +1,11 @@
 pub mod featured;
 pub mod product_all;
 pub mod product_id;
+pub mod translations;
+pub mod variants;
 
 pub use featured::get_all_featured_products;
 pub use product_all::get_all_products;
-pub use product_id::get_product_by_id;
+pub use product_id::{get_product_by_id, get_product_by_id_including_inactive};
+pub use translations::localize_products;
+pub use variants::{attach_variants, get_variants_for_products};
@@ -1,3 +1,4 @@
+use crate::actions::get::product::attach_variants;
 use crate::pool::connect::pool;
 use crate::structs::product::{Product, ProductImage};
 use rust_decimal::Decimal;
@@ -38,21 +39,29 @@ pub async fn get_all_products() -> Result<Vec<Product>, SqlxError> {
             p.colors,
             p.size,
             p.product_type,
+            p.parent_id,
+            p.slug,
+            rs.average_rating,
+            COALESCE(rs.review_count, 0) AS review_count,
             pi.product_id AS image_product_id,
             pi.url,
             pi.alt_text,
             pi.is_primary,
+            pi.id AS image_id,
+            pi.sort_order AS image_sort_order,
+            pi.renditions AS image_renditions,
             i.quantity_on_hand,
             i.quantity_reserved
         FROM products p
-        JOIN inventory i ON p.id = i.product_id
+        JOIN product_stock i ON p.id = i.product_id
+        LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
         LEFT JOIN product_images pi ON p.id = pi.product_id
         LEFT JOIN discount_promotions_products dp ON dp.product_id = p.id
         LEFT JOIN discount_promotions d
                ON d.id = dp.discount_id
               AND d.start_date <= now()
               AND d.end_date >= now()
-        WHERE p.is_active = true
+        WHERE p.is_active = true AND p.parent_id IS NULL
         ORDER BY
             p.id,
             CASE
@@ -63,6 +72,7 @@ pub async fn get_all_products() -> Result<Vec<Product>, SqlxError> {
                 ELSE 0
             END DESC,
             pi.is_primary DESC,
+            pi.sort_order ASC,
             p.created_at DESC;
         "#,
     )
@@ -103,26 +113,37 @@ pub async fn get_all_products() -> Result<Vec<Product>, SqlxError> {
             size: row.get("size"),
             product_type: row.get("product_type"),
             images: Some(Vec::new()),
+            parent_id: row.get("parent_id"),
+            slug: row.get("slug"),
+            average_rating: row.get("average_rating"),
+            review_count: row.get("review_count"),
+            variants: None,
         });
 
         // Add image if it exists (LEFT JOIN might return NULL for products without images)
         if let Ok(image_product_id) = row.try_get::<Uuid, _>("image_product_id") {
             if let Some(ref mut images) = product.images {
                 images.push(ProductImage {
+                    id: row.get("image_id"),
                     product_id: image_product_id,
                     url: row.get("url"),
                     alt_text: row.get("alt_text"),
                     is_primary: row.get("is_primary"),
+                    sort_order: row.get("image_sort_order"),
+                    renditions: row.get("image_renditions"),
                 });
             }
         }
     }
 
     // Convert HashMap to Vec, maintaining the original order from SQL
-    let products: Vec<Product> = product_order
+    let mut products: Vec<Product> = product_order
         .into_iter()
         .filter_map(|id| products_map.remove(&id))
         .collect();
 
+    // Variants are listed under their parent instead of on their own
+    attach_variants(&mut products, false).await?;
+
     Ok(products)
 }
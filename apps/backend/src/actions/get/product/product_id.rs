@@ -5,6 +5,15 @@ use sqlx::{Error as SqlxError, Row};
 use uuid::Uuid;
 
 pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
+    fetch_product(id, false).await
+}
+
+/// Get a product for the admin, also when it is deactivated
+pub async fn get_product_by_id_including_inactive(id: Uuid) -> Result<Option<Product>, SqlxError> {
+    fetch_product(id, true).await
+}
+
+async fn fetch_product(id: Uuid, include_inactive: bool) -> Result<Option<Product>, SqlxError> {
     let pool = pool();
 
     // Use JOIN for single product too for consistency and performance
@@ -24,10 +33,17 @@ pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
             p.colors,
             p.size,
             p.product_type,
+            p.parent_id,
+            p.slug,
+            rs.average_rating,
+            COALESCE(rs.review_count, 0) AS review_count,
             pi.product_id AS image_product_id,
             pi.url,
             pi.alt_text,
             pi.is_primary,
+            pi.id AS image_id,
+            pi.sort_order AS image_sort_order,
+            pi.renditions AS image_renditions,
             i.quantity_on_hand,
             i.quantity_reserved,
             COALESCE((
@@ -50,12 +66,14 @@ pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
             ), p.price) AS discounted_price
         FROM products p
         LEFT JOIN product_images pi ON p.id = pi.product_id
-        JOIN inventory i ON p.id = i.product_id
-        WHERE p.id = $1 AND p.is_active = true
-        ORDER BY pi.is_primary DESC;
+        JOIN product_stock i ON p.id = i.product_id
+        LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
+        WHERE p.id = $1 AND ($2 OR p.is_active = true)
+        ORDER BY pi.is_primary DESC, pi.sort_order ASC;
         "#,
     )
     .bind(id)
+    .bind(include_inactive)
     .fetch_all(pool)
     .await?;
 
@@ -73,6 +91,10 @@ pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
     let size = first_row.get("size");
     let is_active = first_row.get("is_active");
     let product_type = first_row.get("product_type");
+    let parent_id = first_row.get("parent_id");
+    let slug = first_row.get("slug");
+    let average_rating = first_row.get("average_rating");
+    let review_count = first_row.get("review_count");
     let available_stock: Decimal = first_row.get::<Decimal, _>("quantity_on_hand")
         - first_row.get::<Decimal, _>("quantity_reserved");
     let price = first_row.get("price");
@@ -87,10 +109,13 @@ pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
         // Add image if it exists
         if let Ok(image_product_id) = row.try_get::<Uuid, _>("image_product_id") {
             images.push(ProductImage {
+                id: row.get("image_id"),
                 product_id: image_product_id,
                 url: row.get("url"),
                 alt_text: row.get("alt_text"),
                 is_primary: row.get("is_primary"),
+                sort_order: row.get("image_sort_order"),
+                renditions: row.get("image_renditions"),
             });
         }
     }
@@ -116,6 +141,11 @@ pub async fn get_product_by_id(id: Uuid) -> Result<Option<Product>, SqlxError> {
         } else {
             Some(images)
         },
+        parent_id,
+        slug,
+        average_rating,
+        review_count,
+        variants: None,
     };
 
     Ok(Some(product))
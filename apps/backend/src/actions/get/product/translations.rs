@@ -0,0 +1,47 @@
+use crate::pool::connect::pool;
+use crate::structs::locale::Locale;
+use crate::structs::product::Product;
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Show the products and their variants in a locale. Products without a translation keep
+/// their content in the default locale.
+pub async fn localize_products(products: &mut [Product], locale: Locale) -> Result<(), SqlxError> {
+    if locale.is_default() || products.is_empty() {
+        return Ok(());
+    }
+
+    let product_ids: Vec<Uuid> = products
+        .iter()
+        .flat_map(|product| {
+            std::iter::once(product.id)
+                .chain(product.variants.iter().flatten().map(|variant| variant.id))
+        })
+        .collect();
+
+    let pool = pool();
+    let mut translations: HashMap<Uuid, (String, String)> = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT product_id, name, description FROM product_translations WHERE product_id = ANY($1) AND locale = $2",
+    )
+    .bind(&product_ids)
+    .bind(locale)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(product_id, name, description)| (product_id, (name, description)))
+    .collect();
+
+    for product in products.iter_mut() {
+        if let Some((name, description)) = translations.remove(&product.id) {
+            product.name = name;
+            product.description = description;
+        }
+        for variant in product.variants.iter_mut().flatten() {
+            if let Some((name, _)) = translations.remove(&variant.id) {
+                variant.name = name;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::pool::connect::pool;
+use crate::structs::product::{Product, ProductVariant};
+use sqlx::Error as SqlxError;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Variants of the given parent products, cheapest first. Deactivated variants are only
+/// included for the admin.
+pub async fn get_variants_for_products(
+    parent_ids: &[Uuid],
+    include_inactive: bool,
+) -> Result<Vec<ProductVariant>, SqlxError> {
+    let pool = pool();
+
+    sqlx::query_as::<_, ProductVariant>(
+        r#"
+        SELECT
+            p.id,
+            p.parent_id,
+            p.slug,
+            p.name,
+            p.sku,
+            p.price,
+            COALESCE((
+                SELECT
+                    CASE
+                        WHEN dp.discount_type = 'percentage'
+                        THEN ROUND(p.price - (p.price * dp.discount_value / 100), 2)
+                        ELSE p.price - dp.discount_value
+                    END
+                FROM discount_promotions_products dpp
+                JOIN discount_promotions dp ON dp.id = dpp.discount_id
+                WHERE dpp.product_id = p.id
+                  AND now() BETWEEN dp.start_date AND dp.end_date
+                ORDER BY
+                    CASE
+                        WHEN dp.discount_type = 'percentage' THEN p.price * dp.discount_value / 100
+                        ELSE dp.discount_value
+                    END DESC
+                LIMIT 1
+            ), p.price) AS discounted_price,
+            p.size,
+            p.colors,
+            GREATEST(i.quantity_on_hand - i.quantity_reserved, 0) AS stock
+        FROM products p
+        JOIN product_stock i ON p.id = i.product_id
+        WHERE p.parent_id = ANY($1) AND ($2 OR p.is_active = true)
+        ORDER BY p.price ASC, p.sku ASC
+        "#,
+    )
+    .bind(parent_ids)
+    .bind(include_inactive)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fill in the variants of the parent products in a listing
+pub async fn attach_variants(
+    products: &mut [Product],
+    include_inactive: bool,
+) -> Result<(), SqlxError> {
+    let parent_ids: Vec<Uuid> = products
+        .iter()
+        .filter(|product| product.parent_id.is_none())
+        .map(|product| product.id)
+        .collect();
+    if parent_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut variants: HashMap<Uuid, Vec<ProductVariant>> = HashMap::new();
+    for variant in get_variants_for_products(&parent_ids, include_inactive).await? {
+        variants.entry(variant.parent_id).or_default().push(variant);
+    }
+
+    for product in products.iter_mut() {
+        product.variants = variants.remove(&product.id);
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
+use crate::middleware::RequestLocale;
 use crate::response::{ApiResponse, AppResponse};
 use crate::services::search::{SearchQuery, SearchResult, SearchService};
+use crate::structs::locale::Locale;
 use axum::extract::Query;
 use serde::Deserialize;
 use sqlx::Error as SqlxError;
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchParams {
@@ -13,6 +16,8 @@ pub struct SearchParams {
     pub price_min: Option<f64>,
     pub price_max: Option<f64>,
     pub in_stock: Option<bool>,
+    pub category_id: Option<Uuid>,
+    pub occasion_id: Option<Uuid>,
     pub sort_by: Option<String>,
     pub sort_direction: Option<String>,
     pub page: Option<u32>,
@@ -20,10 +25,13 @@ pub struct SearchParams {
 }
 
 /// Search products with comprehensive filtering and sorting
-pub async fn search_products(Query(params): Query<SearchParams>) -> ApiResponse<SearchResult> {
+pub async fn search_products(
+    Query(params): Query<SearchParams>,
+    RequestLocale(locale): RequestLocale,
+) -> ApiResponse<SearchResult> {
     // Parse search query
     let search_query = match parse_search_params(params) {
-        Ok(query) => query,
+        Ok(query) => SearchQuery { locale, ..query },
         Err(e) => {
             return AppResponse::error(crate::response::error::AppError::bad_request(&format!(
                 "Invalid search parameters: {}",
@@ -45,6 +53,7 @@ pub async fn search_products(Query(params): Query<SearchParams>) -> ApiResponse<
                 total_pages: 0,
                 search_time_ms: 0,
                 suggestions: None,
+                facets: None,
             };
             AppResponse::ok(empty_result)
         }
@@ -90,6 +99,7 @@ pub async fn get_search_suggestions(
 /// Search with auto-correction for better UX
 pub async fn search_with_corrections(
     Query(params): Query<SearchParams>,
+    RequestLocale(locale): RequestLocale,
 ) -> ApiResponse<SearchResult> {
     let query = params.q.as_ref().map(|s| s.as_str()).unwrap_or("");
 
@@ -102,7 +112,7 @@ pub async fn search_with_corrections(
     // Parse filters
     let filters = parse_search_filters(&params);
 
-    match SearchService::search_with_corrections(query, Some(filters)).await {
+    match SearchService::search_with_corrections(query, Some(filters), locale).await {
         Ok(result) => AppResponse::ok(result),
         Err(e) => {
             tracing::error!("Search with corrections error: {}", e);
@@ -148,6 +158,7 @@ pub fn parse_search_params(params: SearchParams) -> Result<SearchQuery, String>
             "price" => SearchSortField::Price,
             "created_at" | "date" => SearchSortField::CreatedAt,
             "stock" => SearchSortField::Stock,
+            "rating" => SearchSortField::Rating,
             "relevance" | _ => SearchSortField::Relevance,
         };
 
@@ -177,6 +188,7 @@ pub fn parse_search_params(params: SearchParams) -> Result<SearchQuery, String>
         filters,
         sort,
         pagination,
+        locale: Locale::DEFAULT,
     })
 }
 
@@ -198,6 +210,8 @@ pub fn parse_search_filters(params: &SearchParams) -> crate::services::search::S
         price_min: params.price_min.and_then(|p| Decimal::try_from(p).ok()),
         price_max: params.price_max.and_then(|p| Decimal::try_from(p).ok()),
         in_stock: params.in_stock,
+        category_id: params.category_id,
+        occasion_id: params.occasion_id,
     }
 }
 
@@ -209,4 +223,6 @@ pub fn has_filters(params: &SearchParams) -> bool {
         || params.price_min.is_some()
         || params.price_max.is_some()
         || params.in_stock.is_some()
+        || params.category_id.is_some()
+        || params.occasion_id.is_some()
 }
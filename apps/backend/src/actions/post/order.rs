@@ -1,6 +1,6 @@
 use crate::pool::connect::pool;
 use crate::structs::order::{Order, OrderLine};
-use sqlx::{Error as SqlxError, Row};
+use sqlx::{Error as SqlxError, PgConnection, Row};
 use std::error::Error;
 use uuid::Uuid;
 
@@ -13,19 +13,22 @@ pub async fn post_order(order: &Order) -> Result<Order, SqlxError> {
             id, user_id, order_number, status,
             subtotal, tax_amount, shipping_cost, discount_amount,
             total_amount, notes, shipping_address, billing_address,
-            created_at, updated_at
+            created_at, updated_at, delivery_date, awaiting_stock,
+            expected_ship_date, pickup_location_id, stock_location_id
         )
         VALUES (
             $1, $2, $3, $4,
             $5, $6, $7, $8,
             $9, $10, $11, $12,
-            $13, $14
+            $13, $14, $15, $16,
+            $17, $18, $19
         )
         RETURNING
             id, user_id, order_number, status,
             subtotal, tax_amount, shipping_cost, discount_amount,
             total_amount, notes, shipping_address, billing_address,
-            created_at, updated_at
+            created_at, updated_at, delivery_date, awaiting_stock,
+            expected_ship_date, pickup_location_id, stock_location_id
         "#,
     )
     .bind(order.id)
@@ -48,6 +51,11 @@ pub async fn post_order(order: &Order) -> Result<Order, SqlxError> {
     )
     .bind(order.created_at)
     .bind(order.updated_at)
+    .bind(order.delivery_date)
+    .bind(order.awaiting_stock)
+    .bind(order.expected_ship_date)
+    .bind(order.pickup_location_id)
+    .bind(order.stock_location_id)
     .fetch_one(pool)
     .await?;
 
@@ -68,6 +76,11 @@ pub async fn post_order(order: &Order) -> Result<Order, SqlxError> {
             .map_err(|e| SqlxError::Decode(Box::new(e) as Box<dyn Error + Send + Sync>))?,
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
+        delivery_date: row.get("delivery_date"),
+        awaiting_stock: row.get("awaiting_stock"),
+        expected_ship_date: row.get("expected_ship_date"),
+        pickup_location_id: row.get("pickup_location_id"),
+        stock_location_id: row.get("stock_location_id"),
     };
 
     Ok(order)
@@ -75,14 +88,40 @@ pub async fn post_order(order: &Order) -> Result<Order, SqlxError> {
 
 pub async fn post_order_line(order_line: &OrderLine) -> Result<OrderLine, SqlxError> {
     let pool = pool();
+    let mut conn = pool.acquire().await?;
 
-    let row = sqlx::query(
+    insert_order_line(&mut conn, order_line).await
+}
+
+pub async fn post_order_lines(order_lines: &[OrderLine]) -> Result<Vec<OrderLine>, SqlxError> {
+    let pool = pool();
+    let mut tx = pool.begin().await?;
+    let mut created_lines = Vec::new();
+
+    for order_line in order_lines {
+        created_lines.push(insert_order_line(&mut tx, order_line).await?);
+    }
+
+    tx.commit().await?;
+    Ok(created_lines)
+}
+
+/// Insert a single order line, including its product snapshot
+async fn insert_order_line(
+    conn: &mut PgConnection,
+    order_line: &OrderLine,
+) -> Result<OrderLine, SqlxError> {
+    sqlx::query_as::<_, OrderLine>(
         r#"
         INSERT INTO order_line (
-            order_id, product_id, quantity, unit_price, discount_amount, created_at
+            order_id, product_id, quantity, unit_price, discount_amount, created_at,
+            product_name, product_sku, product_size, product_colors, product_image_url,
+            vat_rate, applied_promotion_id
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
-        RETURNING id, created_at, order_id, product_id, quantity, unit_price, discount_amount
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING id, created_at, order_id, product_id, quantity, unit_price, discount_amount,
+            product_name, product_sku, product_size, product_colors, product_image_url,
+            vat_rate, applied_promotion_id
         "#,
     )
     .bind(order_line.order_id)
@@ -91,67 +130,37 @@ pub async fn post_order_line(order_line: &OrderLine) -> Result<OrderLine, SqlxEr
     .bind(order_line.unit_price)
     .bind(order_line.discount_amount)
     .bind(order_line.created_at)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(OrderLine {
-        id: row.get("id"),
-        created_at: row.get("created_at"),
-        order_id: row.get("order_id"),
-        product_id: row.get("product_id"),
-        quantity: row.get("quantity"),
-        unit_price: row.get("unit_price"),
-        discount_amount: row.get("discount_amount"),
-    })
+    .bind(&order_line.product_name)
+    .bind(&order_line.product_sku)
+    .bind(&order_line.product_size)
+    .bind(&order_line.product_colors)
+    .bind(&order_line.product_image_url)
+    .bind(order_line.vat_rate)
+    .bind(order_line.applied_promotion_id)
+    .fetch_one(conn)
+    .await
 }
 
-pub async fn post_order_lines(order_lines: &[OrderLine]) -> Result<Vec<OrderLine>, SqlxError> {
+/// Create complete order with order lines in a single transaction
+pub async fn create_order_with_lines(
+    order: &Order,
+    order_lines: &[OrderLine],
+) -> Result<(Order, Vec<OrderLine>), SqlxError> {
     let pool = pool();
     let mut tx = pool.begin().await?;
-    let mut created_lines = Vec::new();
-
-    for order_line in order_lines {
-        let row = sqlx::query(
-            r#"
-            INSERT INTO order_line (
-                order_id, product_id, quantity, unit_price, discount_amount, created_at
-            )
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, created_at, order_id, product_id, quantity, unit_price, discount_amount
-            "#,
-        )
-        .bind(order_line.order_id)
-        .bind(order_line.product_id)
-        .bind(order_line.quantity)
-        .bind(order_line.unit_price)
-        .bind(order_line.discount_amount)
-        .bind(order_line.created_at)
-        .fetch_one(&mut *tx)
-        .await?;
 
-        created_lines.push(OrderLine {
-            id: row.get("id"),
-            created_at: row.get("created_at"),
-            order_id: row.get("order_id"),
-            product_id: row.get("product_id"),
-            quantity: row.get("quantity"),
-            unit_price: row.get("unit_price"),
-            discount_amount: row.get("discount_amount"),
-        });
-    }
+    let created = insert_order_with_lines(&mut tx, order, order_lines).await?;
 
     tx.commit().await?;
-    Ok(created_lines)
+    Ok(created)
 }
 
-/// Create complete order with order lines in a single transaction
-pub async fn create_order_with_lines(
+/// Insert an order with its order lines within the caller's transaction
+pub async fn insert_order_with_lines(
+    conn: &mut PgConnection,
     order: &Order,
     order_lines: &[OrderLine],
 ) -> Result<(Order, Vec<OrderLine>), SqlxError> {
-    let pool = pool();
-    let mut tx = pool.begin().await?;
-
     // Insert the order
     let order_row = sqlx::query(
         r#"
@@ -159,19 +168,22 @@ pub async fn create_order_with_lines(
             id, user_id, order_number, status,
             subtotal, tax_amount, shipping_cost, discount_amount,
             total_amount, notes, shipping_address, billing_address,
-            created_at, updated_at
+            created_at, updated_at, delivery_date, awaiting_stock,
+            expected_ship_date, pickup_location_id, stock_location_id
         )
         VALUES (
             $1, $2, $3, $4,
             $5, $6, $7, $8,
             $9, $10, $11, $12,
-            $13, $14
+            $13, $14, $15, $16,
+            $17, $18, $19
         )
         RETURNING
             id, user_id, order_number, status,
             subtotal, tax_amount, shipping_cost, discount_amount,
             total_amount, notes, shipping_address, billing_address,
-            created_at, updated_at
+            created_at, updated_at, delivery_date, awaiting_stock,
+            expected_ship_date, pickup_location_id, stock_location_id
         "#,
     )
     .bind(order.id)
@@ -194,7 +206,12 @@ pub async fn create_order_with_lines(
     )
     .bind(order.created_at)
     .bind(order.updated_at)
-    .fetch_one(&mut *tx)
+    .bind(order.delivery_date)
+    .bind(order.awaiting_stock)
+    .bind(order.expected_ship_date)
+    .bind(order.pickup_location_id)
+    .bind(order.stock_location_id)
+    .fetch_one(&mut *conn)
     .await?;
 
     let created_order = Order {
@@ -214,41 +231,19 @@ pub async fn create_order_with_lines(
             .map_err(|e| SqlxError::Decode(Box::new(e) as Box<dyn Error + Send + Sync>))?,
         created_at: order_row.get("created_at"),
         updated_at: order_row.get("updated_at"),
+        delivery_date: order_row.get("delivery_date"),
+        awaiting_stock: order_row.get("awaiting_stock"),
+        expected_ship_date: order_row.get("expected_ship_date"),
+        pickup_location_id: order_row.get("pickup_location_id"),
+        stock_location_id: order_row.get("stock_location_id"),
     };
 
     // Insert order lines
     let mut created_lines = Vec::new();
     for order_line in order_lines {
-        let line_row = sqlx::query(
-            r#"
-            INSERT INTO order_line (
-                order_id, product_id, quantity, unit_price, discount_amount, created_at
-            )
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, created_at, order_id, product_id, quantity, unit_price, discount_amount
-            "#,
-        )
-        .bind(order_line.order_id)
-        .bind(order_line.product_id)
-        .bind(order_line.quantity)
-        .bind(order_line.unit_price)
-        .bind(order_line.discount_amount)
-        .bind(order_line.created_at)
-        .fetch_one(&mut *tx)
-        .await?;
-
-        created_lines.push(OrderLine {
-            id: line_row.get("id"),
-            created_at: line_row.get("created_at"),
-            order_id: line_row.get("order_id"),
-            product_id: line_row.get("product_id"),
-            quantity: line_row.get("quantity"),
-            unit_price: line_row.get("unit_price"),
-            discount_amount: line_row.get("discount_amount"),
-        });
+        created_lines.push(insert_order_line(&mut *conn, order_line).await?);
     }
 
-    tx.commit().await?;
     Ok((created_order, created_lines))
 }
 
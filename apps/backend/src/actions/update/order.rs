@@ -18,7 +18,8 @@ pub async fn update_order_status(id: Uuid, order_status: OrderStatus) -> Result<
             id, user_id, order_number, status,
             subtotal, tax_amount, shipping_cost, discount_amount,
             total_amount, notes, shipping_address, billing_address,
-            created_at, updated_at
+            created_at, updated_at, delivery_date, awaiting_stock,
+            expected_ship_date, pickup_location_id, stock_location_id
         "#,
     )
     .bind(order_status)
@@ -45,6 +46,11 @@ pub async fn update_order_status(id: Uuid, order_status: OrderStatus) -> Result<
         })?,
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
+        delivery_date: row.get("delivery_date"),
+        awaiting_stock: row.get("awaiting_stock"),
+        expected_ship_date: row.get("expected_ship_date"),
+        pickup_location_id: row.get("pickup_location_id"),
+        stock_location_id: row.get("stock_location_id"),
     };
 
     Ok(order)
@@ -0,0 +1,9 @@
+use crate::services::AbandonedCartService;
+use tracing::error;
+
+/// Queue reminder emails for carts that have been left behind
+pub async fn run() {
+    if let Err(e) = AbandonedCartService::queue_reminders().await {
+        error!("Abandoned cart job failed: {}", e);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::services::StockBatchService;
+use tracing::error;
+
+/// Write off the unreserved stock of batches past their expiry date
+pub async fn run() {
+    if let Err(e) = StockBatchService::write_off_expired().await {
+        error!("Expired stock job failed: {}", e);
+    }
+}
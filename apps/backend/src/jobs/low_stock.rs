@@ -0,0 +1,9 @@
+use crate::services::LowStockService;
+use tracing::error;
+
+/// Alert staff about products that dropped to their reorder threshold
+pub async fn run() {
+    if let Err(e) = LowStockService::check_low_stock().await {
+        error!("Low stock job failed: {}", e);
+    }
+}
@@ -0,0 +1,63 @@
+pub mod abandoned_carts;
+pub mod expired_stock;
+pub mod low_stock;
+pub mod order_reconciliation;
+pub mod price_changes;
+pub mod related_products;
+pub mod stock_ledger;
+
+use std::future::Future;
+use std::time::Duration;
+use tracing::info;
+
+/// Spawn all periodic background jobs on the runtime
+pub fn spawn_background_jobs() {
+    let interval =
+        Duration::from_secs(crate::secrets::get_abandoned_cart_check_interval_minutes() * 60);
+    spawn_periodic("abandoned_carts", interval, abandoned_carts::run);
+
+    let interval =
+        Duration::from_secs(crate::secrets::get_order_reconciliation_interval_hours() * 3600);
+    spawn_periodic("order_reconciliation", interval, order_reconciliation::run);
+
+    let interval =
+        Duration::from_secs(crate::secrets::get_stock_expiry_check_interval_hours() * 3600);
+    spawn_periodic("expired_stock", interval, expired_stock::run);
+
+    let interval =
+        Duration::from_secs(crate::secrets::get_stock_ledger_check_interval_hours() * 3600);
+    spawn_periodic("stock_ledger", interval, stock_ledger::run);
+
+    let interval = Duration::from_secs(crate::secrets::get_low_stock_check_interval_minutes() * 60);
+    spawn_periodic("low_stock", interval, low_stock::run);
+
+    let interval =
+        Duration::from_secs(crate::secrets::get_related_products_interval_hours() * 3600);
+    spawn_periodic("related_products", interval, related_products::run);
+
+    let interval =
+        Duration::from_secs(crate::secrets::get_price_change_check_interval_minutes() * 60);
+    spawn_periodic("price_changes", interval, price_changes::run);
+
+    info!("Background jobs started");
+}
+
+/// Run `job` every `interval`, starting one interval after startup
+fn spawn_periodic<F, Fut>(name: &'static str, interval: Duration, job: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately; skip it so startup stays fast
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            tracing::debug!("Running background job '{}'", name);
+            job().await;
+        }
+    });
+}
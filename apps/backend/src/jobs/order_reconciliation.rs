@@ -0,0 +1,9 @@
+use crate::services::ReconciliationService;
+use tracing::error;
+
+/// Recompute order totals from their lines and flag the ones that don't add up
+pub async fn run() {
+    if let Err(e) = ReconciliationService::reconcile_orders().await {
+        error!("Order reconciliation job failed: {}", e);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::services::PriceHistoryService;
+use tracing::error;
+
+/// Apply the scheduled list price changes that are due
+pub async fn run() {
+    if let Err(e) = PriceHistoryService::apply_due_changes().await {
+        error!("Price change job failed: {}", e);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::services::RecommendationService;
+use tracing::error;
+
+/// Refresh the products bought together from the latest orders
+pub async fn run() {
+    if let Err(e) = RecommendationService::recompute_related().await {
+        error!("Related products job failed: {}", e);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::services::StockMovementService;
+use tracing::error;
+
+/// Compare the stock of every product with its movements; drift is logged as a warning
+pub async fn run() {
+    if let Err(e) = StockMovementService::check_ledger().await {
+        error!("Stock ledger job failed: {}", e);
+    }
+}
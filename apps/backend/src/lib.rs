@@ -1,4 +1,5 @@
 pub mod actions;
+pub mod jobs;
 pub mod middleware;
 pub mod pool;
 pub mod response;
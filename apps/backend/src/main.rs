@@ -1,4 +1,5 @@
 pub mod actions;
+pub mod jobs;
 pub mod middleware;
 pub mod pool;
 pub mod response;
@@ -26,6 +27,9 @@ async fn main(#[shuttle_runtime::Secrets] secrets: SecretStore) -> shuttle_axum:
     // Warm up the database connection pool
     warmup_database().await;
 
+    // Start periodic background jobs
+    jobs::spawn_background_jobs();
+
     // Enable CORS middleware
     let cors = middleware::cors::cors_middleware();
 
@@ -0,0 +1,38 @@
+use crate::structs::locale::Locale;
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{header::ACCEPT_LANGUAGE, request::Parts},
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+
+/// The locale a request asked for, through `?lang=` or `Accept-Language`
+#[derive(Clone, Copy, Debug)]
+pub struct RequestLocale(pub Locale);
+
+#[derive(Deserialize)]
+struct LangParam {
+    lang: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for RequestLocale
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let lang = Query::<LangParam>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(param)| param.lang);
+        let accept_language = parts
+            .headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+
+        Ok(RequestLocale(Locale::negotiate(
+            lang.as_deref(),
+            accept_language,
+        )))
+    }
+}
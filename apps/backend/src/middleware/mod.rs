@@ -1,8 +1,10 @@
 pub mod auth;
 pub mod cors;
+pub mod locale;
 pub mod request_logger;
 
 pub use auth::{
     AuthUser, admin_middleware, auth_middleware, extract_auth_user, optional_auth_middleware,
 };
+pub use locale::RequestLocale;
 pub use request_logger::request_logger_middleware;
@@ -0,0 +1,32 @@
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::BillOfMaterialsService;
+use crate::structs::bill_of_materials::{
+    BouquetRecipe, ProductionList, ProductionListQuery, SetRecipeRequest,
+};
+use axum::{
+    Json,
+    extract::{Path, Query},
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+/// GET /admin/products/:id/recipe - Components a bouquet is made of
+pub async fn get_recipe(Path(bouquet_id): Path<Uuid>) -> ApiResponse<BouquetRecipe> {
+    AppResponse::from_result(BillOfMaterialsService::get_recipe(bouquet_id).await)
+}
+
+/// PUT /admin/products/:id/recipe - Replace the recipe of a bouquet
+pub async fn set_recipe(
+    Path(bouquet_id): Path<Uuid>,
+    Json(request): Json<SetRecipeRequest>,
+) -> ApiResponse<BouquetRecipe> {
+    AppResponse::from_result(BillOfMaterialsService::set_recipe(bouquet_id, &request).await)
+}
+
+/// GET /admin/production?date= - Bouquets to assemble for a delivery date, today by default
+pub async fn get_production_list(
+    Query(query): Query<ProductionListQuery>,
+) -> ApiResponse<ProductionList> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    AppResponse::from_result(BillOfMaterialsService::production_list(date).await)
+}
@@ -1,6 +1,9 @@
 use crate::middleware::auth::AuthUser;
 use crate::response::{ApiResponse, AppResponse, error::AppError};
-use crate::services::CartService;
+use crate::services::{AbandonedCartService, CartService};
+use crate::structs::abandoned_cart::{
+    AbandonedCartStats, CartReminderPreference, RestoreCartRequest,
+};
 use crate::structs::cart::{AddCartItemRequest, MergeCartRequest, UpdateCartItemRequest};
 use axum::{
     Json,
@@ -105,3 +108,54 @@ pub async fn merge_cart(
 
     CartService::merge_guest_cart(user_id, request.items).await
 }
+
+/// POST /api/cart/restore - Restore the cart from an abandoned cart reminder link
+pub async fn restore_cart(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<RestoreCartRequest>,
+) -> ApiResponse<crate::structs::cart::CartResponse> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if request.token.trim().is_empty() {
+        return AppResponse::Error(AppError::ValidationError(
+            "Restore token is required".to_string(),
+        ));
+    }
+
+    AbandonedCartService::restore_cart(user_id, request.token.trim()).await
+}
+
+/// PATCH /api/cart/reminders - Opt in or out of abandoned cart reminders
+pub async fn update_cart_reminder_preference(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(preference): Json<CartReminderPreference>,
+) -> ApiResponse<CartReminderPreference> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match AbandonedCartService::set_reminders_enabled(user_id, preference.enabled).await {
+        Ok(()) => AppResponse::Success(preference),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /cart/reminders/unsubscribe/:token - Opt out from the link in a reminder email
+pub async fn unsubscribe_cart_reminders(Path(token): Path<String>) -> ApiResponse<()> {
+    match AbandonedCartService::unsubscribe_by_token(&token).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// GET /admin/carts/abandoned - Reminder and recovery statistics
+pub async fn get_abandoned_cart_stats() -> ApiResponse<AbandonedCartStats> {
+    match AbandonedCartService::get_stats().await {
+        Ok(stats) => AppResponse::Success(stats),
+        Err(e) => AppResponse::Error(e),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::FeaturedService;
+use crate::structs::featured::{FeaturedAdminQuery, FeaturedPlacement, FeaturedRequest};
+use axum::{
+    Json,
+    extract::{Path, Query},
+};
+use uuid::Uuid;
+
+/// GET /admin/featured - Featured placements, optionally of one type and including ended ones
+pub async fn get_placements(
+    Query(query): Query<FeaturedAdminQuery>,
+) -> ApiResponse<Vec<FeaturedPlacement>> {
+    AppResponse::from_result(
+        FeaturedService::list_placements(query.featured_type, query.include_ended.unwrap_or(false))
+            .await,
+    )
+}
+
+/// POST /admin/featured - Place a product in a featured spot
+pub async fn create_placement(
+    Json(request): Json<FeaturedRequest>,
+) -> ApiResponse<FeaturedPlacement> {
+    AppResponse::from_result(FeaturedService::create_placement(&request).await)
+}
+
+/// PUT /admin/featured/:id - Replace a placement
+pub async fn update_placement(
+    Path(placement_id): Path<Uuid>,
+    Json(request): Json<FeaturedRequest>,
+) -> ApiResponse<FeaturedPlacement> {
+    AppResponse::from_result(FeaturedService::update_placement(placement_id, &request).await)
+}
+
+/// DELETE /admin/featured/:id - Remove a placement
+pub async fn delete_placement(Path(placement_id): Path<Uuid>) -> ApiResponse<()> {
+    AppResponse::from_result(FeaturedService::delete_placement(placement_id).await)
+}
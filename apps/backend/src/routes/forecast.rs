@@ -0,0 +1,46 @@
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::ForecastService;
+use crate::structs::forecast::{
+    ForecastQuery, ProductForecast, SeasonalEvent, SeasonalEventRequest,
+};
+use axum::{
+    Json,
+    extract::{Path, Query},
+};
+use uuid::Uuid;
+
+const DEFAULT_FORECAST_DAYS: i32 = 14;
+const MAX_FORECAST_DAYS: i32 = 90;
+
+/// GET /admin/forecast?days=&product_id= - Expected demand per product per day
+pub async fn get_forecast(Query(query): Query<ForecastQuery>) -> ApiResponse<Vec<ProductForecast>> {
+    let days = query.days.unwrap_or(DEFAULT_FORECAST_DAYS);
+    if !(1..=MAX_FORECAST_DAYS).contains(&days) {
+        return AppResponse::Error(AppError::ValidationError(format!(
+            "Days must be between 1 and {}",
+            MAX_FORECAST_DAYS
+        )));
+    }
+
+    AppResponse::from_result(ForecastService::product_forecasts(days, query.product_id).await)
+}
+
+/// GET /admin/forecast/events - Seasonal events and their demand multipliers
+pub async fn get_seasonal_events() -> ApiResponse<Vec<SeasonalEvent>> {
+    AppResponse::from_result(ForecastService::list_events().await)
+}
+
+/// POST /admin/forecast/events - Add a seasonal event
+pub async fn create_seasonal_event(
+    Json(request): Json<SeasonalEventRequest>,
+) -> ApiResponse<SeasonalEvent> {
+    AppResponse::from_result(ForecastService::create_event(&request).await)
+}
+
+/// DELETE /admin/forecast/events/:id - Remove a seasonal event
+pub async fn delete_seasonal_event(Path(event_id): Path<Uuid>) -> ApiResponse<()> {
+    match ForecastService::delete_event(event_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
@@ -1,11 +1,27 @@
 use crate::actions::get::get_all_featured_products;
+use crate::actions::get::product::localize_products;
+use crate::middleware::RequestLocale;
 use crate::response::{ApiResponse, AppResponse, error::AppError, success};
+use crate::structs::featured::FeaturedQuery;
 use crate::structs::product::Product;
+use axum::extract::Query;
 
-// GET /products/featured - Get all products
-pub async fn get_featured_products() -> ApiResponse<Vec<Product>> {
-    match get_all_featured_products().await {
-        Ok(products) => success(products),
+// GET /products/featured - Featured products that are scheduled now, optionally of one type
+pub async fn get_featured_products(
+    Query(query): Query<FeaturedQuery>,
+    RequestLocale(locale): RequestLocale,
+) -> ApiResponse<Vec<Product>> {
+    let mut products = match get_all_featured_products(query.featured_type).await {
+        Ok(products) => products,
+        Err(db_error) => {
+            return AppResponse::Error(AppError::DatabaseError(format!(
+                "Failed to retrieve products due to a database error: {}. Please try again later or contact support if the problem persists.",
+                db_error
+            )));
+        }
+    };
+    match localize_products(&mut products, locale).await {
+        Ok(()) => success(products),
         Err(db_error) => AppResponse::Error(AppError::DatabaseError(format!(
             "Failed to retrieve products due to a database error: {}. Please try again later or contact support if the problem persists.",
             db_error
@@ -1,14 +1,28 @@
-use axum::extract::Path;
+use axum::extract::{Path, RawQuery};
+use axum::response::{IntoResponse, Redirect, Response};
 use uuid::Uuid;
 
+use crate::actions::get::product::{attach_variants, localize_products};
 use crate::actions::get::{get_all_products, get_product_by_id};
+use crate::middleware::RequestLocale;
 use crate::response::{ApiResponse, AppResponse, error::AppError, success};
-use crate::structs::product::Product;
+use crate::services::ProductService;
+use crate::structs::locale::Locale;
+use crate::structs::product::{Product, SlugLookup};
 
 // GET /products - Get all products
-pub async fn get_products() -> ApiResponse<Vec<Product>> {
-    match get_all_products().await {
-        Ok(products) => success(products),
+pub async fn get_products(RequestLocale(locale): RequestLocale) -> ApiResponse<Vec<Product>> {
+    let mut products = match get_all_products().await {
+        Ok(products) => products,
+        Err(db_error) => {
+            return AppResponse::Error(AppError::DatabaseError(format!(
+                "Failed to retrieve products due to a database error: {}. Please try again later or contact support if the problem persists.",
+                db_error
+            )));
+        }
+    };
+    match localize_products(&mut products, locale).await {
+        Ok(()) => success(products),
         Err(db_error) => AppResponse::Error(AppError::DatabaseError(format!(
             "Failed to retrieve products due to a database error: {}. Please try again later or contact support if the problem persists.",
             db_error
@@ -17,9 +31,51 @@ pub async fn get_products() -> ApiResponse<Vec<Product>> {
 }
 
 // // GET /product/:id - Get product by ID
-pub async fn get_product(Path(id): Path<Uuid>) -> ApiResponse<Product> {
+pub async fn get_product(
+    Path(id): Path<Uuid>,
+    RequestLocale(locale): RequestLocale,
+) -> ApiResponse<Product> {
+    product_with_variants(id, locale).await
+}
+
+// GET /products/by-slug/:slug - Get product by slug; earlier slugs redirect to the current one
+pub async fn get_product_by_slug(
+    Path(slug): Path<String>,
+    RawQuery(query): RawQuery,
+    RequestLocale(locale): RequestLocale,
+) -> Response {
+    match ProductService::resolve_slug(&slug).await {
+        Ok(SlugLookup::Current(id)) => product_with_variants(id, locale).await.into_response(),
+        Ok(SlugLookup::Moved(slug)) => {
+            // Keep the query so that `?lang=` survives the redirect
+            let query = query.map(|query| format!("?{}", query)).unwrap_or_default();
+            Redirect::permanent(&format!("/products/by-slug/{}{}", slug, query)).into_response()
+        }
+        Err(e) => AppResponse::<(), AppError>::Error(e).into_response(),
+    }
+}
+
+async fn product_with_variants(id: Uuid, locale: Locale) -> ApiResponse<Product> {
     match get_product_by_id(id).await {
-        Ok(Some(product)) => success(product),
+        Ok(Some(mut product)) => {
+            // A parent is shown with its variants
+            if let Err(db_error) = attach_variants(std::slice::from_mut(&mut product), false).await
+            {
+                return AppResponse::Error(AppError::DatabaseError(format!(
+                    "Failed to retrieve the variants of product {}: {}",
+                    id, db_error
+                )));
+            }
+            if let Err(db_error) =
+                localize_products(std::slice::from_mut(&mut product), locale).await
+            {
+                return AppResponse::Error(AppError::DatabaseError(format!(
+                    "Failed to retrieve the translation of product {}: {}",
+                    id, db_error
+                )));
+            }
+            success(product)
+        }
         Ok(_) => AppResponse::Error(AppError::NotFound(format!(
             "Product with ID {} not found. Please check the product ID and try again.",
             id
@@ -2,13 +2,17 @@ use crate::actions::get::search::{
     SearchParams, get_popular_searches, get_search_suggestions, search_products,
     search_with_corrections,
 };
+use crate::middleware::RequestLocale;
 use crate::response::ApiResponse;
 use crate::services::search::SearchResult;
 use axum::{Router, extract::Query, routing::get};
 
 /// Search products endpoint
-pub async fn search_products_route(params: Query<SearchParams>) -> ApiResponse<SearchResult> {
-    search_products(params).await
+pub async fn search_products_route(
+    params: Query<SearchParams>,
+    locale: RequestLocale,
+) -> ApiResponse<SearchResult> {
+    search_products(params, locale).await
 }
 
 /// Get search suggestions endpoint
@@ -19,8 +23,9 @@ pub async fn search_suggestions_route(params: Query<SearchParams>) -> ApiRespons
 /// Search with auto-corrections endpoint
 pub async fn search_with_corrections_route(
     params: Query<SearchParams>,
+    locale: RequestLocale,
 ) -> ApiResponse<SearchResult> {
-    search_with_corrections(params).await
+    search_with_corrections(params, locale).await
 }
 
 /// Get popular searches endpoint
@@ -0,0 +1,332 @@
+use crate::actions::get::get_product_by_id;
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::secrets;
+use crate::services::{
+    InventoryService, InventoryStatus, LowStockProduct, StockBatchService, StockLocationService,
+    StockMovementService,
+};
+use crate::structs::inventory::{
+    BackorderPolicyRequest, LowStockQuery, ReceiveStockRequest, ReorderThresholdRequest,
+    StockCountRequest, WriteOffReason, WriteOffRequest,
+};
+use crate::structs::stock_batch::{ExpiringBatch, ExpiringBatchQuery};
+use crate::structs::stock_location::StockTransferRequest;
+use crate::structs::stock_movement::{
+    MovementContext, StockLedgerReport, StockMovement, StockMovementQuery,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query},
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+const DEFAULT_EXPIRY_WINDOW_DAYS: i32 = 3;
+const MAX_EXPIRY_WINDOW_DAYS: i32 = 60;
+const MAX_NOTE_LENGTH: usize = 500;
+
+fn validate_quantity(quantity: Decimal) -> Result<(), AppError> {
+    if quantity <= Decimal::ZERO {
+        return Err(AppError::ValidationError(
+            "Quantity must be greater than 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn clean_note(note: Option<String>) -> Result<Option<String>, AppError> {
+    let note = note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    if note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+    {
+        return Err(AppError::ValidationError(format!(
+            "Note cannot be longer than {} characters",
+            MAX_NOTE_LENGTH
+        )));
+    }
+    Ok(note)
+}
+
+/// Movement context for a stock change made by the admin
+fn admin_context(auth_user: &AuthUser, note: Option<String>) -> Result<MovementContext, AppError> {
+    Ok(MovementContext {
+        note,
+        ..MovementContext::by_user(auth_user.user_uuid()?)
+    })
+}
+
+async fn product_status(product_id: Uuid) -> Result<InventoryStatus, AppError> {
+    InventoryService::get_product_status(product_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Product {} not found in inventory", product_id)))
+}
+
+/// GET /admin/inventory/manage - Stock of all active products
+pub async fn get_inventory_overview() -> ApiResponse<Vec<InventoryStatus>> {
+    AppResponse::from_result(InventoryService::get_inventory_status().await)
+}
+
+/// GET /admin/inventory/low-stock - Active products at or below their reorder threshold
+pub async fn get_low_stock(
+    Query(query): Query<LowStockQuery>,
+) -> ApiResponse<Vec<LowStockProduct>> {
+    if query
+        .threshold
+        .is_some_and(|threshold| threshold < Decimal::ZERO)
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Threshold cannot be negative".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(
+        InventoryService::get_low_stock_products(
+            secrets::get_low_stock_default_threshold(),
+            query.threshold,
+        )
+        .await,
+    )
+}
+
+/// PATCH /admin/inventory/:product_id/threshold - Set or clear the reorder threshold of a product
+pub async fn update_reorder_threshold(
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ReorderThresholdRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if request
+        .reorder_threshold
+        .is_some_and(|threshold| threshold < Decimal::ZERO)
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Reorder threshold cannot be negative".to_string(),
+        ));
+    }
+
+    if let Err(e) =
+        InventoryService::set_reorder_threshold(product_id, request.reorder_threshold).await
+    {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// PATCH /admin/inventory/:product_id/backorder - Take orders beyond the available stock,
+/// or stop doing so with a null policy
+pub async fn update_backorder_policy(
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<BackorderPolicyRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if let Some(policy) = &request.policy {
+        if policy.limit.is_some_and(|limit| limit < Decimal::ZERO) {
+            return AppResponse::Error(AppError::ValidationError(
+                "Backorder limit cannot be negative".to_string(),
+            ));
+        }
+        if policy
+            .expected_available_on
+            .is_some_and(|date| date < Utc::now().date_naive())
+        {
+            return AppResponse::Error(AppError::ValidationError(
+                "Expected availability date cannot be in the past".to_string(),
+            ));
+        }
+    }
+
+    if let Err(e) =
+        InventoryService::set_backorder_policy(product_id, request.policy.as_ref()).await
+    {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// GET /admin/inventory/expiring - Batches that expire within `days`, to discount or feature
+pub async fn get_expiring_batches(
+    Query(query): Query<ExpiringBatchQuery>,
+) -> ApiResponse<Vec<ExpiringBatch>> {
+    let days = query.days.unwrap_or(DEFAULT_EXPIRY_WINDOW_DAYS);
+    if !(0..=MAX_EXPIRY_WINDOW_DAYS).contains(&days) {
+        return AppResponse::Error(AppError::ValidationError(format!(
+            "Days must be between 0 and {}",
+            MAX_EXPIRY_WINDOW_DAYS
+        )));
+    }
+
+    AppResponse::from_result(StockBatchService::list_expiring(days).await)
+}
+
+/// POST /admin/inventory/:product_id/receive - Add a delivery to the on-hand stock
+pub async fn receive_stock(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ReceiveStockRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if let Err(e) = validate_quantity(request.quantity) {
+        return AppResponse::Error(e);
+    }
+    if request
+        .expires_on
+        .is_some_and(|expires_on| expires_on < Utc::now().date_naive())
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Expiry date cannot be in the past".to_string(),
+        ));
+    }
+    let context = match clean_note(request.note).and_then(|note| admin_context(&auth_user, note)) {
+        Ok(context) => context,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match get_product_by_id(product_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return AppResponse::Error(AppError::not_found("Product")),
+        Err(e) => {
+            return AppResponse::Error(AppError::DatabaseError(format!(
+                "Failed to fetch product {}: {}",
+                product_id, e
+            )));
+        }
+    }
+
+    let location_id = match StockLocationService::or_default(request.location_id).await {
+        Ok(location_id) => location_id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if let Err(e) = InventoryService::add_inventory(
+        product_id,
+        location_id,
+        request.quantity,
+        request.expires_on,
+        &context,
+    )
+    .await
+    {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// POST /admin/inventory/:product_id/count - Set on-hand stock to a counted quantity
+pub async fn record_stock_count(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<StockCountRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if request.counted_quantity < Decimal::ZERO {
+        return AppResponse::Error(AppError::ValidationError(
+            "Counted quantity cannot be negative".to_string(),
+        ));
+    }
+    let context = match clean_note(request.note).and_then(|note| admin_context(&auth_user, note)) {
+        Ok(context) => context,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    let location_id = match StockLocationService::or_default(request.location_id).await {
+        Ok(location_id) => location_id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if let Err(e) = InventoryService::record_stock_count(
+        product_id,
+        location_id,
+        request.counted_quantity,
+        &context,
+    )
+    .await
+    {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// POST /admin/inventory/:product_id/write-off - Remove damaged, wilted or lost stock
+pub async fn write_off_stock(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<WriteOffRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if let Err(e) = validate_quantity(request.quantity) {
+        return AppResponse::Error(e);
+    }
+    let note = match clean_note(request.note) {
+        Ok(note) => note,
+        Err(e) => return AppResponse::Error(e),
+    };
+    if request.reason == WriteOffReason::Other && note.is_none() {
+        return AppResponse::Error(AppError::ValidationError(
+            "A note is required when the write-off reason is 'other'".to_string(),
+        ));
+    }
+
+    // The ledger has a single write-off reason, the cause is kept in the note
+    let note = match note {
+        Some(note) => format!("{}: {}", request.reason, note),
+        None => request.reason.to_string(),
+    };
+    let context = match admin_context(&auth_user, Some(note)) {
+        Ok(context) => context,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    let location_id = match StockLocationService::or_default(request.location_id).await {
+        Ok(location_id) => location_id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if let Err(e) =
+        InventoryService::write_off(product_id, location_id, request.quantity, &context).await
+    {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// POST /admin/inventory/:product_id/transfer - Move available stock to another location
+pub async fn transfer_stock(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<StockTransferRequest>,
+) -> ApiResponse<InventoryStatus> {
+    if let Err(e) = validate_quantity(request.quantity) {
+        return AppResponse::Error(e);
+    }
+    let context =
+        match clean_note(request.note.clone()).and_then(|note| admin_context(&auth_user, note)) {
+            Ok(context) => context,
+            Err(e) => return AppResponse::Error(e),
+        };
+
+    if let Err(e) = StockLocationService::transfer(product_id, &request, &context).await {
+        return AppResponse::Error(e);
+    }
+
+    AppResponse::from_result(product_status(product_id).await)
+}
+
+/// GET /admin/inventory/:product_id/movements - Browse the stock ledger of a product
+pub async fn get_stock_movements(
+    Path(product_id): Path<Uuid>,
+    Query(query): Query<StockMovementQuery>,
+) -> ApiResponse<Vec<StockMovement>> {
+    AppResponse::from_result(
+        StockMovementService::list_movements(product_id, query.limit, query.offset).await,
+    )
+}
+
+/// GET /admin/inventory/ledger-check - Compare stock with the ledger and list the differences
+pub async fn check_stock_ledger() -> ApiResponse<StockLedgerReport> {
+    AppResponse::from_result(StockMovementService::check_ledger().await)
+}
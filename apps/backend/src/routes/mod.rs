@@ -1,16 +1,34 @@
 pub mod auth;
+pub mod bill_of_materials;
 pub mod cart;
+pub mod featured;
+pub mod forecast;
 pub mod get;
 pub mod health_check;
+pub mod inventory;
+pub mod notification;
 pub mod post;
+pub mod product;
+pub mod product_image;
 pub mod promotion;
+pub mod purchase_order;
+pub mod recommendation;
+pub mod reconciliation;
+pub mod review;
+pub mod stock_location;
+pub mod stock_notification;
+pub mod taxonomy;
+pub mod wishlist;
 
 use crate::middleware::{admin_middleware, auth_middleware, optional_auth_middleware};
 use crate::response::{ApiResponse, AppResponse, error::AppError};
 use axum::{
-    Router, middleware,
-    routing::{delete, get, patch, post},
+    Router,
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, patch, post, put},
 };
+use tower_http::services::ServeDir;
 
 async fn handle_404() -> ApiResponse<()> {
     AppResponse::Error(AppError::NotFound(
@@ -22,6 +40,11 @@ pub fn setup_routes(router: Router) -> Router {
     router
         // Health check endpoint (public)
         .route("/health", get(health_check::health_check))
+        // Uploaded product images
+        .nest_service(
+            "/media",
+            ServeDir::new(crate::secrets::get_image_storage_dir()),
+        )
         // Merge public routes instead of nesting at root
         .merge(public_routes())
         // RESTful routes (public)
@@ -47,6 +70,27 @@ fn public_routes() -> Router {
         )
         .route("/products", get(get::product::get_products))
         .route("/products/{id}", get(get::product::get_product))
+        .route(
+            "/products/by-slug/{slug}",
+            get(get::product::get_product_by_slug),
+        )
+        .route(
+            "/products/{id}/notify-me",
+            post(stock_notification::notify_me),
+        )
+        .route("/products/{id}/reviews", get(review::get_product_reviews))
+        .route(
+            "/products/{id}/reference-price",
+            get(product::get_reference_price),
+        )
+        .route(
+            "/products/{id}/related",
+            get(recommendation::get_related_products),
+        )
+        .route(
+            "/cart/recommendations",
+            post(recommendation::get_guest_cart_recommendations),
+        )
         // Search routes (public)
         .route(
             "/products/search",
@@ -60,6 +104,22 @@ fn public_routes() -> Router {
             "/products/search/popular",
             get(crate::actions::get::search::get_popular_searches),
         )
+        // Categories and occasions
+        .route("/categories", get(taxonomy::get_category_tree))
+        .route(
+            "/categories/{id}/products",
+            get(taxonomy::get_category_products),
+        )
+        .route("/occasions", get(taxonomy::get_occasions))
+        .route(
+            "/occasions/{id}/products",
+            get(taxonomy::get_occasion_products),
+        )
+        // Pickup points for checkout
+        .route(
+            "/locations/pickup-points",
+            get(stock_location::get_pickup_points),
+        )
         // Public inventory routes (limited access)
         .route("/inventory", get(get::inventory::get_all_inventory))
         .route(
@@ -112,6 +172,10 @@ fn authenticated_routes() -> Router {
             "/order/validate-pricing",
             post(crate::routes::post::validate_order_pricing),
         )
+        .route(
+            "/order/quote",
+            post(crate::routes::post::create_order_quote),
+        )
         .route("/order/cancel", post(crate::routes::post::cancel_order))
         // Cart routes
         .route("/cart", get(cart::get_cart))
@@ -120,6 +184,39 @@ fn authenticated_routes() -> Router {
         .route("/cart/items/{item_id}", patch(cart::update_cart_item))
         .route("/cart/items/{item_id}", delete(cart::remove_cart_item))
         .route("/cart/merge", post(cart::merge_cart))
+        .route(
+            "/cart/recommendations",
+            get(recommendation::get_cart_recommendations),
+        )
+        .route("/cart/restore", post(cart::restore_cart))
+        .route(
+            "/cart/reminders",
+            patch(cart::update_cart_reminder_preference),
+        )
+        .route(
+            "/cart/items/{item_id}/save-for-later",
+            post(wishlist::save_cart_item_for_later),
+        )
+        // Wishlist routes
+        .route("/wishlists", get(wishlist::get_wishlists))
+        .route("/wishlists", post(wishlist::create_wishlist))
+        .route("/wishlists/{id}", get(wishlist::get_wishlist))
+        .route("/wishlists/{id}", patch(wishlist::update_wishlist))
+        .route("/wishlists/{id}", delete(wishlist::delete_wishlist))
+        .route("/wishlists/{id}/items", post(wishlist::add_wishlist_item))
+        .route(
+            "/wishlists/{id}/items/{item_id}",
+            delete(wishlist::remove_wishlist_item),
+        )
+        .route(
+            "/wishlists/{id}/items/{item_id}/move-to-cart",
+            post(wishlist::move_wishlist_item_to_cart),
+        )
+        .route("/wishlists/{id}/share", post(wishlist::share_wishlist))
+        .route("/wishlists/{id}/share", delete(wishlist::unshare_wishlist))
+        // Reviews of delivered products
+        .route("/products/{id}/reviews", post(review::submit_review))
+        .route("/reviews", get(review::get_my_reviews))
         // User profile and account management
         .route("/profile", get(auth::profile))
         .route("/logout", post(auth::logout))
@@ -144,12 +241,215 @@ fn admin_routes() -> Router {
             "/users/{user_id}/orders",
             get(get::order::get_orders_by_user_admin),
         )
+        // Order totals reconciliation
+        .route(
+            "/orders/reconciliation",
+            get(reconciliation::get_reconciliation_flags),
+        )
+        .route(
+            "/orders/reconciliation",
+            post(reconciliation::run_reconciliation),
+        )
+        .route(
+            "/orders/reconciliation/{order_id}/review",
+            post(reconciliation::review_reconciliation_flag),
+        )
         // User management
         .route("/users/{id}", get(auth::get_user))
         .route("/users/role", post(auth::update_user_role))
         .route("/users/create-admin", post(auth::create_admin))
         // Advanced inventory management
-        .route("/inventory/manage", get(get::inventory::get_all_inventory))
+        .route("/inventory/manage", get(inventory::get_inventory_overview))
+        .route("/inventory/low-stock", get(inventory::get_low_stock))
+        .route("/inventory/expiring", get(inventory::get_expiring_batches))
+        .route(
+            "/inventory/{product_id}/threshold",
+            patch(inventory::update_reorder_threshold),
+        )
+        .route(
+            "/inventory/{product_id}/backorder",
+            patch(inventory::update_backorder_policy),
+        )
+        .route(
+            "/inventory/{product_id}/receive",
+            post(inventory::receive_stock),
+        )
+        .route(
+            "/inventory/{product_id}/count",
+            post(inventory::record_stock_count),
+        )
+        .route(
+            "/inventory/{product_id}/write-off",
+            post(inventory::write_off_stock),
+        )
+        .route(
+            "/inventory/{product_id}/transfer",
+            post(inventory::transfer_stock),
+        )
+        .route(
+            "/inventory/ledger-check",
+            get(inventory::check_stock_ledger),
+        )
+        .route(
+            "/inventory/{product_id}/movements",
+            get(inventory::get_stock_movements),
+        )
+        // Stock locations
+        .route("/locations", get(stock_location::get_locations))
+        .route("/locations", post(stock_location::create_location))
+        .route("/locations/{id}", patch(stock_location::update_location))
+        // Product catalogue
+        .route("/products", post(product::create_product))
+        .route("/products/{id}", get(product::get_product))
+        .route("/products/{id}", patch(product::update_product))
+        .route(
+            "/products/{id}/deactivate",
+            post(product::deactivate_product),
+        )
+        .route(
+            "/products/{id}/reactivate",
+            post(product::reactivate_product),
+        )
+        .route(
+            "/products/{id}/price-history",
+            get(product::get_price_history),
+        )
+        .route(
+            "/products/{id}/price-changes",
+            post(product::schedule_price_change),
+        )
+        .route(
+            "/products/{id}/price-changes/{change_id}",
+            delete(product::cancel_price_change),
+        )
+        .route(
+            "/products/related/recompute",
+            post(recommendation::recompute_related_products),
+        )
+        .route(
+            "/products/{id}/translations",
+            get(product::get_translations),
+        )
+        .route(
+            "/products/{id}/translations/{locale}",
+            put(product::set_translation),
+        )
+        .route(
+            "/products/{id}/translations/{locale}",
+            delete(product::delete_translation),
+        )
+        // Review moderation
+        .route("/reviews", get(review::get_review_queue))
+        .route("/reviews/{id}/moderate", post(review::moderate_review))
+        // Product images
+        .route("/products/{id}/images", get(product_image::get_images))
+        .route(
+            "/products/{id}/images",
+            post(product_image::upload_image).layer(DefaultBodyLimit::max(
+                crate::secrets::get_max_image_upload_bytes(),
+            )),
+        )
+        .route(
+            "/products/{id}/images",
+            delete(product_image::delete_all_images),
+        )
+        .route(
+            "/products/{id}/images/order",
+            put(product_image::reorder_images),
+        )
+        .route(
+            "/products/{id}/images/{image_id}",
+            patch(product_image::update_image),
+        )
+        .route(
+            "/products/{id}/images/{image_id}",
+            delete(product_image::delete_image),
+        )
+        // Featured placements
+        .route("/featured", get(featured::get_placements))
+        .route("/featured", post(featured::create_placement))
+        .route("/featured/{id}", put(featured::update_placement))
+        .route("/featured/{id}", delete(featured::delete_placement))
+        // Categories and occasions
+        .route("/categories", get(taxonomy::get_categories))
+        .route("/categories", post(taxonomy::create_category))
+        .route("/categories/{id}", put(taxonomy::update_category))
+        .route("/categories/{id}", delete(taxonomy::delete_category))
+        .route("/occasions", get(taxonomy::get_occasions))
+        .route("/occasions", post(taxonomy::create_occasion))
+        .route("/occasions/{id}", put(taxonomy::update_occasion))
+        .route("/occasions/{id}", delete(taxonomy::delete_occasion))
+        .route(
+            "/products/{id}/taxonomy",
+            get(taxonomy::get_product_taxonomy),
+        )
+        .route(
+            "/products/{id}/categories",
+            put(taxonomy::set_product_categories),
+        )
+        .route(
+            "/products/{id}/occasions",
+            put(taxonomy::set_product_occasions),
+        )
+        // Bouquet recipes and daily production
+        .route("/products/{id}/recipe", get(bill_of_materials::get_recipe))
+        .route("/products/{id}/recipe", put(bill_of_materials::set_recipe))
+        .route("/production", get(bill_of_materials::get_production_list))
+        // Suppliers and purchase orders
+        .route("/suppliers", get(purchase_order::get_suppliers))
+        .route("/suppliers", post(purchase_order::create_supplier))
+        .route("/suppliers/{id}", get(purchase_order::get_supplier))
+        .route("/suppliers/{id}", patch(purchase_order::update_supplier))
+        .route("/purchase-orders", get(purchase_order::get_purchase_orders))
+        .route(
+            "/purchase-orders",
+            post(purchase_order::create_purchase_order),
+        )
+        .route(
+            "/purchase-orders/suggestions",
+            get(purchase_order::get_suggested_purchases),
+        )
+        .route(
+            "/purchase-orders/{id}",
+            get(purchase_order::get_purchase_order),
+        )
+        .route(
+            "/purchase-orders/{id}",
+            put(purchase_order::update_purchase_order),
+        )
+        .route(
+            "/purchase-orders/{id}",
+            delete(purchase_order::delete_purchase_order),
+        )
+        .route(
+            "/purchase-orders/{id}/send",
+            post(purchase_order::send_purchase_order),
+        )
+        .route(
+            "/purchase-orders/{id}/receive",
+            post(purchase_order::receive_purchase_order),
+        )
+        // Demand forecasting
+        .route("/forecast", get(forecast::get_forecast))
+        .route("/forecast/events", get(forecast::get_seasonal_events))
+        .route("/forecast/events", post(forecast::create_seasonal_event))
+        .route(
+            "/forecast/events/{id}",
+            delete(forecast::delete_seasonal_event),
+        )
+        // Back-in-stock demand
+        .route(
+            "/products/notify-me",
+            get(stock_notification::get_subscriber_counts),
+        )
+        // Admin notification feed
+        .route("/notifications", get(notification::get_notifications))
+        .route(
+            "/notifications/{id}/read",
+            post(notification::mark_notification_read),
+        )
+        // Abandoned cart recovery
+        .route("/carts/abandoned", get(cart::get_abandoned_cart_stats))
         // Admin analytics and reporting (placeholder routes)
         .route("/analytics/orders", get(get::order::get_all_orders_admin))
         .route("/analytics/users", get(auth::get_user))
@@ -192,4 +492,19 @@ fn restful_routes() -> Router {
     Router::new()
         // Contact form submission
         .route("/contact", post(crate::routes::post::contact::contact))
+        // Abandoned cart reminder opt-out link
+        .route(
+            "/cart/reminders/unsubscribe/{token}",
+            post(cart::unsubscribe_cart_reminders),
+        )
+        // Back-in-stock subscription confirmation link
+        .route(
+            "/products/notify-me/verify/{token}",
+            post(stock_notification::verify_notify_me),
+        )
+        // Shared wishlist (read-only)
+        .route(
+            "/wishlists/shared/{token}",
+            get(wishlist::get_shared_wishlist),
+        )
 }
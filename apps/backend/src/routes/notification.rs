@@ -0,0 +1,29 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::NotificationService;
+use crate::structs::notification::{AdminNotification, NotificationQuery};
+use axum::extract::{Extension, Path, Query};
+use uuid::Uuid;
+
+/// GET /admin/notifications - Admin notification feed, newest first
+pub async fn get_notifications(
+    Query(query): Query<NotificationQuery>,
+) -> ApiResponse<Vec<AdminNotification>> {
+    let unread_only = query.unread_only.unwrap_or(false);
+    AppResponse::from_result(
+        NotificationService::list_notifications(unread_only, query.limit).await,
+    )
+}
+
+/// POST /admin/notifications/:id/read - Mark a notification as read
+pub async fn mark_notification_read(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(notification_id): Path<Uuid>,
+) -> ApiResponse<AdminNotification> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(NotificationService::mark_read(notification_id, user_id).await)
+}
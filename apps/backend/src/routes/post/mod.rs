@@ -3,6 +3,6 @@ pub mod order;
 
 pub use contact::contact;
 pub use order::{
-    calculate_order_pricing, cancel_order, check_order_inventory, order, ship_order,
-    validate_order_pricing,
+    calculate_order_pricing, cancel_order, check_order_inventory, create_order_quote, order,
+    ship_order, validate_order_pricing,
 };
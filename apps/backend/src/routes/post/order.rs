@@ -1,15 +1,24 @@
 use crate::actions;
-use crate::actions::post::order::{create_order_with_lines, get_order_with_lines};
+use crate::actions::post::order::{get_order_with_lines, insert_order_with_lines};
 use crate::middleware::auth::AuthUser;
+use crate::pool::connect::pool;
 use crate::response::{ApiResponse, AppResponse, error::AppError};
-use crate::services::{InventoryService, PricingResult, PricingService};
+use crate::services::{
+    AbandonedCartService, InventoryService, PricingResult, PricingService, QuoteService,
+    StockLocationService,
+};
 use crate::structs::inventory::{InventoryReservation, InventoryUpdate};
 use crate::structs::order::{IncomingOrder, Order, OrderLine};
+use crate::structs::quote::{QuoteRequest, QuoteResponse};
+use crate::structs::stock_movement::MovementContext;
 use crate::structs::{Address, OrderContent, OrderStatus};
+use crate::utils::tax::Tax;
 use crate::validate::structs::validate_user_id;
 use crate::validate::{validate_address, validate_complete_order};
 use axum::{Extension, Json};
+use chrono::{NaiveDate, Utc};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -19,6 +28,9 @@ pub struct AuthenticatedOrderRequest {
     pub shipping_address: Address,
     pub billing_address: Address,
     pub notes: Option<String>,
+    pub quote_id: Option<String>, // Signed quote from POST /api/order/quote
+    pub delivery_date: Option<NaiveDate>,
+    pub pickup_location_id: Option<Uuid>, // Collect the order at this pickup point
 }
 
 pub async fn order(
@@ -38,13 +50,22 @@ pub async fn order(
         shipping_address: payload.shipping_address,
         billing_address: payload.billing_address,
         notes: payload.notes,
+        delivery_date: payload.delivery_date,
+        pickup_location_id: payload.pickup_location_id,
     };
 
-    // Step 1: Validate and calculate pricing with discounts
-    let pricing_result = match PricingService::calculate_and_validate_pricing(&incoming_order).await
-    {
-        AppResponse::Success(result) => result,
-        AppResponse::Error(err) => return AppResponse::Error(err),
+    // Step 1: Validate and calculate pricing with discounts, or use the prices locked in a quote
+    let (quote_id, pricing_result) = match &payload.quote_id {
+        Some(signed_quote_id) => {
+            match QuoteService::price_order(signed_quote_id, &incoming_order).await {
+                Ok((quote_id, result)) => (Some(quote_id), result),
+                Err(err) => return AppResponse::Error(err),
+            }
+        }
+        None => match PricingService::calculate_and_validate_pricing(&incoming_order).await {
+            AppResponse::Success(result) => (None, result),
+            AppResponse::Error(err) => return AppResponse::Error(err),
+        },
     };
 
     if let Err(err) = validate_complete_order(&incoming_order) {
@@ -75,21 +96,35 @@ pub async fn order(
         )));
     }
 
-    // Step 2: Check inventory availability for all products first
+    if incoming_order
+        .delivery_date
+        .is_some_and(|date| date < Utc::now().date_naive())
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Delivery date cannot be in the past".to_string(),
+        ));
+    }
+
+    // Load the products once to snapshot their details on the order lines
+    let mut products = HashMap::new();
     for content in &incoming_order.items {
         for entry in &content.product {
-            let quantity = Decimal::from(entry.quantity);
-            match InventoryService::check_availability(entry.product_id, quantity).await {
-                Ok(true) => continue, // Product has sufficient inventory
-                Ok(false) => {
-                    return AppResponse::Error(AppError::ValidationError(format!(
-                        "Insufficient inventory for product {}. Requested quantity: {}",
-                        entry.product_id, entry.quantity
+            if products.contains_key(&entry.product_id) {
+                continue;
+            }
+            match actions::get::get_product_by_id(entry.product_id).await {
+                Ok(Some(product)) => {
+                    products.insert(entry.product_id, product);
+                }
+                Ok(_) => {
+                    return AppResponse::Error(AppError::NotFound(format!(
+                        "Product with ID {} is no longer available",
+                        entry.product_id
                     )));
                 }
                 Err(err) => {
                     return AppResponse::Error(AppError::DatabaseError(format!(
-                        "Failed to check inventory for product {}: {}",
+                        "Failed to fetch product {}: {}",
                         entry.product_id, err
                     )));
                 }
@@ -97,8 +132,8 @@ pub async fn order(
         }
     }
 
-    // Step 3: Reserve inventory for all products (STAGE 1: Order Placement)
-    // This marks items as "spoken for" but keeps them in warehouse until shipment
+    // Pickup orders are taken from their pickup point, deliveries from the location for
+    // their zone
     let mut reservations = Vec::new();
     for content in &incoming_order.items {
         for entry in &content.product {
@@ -109,16 +144,85 @@ pub async fn order(
         }
     }
 
-    if let Err(err) = InventoryService::reserve_inventory(&reservations).await {
-        return AppResponse::Error(AppError::DatabaseError(format!(
-            "Failed to reserve inventory: {}",
-            err
-        )));
+    let postal_code = match incoming_order.pickup_location_id {
+        Some(_) => None,
+        None => Some(incoming_order.shipping_address.postal_code.as_str()),
+    };
+    let location_id = match StockLocationService::select_for_order(
+        incoming_order.pickup_location_id,
+        postal_code,
+        &reservations,
+    )
+    .await
+    {
+        Ok(location_id) => location_id,
+        Err(err) => return AppResponse::Error(err),
+    };
+
+    // Step 2: Check inventory availability for all products first, at the location the
+    // whole order is reserved at
+    let stock_checks = match InventoryService::check_stock_at(&reservations, location_id).await {
+        Ok(stock_checks) => stock_checks,
+        Err(err) => return AppResponse::Error(err),
+    };
+    for (reservation, stock_check) in reservations.iter().zip(&stock_checks) {
+        if !stock_check.is_available() {
+            return AppResponse::Error(AppError::ValidationError(format!(
+                "Insufficient inventory for product {}. Requested quantity: {}",
+                reservation.product_id, reservation.quantity_to_reserve
+            )));
+        }
+    }
+
+    // Claim the quote before reserving, so it cannot be used for two orders at once
+    if let Some(quote_id) = quote_id
+        && let Err(err) = QuoteService::claim_quote(quote_id).await
+    {
+        return AppResponse::Error(err);
     }
 
+    // The order ID is known up front so the reservations can be traced back to the order
+    let order_id = Uuid::new_v4();
+    let stock_context = MovementContext::for_order(order_id, user_id);
+
+    // The reservations, their stock movements and the order are written in one
+    // transaction, so a failed order leaves no stock movements behind
+    let mut tx = match pool().begin().await {
+        Ok(tx) => tx,
+        Err(err) => {
+            release_quote(quote_id).await;
+            return AppResponse::Error(AppError::DatabaseError(format!(
+                "Failed to start transaction: {}",
+                err
+            )));
+        }
+    };
+
+    // Step 3: Reserve inventory for all products (STAGE 1: Order Placement)
+    // This marks items as "spoken for" but keeps them in warehouse until shipment
+    let stock_check = match InventoryService::reserve_inventory_with(
+        &mut tx,
+        &reservations,
+        location_id,
+        &stock_context,
+    )
+    .await
+    {
+        Ok(stock_check) => stock_check,
+        Err(err) => {
+            // Another order may have taken the stock since the check
+            release_quote(quote_id).await;
+            return AppResponse::Error(err);
+        }
+    };
+
     // Step 4: Build order with calculated pricing information
     let mut built_order = Order::build_order_with_pricing(&incoming_order, &pricing_result);
-    built_order.id = Some(Uuid::new_v4()); // Generate order ID
+    built_order.id = Some(order_id);
+    // Backordered and pre-ordered units ship when the last of them comes in
+    built_order.awaiting_stock = stock_check.is_backordered();
+    built_order.expected_ship_date = stock_check.expected_available_on();
+    built_order.stock_location_id = Some(location_id);
 
     // Step 5: Create order lines from the payload
     let mut order_lines = Vec::new();
@@ -139,44 +243,37 @@ pub async fn order(
                 .map(|p| p.original_price - p.discounted_price)
                 .unwrap_or_else(|| Decimal::from(0));
 
-            let order_line = OrderLine::new(
-                built_order.id.unwrap(),
+            let applied_promotion_id = pricing_result
+                .products
+                .iter()
+                .find(|p| p.id == entry.product_id)
+                .and_then(|p| p.applied_promotion_id);
+
+            let mut order_line = OrderLine::new(
+                order_id,
                 entry.product_id,
                 Decimal::from(entry.quantity),
                 unit_price,
                 discount_amount * Decimal::from(entry.quantity), // Total discount for this line
             );
+            if let Some(product) = products.get(&entry.product_id) {
+                order_line =
+                    order_line.with_product_snapshot(product, Tax::RATE, applied_promotion_id);
+            }
             order_lines.push(order_line);
         }
     }
 
-    // Step 6: Create order and order lines in a single transaction
-    let (created_order, _created_order_lines) = match create_order_with_lines(
-        &built_order,
-        &order_lines,
-    )
-    .await
-    {
+    // Step 6: Create order and order lines in the same transaction as the reservations
+    let created = match insert_order_with_lines(&mut tx, &built_order, &order_lines).await {
+        Ok(created) => tx.commit().await.map(|_| created),
+        Err(db_error) => Err(db_error),
+    };
+    let (created_order, _created_order_lines) = match created {
         Ok(result) => result,
         Err(db_error) => {
-            // Rollback: Release reserved inventory
-            let inventory_updates: Vec<InventoryUpdate> = reservations
-                .into_iter()
-                .map(|r| InventoryUpdate {
-                    product_id: r.product_id,
-                    quantity_change: r.quantity_to_reserve,
-                })
-                .collect();
-
-            if let Err(rollback_err) =
-                InventoryService::release_reservations(&inventory_updates).await
-            {
-                return AppResponse::Error(AppError::DatabaseError(format!(
-                    "Failed to create order AND failed to rollback inventory reservations: Order error: {}, Rollback error: {}",
-                    db_error, rollback_err
-                )));
-            }
-
+            // Dropping the transaction rolls back the inventory reservations
+            release_quote(quote_id).await;
             return AppResponse::Error(AppError::DatabaseError(format!(
                 "Failed to create order: {}",
                 db_error
@@ -184,6 +281,28 @@ pub async fn order(
         }
     };
 
+    if let (Some(quote_id), Some(order_id)) = (quote_id, created_order.id)
+        && let Err(err) = QuoteService::link_order(quote_id, order_id).await
+    {
+        tracing::warn!(
+            "Failed to link quote {} to order {}: {}",
+            quote_id,
+            order_id,
+            err
+        );
+    }
+
+    // Attribute the order to the abandoned cart reminders it followed up on
+    if let Some(order_id) = created_order.id
+        && let Err(err) = AbandonedCartService::mark_recovered(user_id, order_id).await
+    {
+        tracing::warn!(
+            "Failed to track cart recovery for order {}: {}",
+            order_id,
+            err
+        );
+    }
+
     // Order placed successfully!
     // - Inventory is reserved (quantity_reserved increased)
     // - Items remain in warehouse (quantity_on_hand unchanged)
@@ -192,6 +311,51 @@ pub async fn order(
     AppResponse::Success(created_order)
 }
 
+/// Make a claimed quote usable again when placing the order failed
+async fn release_quote(quote_id: Option<Uuid>) {
+    if let Some(quote_id) = quote_id
+        && let Err(err) = QuoteService::release_quote(quote_id).await
+    {
+        tracing::warn!("Failed to release quote {}: {}", quote_id, err);
+    }
+}
+
+/// Lock the prices of a basket for checkout
+/// The returned quote ID can be sent along with the order to honour these prices,
+/// even when a promotion ends in the meantime
+pub async fn create_order_quote(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(payload): Json<QuoteRequest>,
+) -> ApiResponse<QuoteResponse> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    let has_items = payload
+        .items
+        .iter()
+        .any(|content| !content.product.is_empty());
+    if !has_items {
+        return AppResponse::Error(AppError::ValidationError(
+            "At least one item is required for a quote".to_string(),
+        ));
+    }
+
+    if payload
+        .items
+        .iter()
+        .flat_map(|content| content.product.iter())
+        .any(|entry| entry.quantity <= 0)
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "All item quantities must be greater than 0".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(QuoteService::create_quote(user_id, &payload.items).await)
+}
+
 /// Alternative endpoint for getting pricing information without creating an order
 /// Useful for cart calculations and price previews
 pub async fn calculate_order_pricing(
@@ -211,6 +375,8 @@ pub async fn calculate_order_pricing(
         shipping_address: payload.shipping_address,
         billing_address: payload.billing_address,
         notes: payload.notes,
+        delivery_date: payload.delivery_date,
+        pickup_location_id: payload.pickup_location_id,
     };
 
     PricingService::calculate_discounted_pricing(&incoming_order).await
@@ -235,6 +401,8 @@ pub async fn validate_order_pricing(
         shipping_address: payload.shipping_address,
         billing_address: payload.billing_address,
         notes: payload.notes,
+        delivery_date: payload.delivery_date,
+        pickup_location_id: payload.pickup_location_id,
     };
 
     PricingService::validate_order_pricing(&incoming_order).await
@@ -298,8 +466,21 @@ pub async fn cancel_order(
         })
         .collect();
 
+    // Orders placed before locations existed were reserved at the default location
+    let location_id =
+        match StockLocationService::or_default(order_with_lines.order.stock_location_id).await {
+            Ok(location_id) => location_id,
+            Err(err) => return AppResponse::Error(err),
+        };
+
     // Release the reserved inventory
-    if let Err(err) = InventoryService::release_reservations(&inventory_updates).await {
+    if let Err(err) = InventoryService::release_reservations(
+        &inventory_updates,
+        location_id,
+        &MovementContext::for_order(order_id, user_id).with_note("Cancelled by customer"),
+    )
+    .await
+    {
         return AppResponse::Error(AppError::DatabaseError(format!(
             "Failed to cancel order {}: {}",
             order_id, err
@@ -414,7 +595,19 @@ pub async fn ship_order(
         .collect();
 
     // Fulfill the order (decrease both on_hand and reserved)
-    if let Err(err) = InventoryService::fulfill_order(&inventory_updates).await {
+    let stock_context = MovementContext {
+        order_id: Some(order_id),
+        user_id: auth_user.user_uuid().ok(), // The admin shipping the order
+        note: None,
+    };
+    let location_id =
+        match StockLocationService::or_default(order_with_lines.order.stock_location_id).await {
+            Ok(location_id) => location_id,
+            Err(err) => return AppResponse::Error(err),
+        };
+    if let Err(err) =
+        InventoryService::fulfill_order(&inventory_updates, location_id, &stock_context).await
+    {
         return AppResponse::Error(AppError::DatabaseError(format!(
             "Failed to ship order {}: {}",
             order_id, err
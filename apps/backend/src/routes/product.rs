@@ -0,0 +1,114 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::{PriceHistoryService, ProductService};
+use crate::structs::locale::Locale;
+use crate::structs::price_history::{
+    PriceHistory, ReferencePrice, SchedulePriceChangeRequest, ScheduledPriceChange,
+};
+use crate::structs::product::{
+    CreateProductRequest, Product, ProductTranslation, ProductTranslationRequest,
+    UpdateProductRequest,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path},
+};
+use uuid::Uuid;
+
+/// GET /admin/products/:id - A product, also when it is deactivated
+pub async fn get_product(Path(product_id): Path<Uuid>) -> ApiResponse<Product> {
+    AppResponse::from_result(ProductService::get_admin_product(product_id).await)
+}
+
+/// POST /admin/products - Add a product to the catalogue
+pub async fn create_product(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateProductRequest>,
+) -> ApiResponse<Product> {
+    let admin_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(ProductService::create_product(&request, admin_id).await)
+}
+
+/// PATCH /admin/products/:id - Update a product
+pub async fn update_product(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<UpdateProductRequest>,
+) -> ApiResponse<Product> {
+    let admin_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(ProductService::update_product(product_id, &request, admin_id).await)
+}
+
+/// POST /admin/products/:id/deactivate - Hide a product from the shop
+pub async fn deactivate_product(Path(product_id): Path<Uuid>) -> ApiResponse<Product> {
+    AppResponse::from_result(ProductService::set_active(product_id, false).await)
+}
+
+/// POST /admin/products/:id/reactivate - Show a deactivated product in the shop again
+pub async fn reactivate_product(Path(product_id): Path<Uuid>) -> ApiResponse<Product> {
+    AppResponse::from_result(ProductService::set_active(product_id, true).await)
+}
+
+/// GET /admin/products/:id/translations - The product's content in other locales
+pub async fn get_translations(
+    Path(product_id): Path<Uuid>,
+) -> ApiResponse<Vec<ProductTranslation>> {
+    AppResponse::from_result(ProductService::list_translations(product_id).await)
+}
+
+/// PUT /admin/products/:id/translations/:locale - Add or replace a translation
+pub async fn set_translation(
+    Path((product_id, locale)): Path<(Uuid, Locale)>,
+    Json(request): Json<ProductTranslationRequest>,
+) -> ApiResponse<ProductTranslation> {
+    AppResponse::from_result(ProductService::set_translation(product_id, locale, &request).await)
+}
+
+/// DELETE /admin/products/:id/translations/:locale - Remove a translation
+pub async fn delete_translation(
+    Path((product_id, locale)): Path<(Uuid, Locale)>,
+) -> ApiResponse<()> {
+    AppResponse::from_result(ProductService::delete_translation(product_id, locale).await)
+}
+
+/// GET /admin/products/:id/price-history - Price changes with who made them, and upcoming ones
+pub async fn get_price_history(Path(product_id): Path<Uuid>) -> ApiResponse<PriceHistory> {
+    AppResponse::from_result(PriceHistoryService::price_history(product_id).await)
+}
+
+/// POST /admin/products/:id/price-changes - Schedule a new list price
+pub async fn schedule_price_change(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<SchedulePriceChangeRequest>,
+) -> ApiResponse<ScheduledPriceChange> {
+    let admin_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(
+        PriceHistoryService::schedule_change(product_id, &request, admin_id).await,
+    )
+}
+
+/// DELETE /admin/products/:id/price-changes/:change_id - Cancel a scheduled price change
+pub async fn cancel_price_change(
+    Path((product_id, change_id)): Path<(Uuid, Uuid)>,
+) -> ApiResponse<ScheduledPriceChange> {
+    AppResponse::from_result(PriceHistoryService::cancel_change(product_id, change_id).await)
+}
+
+/// GET /products/:id/reference-price - The lowest recent price, shown as the "was" price
+/// next to a promotion
+pub async fn get_reference_price(Path(product_id): Path<Uuid>) -> ApiResponse<ReferencePrice> {
+    AppResponse::from_result(PriceHistoryService::reference_price(product_id).await)
+}
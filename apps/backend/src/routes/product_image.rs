@@ -0,0 +1,90 @@
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::ProductImageService;
+use crate::structs::product::ProductImage;
+use crate::structs::product_image::{ReorderImagesRequest, UpdateImageRequest};
+use axum::{
+    Json,
+    extract::{Multipart, Path},
+};
+use uuid::Uuid;
+
+/// GET /admin/products/:id/images - The images of a product in display order
+pub async fn get_images(Path(product_id): Path<Uuid>) -> ApiResponse<Vec<ProductImage>> {
+    AppResponse::from_result(ProductImageService::list_images(product_id).await)
+}
+
+/// POST /admin/products/:id/images - Upload an image as multipart form data with a `file`
+/// field and optional `alt_text` and `is_primary` fields
+pub async fn upload_image(
+    Path(product_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> ApiResponse<ProductImage> {
+    let mut file = None;
+    let mut alt_text = None;
+    let mut is_primary = false;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                return AppResponse::Error(AppError::BadRequest(format!("Invalid upload: {}", e)));
+            }
+        };
+
+        let name = field.name().unwrap_or_default().to_string();
+        let result = match name.as_str() {
+            "file" => field.bytes().await.map(|bytes| file = Some(bytes.to_vec())),
+            "alt_text" => field.text().await.map(|text| alt_text = Some(text)),
+            "is_primary" => field
+                .text()
+                .await
+                .map(|text| is_primary = matches!(text.trim(), "true" | "1" | "on")),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            return AppResponse::Error(AppError::BadRequest(format!("Invalid upload: {}", e)));
+        }
+    }
+
+    let Some(file) = file else {
+        return AppResponse::Error(AppError::ValidationError(
+            "An image file is required".to_string(),
+        ));
+    };
+
+    AppResponse::from_result(
+        ProductImageService::upload_image(product_id, file, alt_text, is_primary).await,
+    )
+}
+
+/// PATCH /admin/products/:id/images/:image_id - Change the alt text or make the image primary
+pub async fn update_image(
+    Path((product_id, image_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateImageRequest>,
+) -> ApiResponse<ProductImage> {
+    AppResponse::from_result(
+        ProductImageService::update_image(product_id, image_id, &request).await,
+    )
+}
+
+/// PUT /admin/products/:id/images/order - Put the images in a new order
+pub async fn reorder_images(
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ReorderImagesRequest>,
+) -> ApiResponse<Vec<ProductImage>> {
+    AppResponse::from_result(ProductImageService::reorder_images(product_id, &request).await)
+}
+
+/// DELETE /admin/products/:id/images/:image_id - Remove an image and its stored files
+pub async fn delete_image(Path((product_id, image_id)): Path<(Uuid, Uuid)>) -> ApiResponse<()> {
+    match ProductImageService::delete_image(product_id, image_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// DELETE /admin/products/:id/images - Remove all images of a product and their stored files
+pub async fn delete_all_images(Path(product_id): Path<Uuid>) -> ApiResponse<u64> {
+    AppResponse::from_result(ProductImageService::delete_all_images(product_id).await)
+}
@@ -0,0 +1,151 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::{PurchaseOrderService, SupplierService};
+use crate::structs::purchase_order::{
+    CreateSupplierRequest, PurchaseOrder, PurchaseOrderQuery, PurchaseOrderRequest,
+    PurchaseOrderWithLines, ReceivePurchaseOrderRequest, SuggestedPurchase, SuggestedPurchaseQuery,
+    Supplier, SupplierQuery, UpdateSupplierRequest,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query},
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+const DEFAULT_SUGGESTION_WINDOW_DAYS: i32 = 7;
+const MAX_SUGGESTION_WINDOW_DAYS: i32 = 60;
+
+/// GET /admin/suppliers - Suppliers by name
+pub async fn get_suppliers(Query(query): Query<SupplierQuery>) -> ApiResponse<Vec<Supplier>> {
+    AppResponse::from_result(
+        SupplierService::list_suppliers(query.include_inactive.unwrap_or(false)).await,
+    )
+}
+
+/// GET /admin/suppliers/:id - A single supplier
+pub async fn get_supplier(Path(supplier_id): Path<Uuid>) -> ApiResponse<Supplier> {
+    AppResponse::from_result(SupplierService::get_supplier(supplier_id).await)
+}
+
+/// POST /admin/suppliers - Add a supplier
+pub async fn create_supplier(Json(request): Json<CreateSupplierRequest>) -> ApiResponse<Supplier> {
+    if request.name.trim().is_empty() {
+        return AppResponse::Error(AppError::ValidationError(
+            "Supplier name is required".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(SupplierService::create_supplier(&request).await)
+}
+
+/// PATCH /admin/suppliers/:id - Update a supplier, or deactivate it with `is_active: false`
+pub async fn update_supplier(
+    Path(supplier_id): Path<Uuid>,
+    Json(request): Json<UpdateSupplierRequest>,
+) -> ApiResponse<Supplier> {
+    if request
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Supplier name cannot be empty".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(SupplierService::update_supplier(supplier_id, &request).await)
+}
+
+/// GET /admin/purchase-orders?status= - Purchase orders, newest first
+pub async fn get_purchase_orders(
+    Query(query): Query<PurchaseOrderQuery>,
+) -> ApiResponse<Vec<PurchaseOrder>> {
+    AppResponse::from_result(PurchaseOrderService::list_purchase_orders(query.status).await)
+}
+
+/// GET /admin/purchase-orders/:id - A purchase order with its lines
+pub async fn get_purchase_order(
+    Path(purchase_order_id): Path<Uuid>,
+) -> ApiResponse<PurchaseOrderWithLines> {
+    AppResponse::from_result(PurchaseOrderService::get_purchase_order(purchase_order_id).await)
+}
+
+/// POST /admin/purchase-orders - Create a draft purchase order
+pub async fn create_purchase_order(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<PurchaseOrderRequest>,
+) -> ApiResponse<PurchaseOrderWithLines> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(PurchaseOrderService::create_purchase_order(&request, user_id).await)
+}
+
+/// PUT /admin/purchase-orders/:id - Replace the contents of a draft
+pub async fn update_purchase_order(
+    Path(purchase_order_id): Path<Uuid>,
+    Json(request): Json<PurchaseOrderRequest>,
+) -> ApiResponse<PurchaseOrderWithLines> {
+    AppResponse::from_result(PurchaseOrderService::update_draft(purchase_order_id, &request).await)
+}
+
+/// DELETE /admin/purchase-orders/:id - Delete a draft
+pub async fn delete_purchase_order(Path(purchase_order_id): Path<Uuid>) -> ApiResponse<()> {
+    match PurchaseOrderService::delete_draft(purchase_order_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /admin/purchase-orders/:id/send - Mark a draft as sent to the supplier
+pub async fn send_purchase_order(
+    Path(purchase_order_id): Path<Uuid>,
+) -> ApiResponse<PurchaseOrderWithLines> {
+    AppResponse::from_result(PurchaseOrderService::mark_sent(purchase_order_id).await)
+}
+
+/// POST /admin/purchase-orders/:id/receive - Book a (partial) delivery and add it to the stock
+pub async fn receive_purchase_order(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(purchase_order_id): Path<Uuid>,
+    Json(request): Json<ReceivePurchaseOrderRequest>,
+) -> ApiResponse<PurchaseOrderWithLines> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    let today = Utc::now().date_naive();
+    if request
+        .lines
+        .iter()
+        .any(|line| line.expires_on.is_some_and(|expires_on| expires_on < today))
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Expiry date cannot be in the past".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(
+        PurchaseOrderService::receive(purchase_order_id, &request, user_id).await,
+    )
+}
+
+/// GET /admin/purchase-orders/suggestions?days= - What to buy, based on low stock and the
+/// orders to deliver within `days`
+pub async fn get_suggested_purchases(
+    Query(query): Query<SuggestedPurchaseQuery>,
+) -> ApiResponse<Vec<SuggestedPurchase>> {
+    let days = query.days.unwrap_or(DEFAULT_SUGGESTION_WINDOW_DAYS);
+    if !(0..=MAX_SUGGESTION_WINDOW_DAYS).contains(&days) {
+        return AppResponse::Error(AppError::ValidationError(format!(
+            "Days must be between 0 and {}",
+            MAX_SUGGESTION_WINDOW_DAYS
+        )));
+    }
+
+    AppResponse::from_result(PurchaseOrderService::suggested_purchases(days).await)
+}
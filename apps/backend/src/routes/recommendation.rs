@@ -0,0 +1,81 @@
+use crate::actions::get::product::localize_products;
+use crate::middleware::RequestLocale;
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::RecommendationService;
+use crate::structs::locale::Locale;
+use crate::structs::product::Product;
+use crate::structs::recommendation::{CartRecommendationsRequest, RecommendationQuery};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query},
+};
+use uuid::Uuid;
+
+/// GET /products/:id/related - Products often bought with this one, or similar ones
+pub async fn get_related_products(
+    Path(product_id): Path<Uuid>,
+    Query(query): Query<RecommendationQuery>,
+    RequestLocale(locale): RequestLocale,
+) -> ApiResponse<Vec<Product>> {
+    localized(
+        RecommendationService::related_to_product(product_id, query.limit()).await,
+        locale,
+    )
+    .await
+}
+
+/// POST /cart/recommendations - Suggestions for a guest cart, given its products
+pub async fn get_guest_cart_recommendations(
+    Query(query): Query<RecommendationQuery>,
+    RequestLocale(locale): RequestLocale,
+    Json(request): Json<CartRecommendationsRequest>,
+) -> ApiResponse<Vec<Product>> {
+    if let Err(e) = request.validate() {
+        return AppResponse::Error(AppError::ValidationError(e));
+    }
+    localized(
+        RecommendationService::for_cart(&request.product_ids, query.limit()).await,
+        locale,
+    )
+    .await
+}
+
+/// GET /api/cart/recommendations - Suggestions for the customer's cart
+pub async fn get_cart_recommendations(
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<RecommendationQuery>,
+    RequestLocale(locale): RequestLocale,
+) -> ApiResponse<Vec<Product>> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+    localized(
+        RecommendationService::for_user_cart(user_id, query.limit()).await,
+        locale,
+    )
+    .await
+}
+
+/// POST /admin/products/related/recompute - Recompute the bought-together products now
+pub async fn recompute_related_products() -> ApiResponse<u64> {
+    AppResponse::from_result(RecommendationService::recompute_related().await)
+}
+
+async fn localized(
+    products: Result<Vec<Product>, AppError>,
+    locale: Locale,
+) -> ApiResponse<Vec<Product>> {
+    let mut products = match products {
+        Ok(products) => products,
+        Err(e) => return AppResponse::Error(e),
+    };
+    match localize_products(&mut products, locale).await {
+        Ok(()) => AppResponse::Success(products),
+        Err(e) => AppResponse::Error(AppError::DatabaseError(format!(
+            "Failed to translate products: {}",
+            e
+        ))),
+    }
+}
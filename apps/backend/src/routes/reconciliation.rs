@@ -0,0 +1,45 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::ReconciliationService;
+use crate::structs::reconciliation::{
+    OrderTotalsFlag, ReconciliationQuery, ReconciliationReport, ReviewFlagRequest,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query},
+};
+use uuid::Uuid;
+
+/// POST /admin/orders/reconciliation - Recompute all order totals now
+pub async fn run_reconciliation() -> ApiResponse<ReconciliationReport> {
+    AppResponse::from_result(ReconciliationService::reconcile_orders().await)
+}
+
+/// GET /admin/orders/reconciliation - List orders with mismatching totals
+pub async fn get_reconciliation_flags(
+    Query(query): Query<ReconciliationQuery>,
+) -> ApiResponse<Vec<OrderTotalsFlag>> {
+    let include_reviewed = query.include_reviewed.unwrap_or(false);
+    AppResponse::from_result(ReconciliationService::list_flags(include_reviewed).await)
+}
+
+/// POST /admin/orders/reconciliation/:order_id/review - Mark a flagged order as reviewed
+pub async fn review_reconciliation_flag(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(order_id): Path<Uuid>,
+    Json(request): Json<ReviewFlagRequest>,
+) -> ApiResponse<OrderTotalsFlag> {
+    let reviewer_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    let note = request
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    AppResponse::from_result(
+        ReconciliationService::mark_reviewed(order_id, reviewer_id, note).await,
+    )
+}
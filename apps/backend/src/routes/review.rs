@@ -0,0 +1,72 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::ReviewService;
+use crate::structs::review::{
+    ModerateReviewRequest, ModerationQueueItem, ProductReviews, ProductReviewsQuery, Review,
+    ReviewQueueQuery, ReviewStatus, SubmitReviewRequest,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query},
+};
+use uuid::Uuid;
+
+/// GET /products/:id/reviews - Approved reviews and the average rating of a product
+pub async fn get_product_reviews(
+    Path(product_id): Path<Uuid>,
+    Query(query): Query<ProductReviewsQuery>,
+) -> ApiResponse<ProductReviews> {
+    AppResponse::from_result(
+        ReviewService::product_reviews(product_id, query.limit, query.offset).await,
+    )
+}
+
+/// POST /api/products/:id/reviews - Review a product from a delivered order
+pub async fn submit_review(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<SubmitReviewRequest>,
+) -> ApiResponse<Review> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(ReviewService::submit_review(user_id, product_id, &request).await)
+}
+
+/// GET /api/reviews - The current user's reviews and their moderation status
+pub async fn get_my_reviews(Extension(auth_user): Extension<AuthUser>) -> ApiResponse<Vec<Review>> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(ReviewService::reviews_for_user(user_id).await)
+}
+
+/// GET /admin/reviews - The moderation queue, pending reviews unless another status is asked
+pub async fn get_review_queue(
+    Query(query): Query<ReviewQueueQuery>,
+) -> ApiResponse<Vec<ModerationQueueItem>> {
+    AppResponse::from_result(
+        ReviewService::moderation_queue(query.status.unwrap_or(ReviewStatus::Pending), query.limit)
+            .await,
+    )
+}
+
+/// POST /admin/reviews/:id/moderate - Approve or reject a review
+pub async fn moderate_review(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(review_id): Path<Uuid>,
+    Json(request): Json<ModerateReviewRequest>,
+) -> ApiResponse<Review> {
+    let moderator_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    AppResponse::from_result(
+        ReviewService::moderate_review(review_id, moderator_id, &request).await,
+    )
+}
@@ -0,0 +1,56 @@
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::StockLocationService;
+use crate::structs::stock_location::{
+    CreateStockLocationRequest, PickupPoint, StockLocation, StockLocationQuery,
+    UpdateStockLocationRequest,
+};
+use axum::{
+    Json,
+    extract::{Path, Query},
+};
+use uuid::Uuid;
+
+/// GET /locations/pickup-points - Where customers can collect their order
+pub async fn get_pickup_points() -> ApiResponse<Vec<PickupPoint>> {
+    AppResponse::from_result(StockLocationService::list_pickup_points().await)
+}
+
+/// GET /admin/locations - Stock locations by priority
+pub async fn get_locations(
+    Query(query): Query<StockLocationQuery>,
+) -> ApiResponse<Vec<StockLocation>> {
+    AppResponse::from_result(
+        StockLocationService::list_locations(query.include_inactive.unwrap_or(false)).await,
+    )
+}
+
+/// POST /admin/locations - Add a stock location
+pub async fn create_location(
+    Json(request): Json<CreateStockLocationRequest>,
+) -> ApiResponse<StockLocation> {
+    if request.name.trim().is_empty() {
+        return AppResponse::Error(AppError::ValidationError(
+            "Location name is required".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(StockLocationService::create_location(&request).await)
+}
+
+/// PATCH /admin/locations/:id - Update a location, or deactivate it with `is_active: false`
+pub async fn update_location(
+    Path(location_id): Path<Uuid>,
+    Json(request): Json<UpdateStockLocationRequest>,
+) -> ApiResponse<StockLocation> {
+    if request
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return AppResponse::Error(AppError::ValidationError(
+            "Location name cannot be empty".to_string(),
+        ));
+    }
+
+    AppResponse::from_result(StockLocationService::update_location(location_id, &request).await)
+}
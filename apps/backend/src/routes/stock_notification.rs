@@ -0,0 +1,51 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::BackInStockService;
+use crate::structs::stock_notification::{
+    NotifyMeRequest, NotifyMeResponse, ProductSubscriberCount,
+};
+use crate::validate::validate_email;
+use axum::{
+    Json,
+    extract::{Extension, Path},
+};
+use uuid::Uuid;
+
+/// POST /products/:id/notify-me - Get an email when a sold-out product is back in stock.
+/// Logged-in users are subscribed with their account address, guests have to confirm theirs.
+pub async fn notify_me(
+    auth_user: Option<Extension<AuthUser>>,
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<NotifyMeRequest>,
+) -> ApiResponse<NotifyMeResponse> {
+    let (user_id, email) = match auth_user {
+        Some(Extension(auth_user)) => match auth_user.user_uuid() {
+            Ok(id) => (Some(id), auth_user.email()),
+            Err(e) => return AppResponse::Error(e),
+        },
+        None => match request.email {
+            Some(email) => (None, email.trim().to_string()),
+            None => {
+                return AppResponse::Error(AppError::ValidationError(
+                    "Email is required when not logged in".to_string(),
+                ));
+            }
+        },
+    };
+
+    if let Err(e) = validate_email(&email) {
+        return AppResponse::Error(AppError::ValidationError(e));
+    }
+
+    AppResponse::from_result(BackInStockService::subscribe(product_id, user_id, &email).await)
+}
+
+/// POST /products/notify-me/verify/:token - Confirm a guest back-in-stock subscription
+pub async fn verify_notify_me(Path(token): Path<String>) -> ApiResponse<NotifyMeResponse> {
+    AppResponse::from_result(BackInStockService::verify(&token).await)
+}
+
+/// GET /admin/products/notify-me - Open back-in-stock subscriptions per product
+pub async fn get_subscriber_counts() -> ApiResponse<Vec<ProductSubscriberCount>> {
+    AppResponse::from_result(BackInStockService::subscriber_counts().await)
+}
@@ -0,0 +1,119 @@
+use crate::actions::get::search::{SearchParams, search_products};
+use crate::middleware::RequestLocale;
+use crate::response::{ApiResponse, AppResponse};
+use crate::services::TaxonomyService;
+use crate::services::search::SearchResult;
+use crate::structs::taxonomy::{
+    Category, CategoryNode, CategoryQuery, CategoryRequest, Occasion, OccasionRequest,
+    ProductCategoriesRequest, ProductOccasionsRequest, ProductTaxonomy,
+};
+use axum::{
+    Json,
+    extract::{Path, Query},
+};
+use uuid::Uuid;
+
+/// GET /categories - Active categories as a tree
+pub async fn get_category_tree() -> ApiResponse<Vec<CategoryNode>> {
+    AppResponse::from_result(TaxonomyService::category_tree(false).await)
+}
+
+/// GET /categories/:id/products - Products in a category or any of its subcategories.
+/// Accepts the same filters, sorting and paging as the product search.
+pub async fn get_category_products(
+    Path(category_id): Path<Uuid>,
+    Query(mut params): Query<SearchParams>,
+    locale: RequestLocale,
+) -> ApiResponse<SearchResult> {
+    params.category_id = Some(category_id);
+    search_products(Query(params), locale).await
+}
+
+/// GET /occasions - All occasions
+pub async fn get_occasions() -> ApiResponse<Vec<Occasion>> {
+    AppResponse::from_result(TaxonomyService::list_occasions().await)
+}
+
+/// GET /occasions/:id/products - Products tagged with an occasion
+pub async fn get_occasion_products(
+    Path(occasion_id): Path<Uuid>,
+    Query(mut params): Query<SearchParams>,
+    locale: RequestLocale,
+) -> ApiResponse<SearchResult> {
+    params.occasion_id = Some(occasion_id);
+    search_products(Query(params), locale).await
+}
+
+/// GET /admin/categories - Categories as a flat list, optionally including inactive ones
+pub async fn get_categories(Query(query): Query<CategoryQuery>) -> ApiResponse<Vec<Category>> {
+    AppResponse::from_result(
+        TaxonomyService::list_categories(query.include_inactive.unwrap_or(false)).await,
+    )
+}
+
+/// POST /admin/categories - Add a category
+pub async fn create_category(Json(request): Json<CategoryRequest>) -> ApiResponse<Category> {
+    AppResponse::from_result(TaxonomyService::create_category(&request).await)
+}
+
+/// PUT /admin/categories/:id - Replace a category, e.g. to move it under another parent
+pub async fn update_category(
+    Path(category_id): Path<Uuid>,
+    Json(request): Json<CategoryRequest>,
+) -> ApiResponse<Category> {
+    AppResponse::from_result(TaxonomyService::update_category(category_id, &request).await)
+}
+
+/// DELETE /admin/categories/:id - Remove a category without subcategories
+pub async fn delete_category(Path(category_id): Path<Uuid>) -> ApiResponse<()> {
+    match TaxonomyService::delete_category(category_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /admin/occasions - Add an occasion
+pub async fn create_occasion(Json(request): Json<OccasionRequest>) -> ApiResponse<Occasion> {
+    AppResponse::from_result(TaxonomyService::create_occasion(&request).await)
+}
+
+/// PUT /admin/occasions/:id - Rename an occasion
+pub async fn update_occasion(
+    Path(occasion_id): Path<Uuid>,
+    Json(request): Json<OccasionRequest>,
+) -> ApiResponse<Occasion> {
+    AppResponse::from_result(TaxonomyService::rename_occasion(occasion_id, &request).await)
+}
+
+/// DELETE /admin/occasions/:id - Remove an occasion and untag its products
+pub async fn delete_occasion(Path(occasion_id): Path<Uuid>) -> ApiResponse<()> {
+    match TaxonomyService::delete_occasion(occasion_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// GET /admin/products/:id/taxonomy - The categories and occasions of a product
+pub async fn get_product_taxonomy(Path(product_id): Path<Uuid>) -> ApiResponse<ProductTaxonomy> {
+    AppResponse::from_result(TaxonomyService::product_taxonomy(product_id).await)
+}
+
+/// PUT /admin/products/:id/categories - Replace the categories of a product
+pub async fn set_product_categories(
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ProductCategoriesRequest>,
+) -> ApiResponse<ProductTaxonomy> {
+    AppResponse::from_result(
+        TaxonomyService::set_product_categories(product_id, &request.category_ids).await,
+    )
+}
+
+/// PUT /admin/products/:id/occasions - Replace the occasions of a product
+pub async fn set_product_occasions(
+    Path(product_id): Path<Uuid>,
+    Json(request): Json<ProductOccasionsRequest>,
+) -> ApiResponse<ProductTaxonomy> {
+    AppResponse::from_result(
+        TaxonomyService::set_product_occasions(product_id, &request.occasion_ids).await,
+    )
+}
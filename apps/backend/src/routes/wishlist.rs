@@ -0,0 +1,229 @@
+use crate::middleware::auth::AuthUser;
+use crate::response::{ApiResponse, AppResponse, error::AppError};
+use crate::services::WishlistService;
+use crate::structs::cart::CartItemWithProduct;
+use crate::structs::wishlist::{
+    AddWishlistItemRequest, CreateWishlistRequest, SharedWishlistResponse, UpdateWishlistRequest,
+    Wishlist, WishlistResponse, WishlistShareLink, WishlistSummary,
+};
+use axum::{
+    Json,
+    extract::{Extension, Path},
+};
+use uuid::Uuid;
+
+const MAX_WISHLIST_NAME_LENGTH: usize = 100;
+
+fn validate_wishlist_name(name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError(
+            "Wishlist name is required".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_WISHLIST_NAME_LENGTH {
+        return Err(AppError::ValidationError(format!(
+            "Wishlist name cannot be longer than {} characters",
+            MAX_WISHLIST_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// GET /api/wishlists - List the current user's wishlists
+pub async fn get_wishlists(
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResponse<Vec<WishlistSummary>> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::list_wishlists(user_id).await {
+        Ok(wishlists) => AppResponse::Success(wishlists),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /api/wishlists - Create a named wishlist
+pub async fn create_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateWishlistRequest>,
+) -> ApiResponse<Wishlist> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if let Err(e) = validate_wishlist_name(&request.name) {
+        return AppResponse::Error(e);
+    }
+
+    match WishlistService::create_wishlist(user_id, request).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// GET /api/wishlists/:id - Get a wishlist with its items and price-drop flags
+pub async fn get_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+) -> ApiResponse<WishlistResponse> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::get_wishlist(user_id, wishlist_id).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// PATCH /api/wishlists/:id - Rename a wishlist or change its occasion date
+pub async fn update_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+    Json(request): Json<UpdateWishlistRequest>,
+) -> ApiResponse<Wishlist> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if let Some(name) = &request.name
+        && let Err(e) = validate_wishlist_name(name)
+    {
+        return AppResponse::Error(e);
+    }
+
+    match WishlistService::update_wishlist(user_id, wishlist_id, request).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// DELETE /api/wishlists/:id - Delete a wishlist
+pub async fn delete_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+) -> ApiResponse<()> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::delete_wishlist(user_id, wishlist_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /api/wishlists/:id/items - Add a product to a wishlist
+pub async fn add_wishlist_item(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+    Json(request): Json<AddWishlistItemRequest>,
+) -> ApiResponse<WishlistResponse> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    if request.quantity.is_some_and(|quantity| quantity <= 0) {
+        return AppResponse::Error(AppError::ValidationError(
+            "Quantity must be greater than 0".to_string(),
+        ));
+    }
+
+    match WishlistService::add_item(user_id, wishlist_id, request).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// DELETE /api/wishlists/:id/items/:item_id - Remove an item from a wishlist
+pub async fn remove_wishlist_item(
+    Extension(auth_user): Extension<AuthUser>,
+    Path((wishlist_id, item_id)): Path<(Uuid, Uuid)>,
+) -> ApiResponse<()> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::remove_item(user_id, wishlist_id, item_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /api/wishlists/:id/items/:item_id/move-to-cart - Move an item into the cart
+pub async fn move_wishlist_item_to_cart(
+    Extension(auth_user): Extension<AuthUser>,
+    Path((wishlist_id, item_id)): Path<(Uuid, Uuid)>,
+) -> ApiResponse<CartItemWithProduct> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    WishlistService::move_item_to_cart(user_id, wishlist_id, item_id).await
+}
+
+/// POST /api/cart/items/:item_id/save-for-later - Move a cart item to the saved for later list
+pub async fn save_cart_item_for_later(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(item_id): Path<Uuid>,
+) -> ApiResponse<WishlistResponse> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::save_cart_item_for_later(user_id, item_id).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// POST /api/wishlists/:id/share - Create a read-only share link
+pub async fn share_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+) -> ApiResponse<WishlistShareLink> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::share_wishlist(user_id, wishlist_id).await {
+        Ok(link) => AppResponse::Success(link),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// DELETE /api/wishlists/:id/share - Revoke the share link
+pub async fn unshare_wishlist(
+    Extension(auth_user): Extension<AuthUser>,
+    Path(wishlist_id): Path<Uuid>,
+) -> ApiResponse<()> {
+    let user_id = match auth_user.user_uuid() {
+        Ok(id) => id,
+        Err(e) => return AppResponse::Error(e),
+    };
+
+    match WishlistService::unshare_wishlist(user_id, wishlist_id).await {
+        Ok(()) => AppResponse::Success(()),
+        Err(e) => AppResponse::Error(e),
+    }
+}
+
+/// GET /wishlists/shared/:token - Public read-only view of a shared wishlist
+pub async fn get_shared_wishlist(Path(token): Path<String>) -> ApiResponse<SharedWishlistResponse> {
+    match WishlistService::get_shared_wishlist(&token).await {
+        Ok(wishlist) => AppResponse::Success(wishlist),
+        Err(e) => AppResponse::Error(e),
+    }
+}
@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use shuttle_runtime::SecretStore;
 use std::sync::OnceLock;
 
@@ -17,6 +18,12 @@ pub fn get_jwt_secret() -> Result<String, String> {
     get_secret("JWT_SECRET").ok_or_else(|| "JWT_SECRET not found in secrets".to_string())
 }
 
+/// Kept apart from the JWT secret, so a signed quote can never pass as an access token
+pub fn get_quote_signing_secret() -> Result<String, String> {
+    get_secret("QUOTE_SIGNING_SECRET")
+        .ok_or_else(|| "QUOTE_SIGNING_SECRET not found in secrets".to_string())
+}
+
 pub fn get_access_token_expiry() -> i64 {
     get_secret("ACCESS_TOKEN_EXPIRY")
         .and_then(|s| s.parse().ok())
@@ -28,3 +35,139 @@ pub fn get_refresh_token_expiry() -> i64 {
         .and_then(|s| s.parse().ok())
         .unwrap_or(86400) // 24 hours default
 }
+
+pub fn get_frontend_url() -> String {
+    get_secret("FRONTEND_URL").unwrap_or_else(|| "http://localhost:3000".to_string())
+}
+
+pub fn get_abandoned_cart_reminder_hours() -> i32 {
+    get_secret("ABANDONED_CART_REMINDER_HOURS")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(24) // 24 hours default
+}
+
+pub fn get_abandoned_cart_check_interval_minutes() -> u64 {
+    get_secret("ABANDONED_CART_CHECK_INTERVAL_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .filter(|minutes: &u64| *minutes > 0)
+        .unwrap_or(30) // 30 minutes default
+}
+
+pub fn get_quote_validity_minutes() -> i64 {
+    get_secret("QUOTE_VALIDITY_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(15) // 15 minutes default
+}
+
+pub fn get_order_reconciliation_interval_hours() -> u64 {
+    get_secret("ORDER_RECONCILIATION_INTERVAL_HOURS")
+        .and_then(|s| s.parse().ok())
+        .filter(|hours: &u64| *hours > 0)
+        .unwrap_or(24) // Daily default
+}
+
+pub fn get_stock_expiry_check_interval_hours() -> u64 {
+    get_secret("STOCK_EXPIRY_CHECK_INTERVAL_HOURS")
+        .and_then(|s| s.parse().ok())
+        .filter(|hours: &u64| *hours > 0)
+        .unwrap_or(24) // Daily default
+}
+
+pub fn get_stock_ledger_check_interval_hours() -> u64 {
+    get_secret("STOCK_LEDGER_CHECK_INTERVAL_HOURS")
+        .and_then(|s| s.parse().ok())
+        .filter(|hours: &u64| *hours > 0)
+        .unwrap_or(24) // Daily default
+}
+
+pub fn get_stock_notification_resend_minutes() -> i32 {
+    get_secret("STOCK_NOTIFICATION_RESEND_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(15) // A guest can ask for a new verification email every 15 minutes
+}
+
+pub fn get_low_stock_default_threshold() -> Decimal {
+    get_secret("LOW_STOCK_DEFAULT_THRESHOLD")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(Decimal::from(5)) // Used for products without their own threshold
+}
+
+pub fn get_low_stock_check_interval_minutes() -> u64 {
+    get_secret("LOW_STOCK_CHECK_INTERVAL_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .filter(|minutes: &u64| *minutes > 0)
+        .unwrap_or(60) // Hourly default
+}
+
+/// Address that receives stock alerts; alerts only go to the admin feed when unset
+pub fn get_staff_notification_email() -> Option<String> {
+    get_secret("STAFF_NOTIFICATION_EMAIL").filter(|email| !email.trim().is_empty())
+}
+
+/// Weight of the most recent day in the demand forecast, between 0 and 1
+pub fn get_forecast_smoothing_factor() -> Decimal {
+    get_secret("FORECAST_SMOOTHING_FACTOR")
+        .and_then(|s| s.parse().ok())
+        .filter(|alpha: &Decimal| *alpha > Decimal::ZERO && *alpha <= Decimal::ONE)
+        .unwrap_or(Decimal::new(3, 1)) // 0.3 default
+}
+
+pub fn get_forecast_history_days() -> i32 {
+    get_secret("FORECAST_HISTORY_DAYS")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(120) // About four months default
+}
+
+pub fn get_related_products_interval_hours() -> u64 {
+    get_secret("RELATED_PRODUCTS_INTERVAL_HOURS")
+        .and_then(|s| s.parse().ok())
+        .filter(|hours: &u64| *hours > 0)
+        .unwrap_or(24) // Daily default
+}
+
+/// How many bought-together products are kept per product
+pub fn get_related_products_limit() -> i64 {
+    get_secret("RELATED_PRODUCTS_LIMIT")
+        .and_then(|s| s.parse().ok())
+        .filter(|limit: &i64| *limit > 0)
+        .unwrap_or(10)
+}
+
+/// Orders two products must share before they count as bought together
+pub fn get_related_products_min_orders() -> i64 {
+    get_secret("RELATED_PRODUCTS_MIN_ORDERS")
+        .and_then(|s| s.parse().ok())
+        .filter(|orders: &i64| *orders > 0)
+        .unwrap_or(2)
+}
+
+pub fn get_price_change_check_interval_minutes() -> u64 {
+    get_secret("PRICE_CHANGE_CHECK_INTERVAL_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .filter(|minutes: &u64| *minutes > 0)
+        .unwrap_or(1) // Scheduled prices go live within a minute
+}
+
+/// Days over which the lowest earlier price is taken as the "was" price of a promotion
+pub fn get_reference_price_days() -> i64 {
+    get_secret("REFERENCE_PRICE_DAYS")
+        .and_then(|s| s.parse().ok())
+        .filter(|days: &i64| *days > 0)
+        .unwrap_or(30)
+}
+
+/// Directory uploaded product images are written to
+pub fn get_image_storage_dir() -> String {
+    get_secret("IMAGE_STORAGE_DIR").unwrap_or_else(|| "uploads".to_string())
+}
+
+/// URL the image storage directory is reachable under
+pub fn get_image_public_url() -> String {
+    get_secret("IMAGE_PUBLIC_URL").unwrap_or_else(|| "/media".to_string())
+}
+
+pub fn get_max_image_upload_bytes() -> usize {
+    get_secret("MAX_IMAGE_UPLOAD_BYTES")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(10 * 1024 * 1024) // 10 MB default
+}
@@ -0,0 +1,345 @@
+use crate::pool::connect::pool;
+use crate::response::{AppResponse, error::AppError};
+use crate::secrets;
+use crate::services::CartService;
+use crate::services::email_service::{EmailService, OutgoingEmail};
+use crate::structs::abandoned_cart::{AbandonedCart, AbandonedCartReminder, AbandonedCartStats};
+use crate::structs::cart::{CartResponse, GuestCartItem};
+use rust_decimal::Decimal;
+use sqlx::Row;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Service for detecting abandoned carts and following up on them
+pub struct AbandonedCartService;
+
+impl AbandonedCartService {
+    /// Find carts that have been idle for longer than the reminder delay and queue one
+    /// reminder email per cart. Returns the number of reminders queued.
+    pub async fn queue_reminders() -> Result<u64, AppError> {
+        let delay_hours = secrets::get_abandoned_cart_reminder_hours();
+        let carts = Self::find_abandoned_carts(delay_hours).await?;
+
+        let mut queued = 0;
+        for cart in carts {
+            match Self::queue_reminder(&cart).await {
+                Ok(true) => queued += 1,
+                Ok(false) => {} // Another run already picked this cart up
+                Err(e) => error!("Failed to queue reminder for cart {}: {}", cart.cart_id, e),
+            }
+        }
+
+        if queued > 0 {
+            info!("Queued {} abandoned cart reminders", queued);
+        }
+        Ok(queued)
+    }
+
+    /// Carts with items, idle for `delay_hours`, without a later order or reminder and
+    /// belonging to users that did not opt out
+    async fn find_abandoned_carts(delay_hours: i32) -> Result<Vec<AbandonedCart>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, AbandonedCart>(
+            r#"
+            WITH cart_activity AS (
+                SELECT
+                    c.id AS cart_id,
+                    c.user_id,
+                    u.email,
+                    u.first_name,
+                    GREATEST(c.updated_at, MAX(ci.updated_at)) AS last_activity
+                FROM carts c
+                JOIN users u ON u.id = c.user_id
+                JOIN cart_items ci ON ci.cart_id = c.id
+                WHERE u.cart_reminders_opt_out = false
+                GROUP BY c.id, c.user_id, u.email, u.first_name, c.updated_at
+            )
+            SELECT ca.cart_id, ca.user_id, ca.email, ca.first_name, ca.last_activity
+            FROM cart_activity ca
+            WHERE ca.last_activity < NOW() - make_interval(hours => $1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM orders o
+                  WHERE o.user_id = ca.user_id AND o.created_at >= ca.last_activity
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM abandoned_cart_reminders r
+                  WHERE r.cart_id = ca.cart_id AND r.cart_last_activity >= ca.last_activity
+              )
+            ORDER BY ca.last_activity ASC
+            "#,
+        )
+        .bind(delay_hours)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to find abandoned carts: {}", e)))
+    }
+
+    /// Record the reminder and queue its email in one transaction
+    async fn queue_reminder(cart: &AbandonedCart) -> Result<bool, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let restore_token = Uuid::new_v4().simple().to_string();
+
+        let reminder_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO abandoned_cart_reminders (cart_id, user_id, restore_token, cart_last_activity, items)
+            SELECT $1, $2, $3, $4,
+                   COALESCE(jsonb_agg(jsonb_build_object('product_id', ci.product_id, 'quantity', ci.quantity)), '[]'::jsonb)
+            FROM cart_items ci
+            WHERE ci.cart_id = $1
+            ON CONFLICT (cart_id, cart_last_activity) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(cart.cart_id)
+        .bind(cart.user_id)
+        .bind(&restore_token)
+        .bind(cart.last_activity)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record reminder: {}", e)))?;
+
+        let reminder_id = match reminder_id {
+            Some(id) => id,
+            None => {
+                tx.rollback().await.ok();
+                return Ok(false);
+            }
+        };
+
+        let base_url = secrets::get_frontend_url();
+        let email = Self::build_reminder_email(cart, &base_url, &restore_token);
+        let email_id = EmailService::queue_email_with(&mut tx, &email).await?;
+
+        sqlx::query("UPDATE abandoned_cart_reminders SET email_id = $1 WHERE id = $2")
+            .bind(email_id)
+            .bind(reminder_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to link reminder email: {}", e))
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit reminder: {}", e)))?;
+
+        Ok(true)
+    }
+
+    /// Build the reminder email with restore and unsubscribe links
+    pub fn build_reminder_email(
+        cart: &AbandonedCart,
+        base_url: &str,
+        restore_token: &str,
+    ) -> OutgoingEmail {
+        let base_url = base_url.trim_end_matches('/');
+        let restore_link = format!("{}/cart/restore?token={}", base_url, restore_token);
+        let unsubscribe_link = format!(
+            "{}/cart/reminders/unsubscribe?token={}",
+            base_url, restore_token
+        );
+
+        let greeting = if cart.first_name.trim().is_empty() {
+            "Hallo".to_string()
+        } else {
+            format!("Hallo {}", cart.first_name.trim())
+        };
+
+        OutgoingEmail {
+            recipient: cart.email.clone(),
+            subject: "Je bloemen wachten nog op je".to_string(),
+            body: format!(
+                "{},\n\nJe hebt nog bloemen in je winkelwagen staan. Via onderstaande link zet je ze direct weer klaar:\n\n{}\n\nWil je deze herinneringen niet meer ontvangen? Meld je af via:\n{}\n\nMet bloemige groet,\nMamabloemetjes",
+                greeting, restore_link, unsubscribe_link
+            ),
+            template: "abandoned_cart_reminder".to_string(),
+        }
+    }
+
+    /// Restore the items from a reminder into the user's cart.
+    /// Items that are still in the cart are left untouched.
+    pub async fn restore_cart(user_id: Uuid, token: &str) -> AppResponse<CartResponse> {
+        let reminder = match Self::get_reminder_by_token(token).await {
+            Ok(Some(reminder)) if reminder.user_id == user_id => reminder,
+            Ok(_) => {
+                return AppResponse::Error(AppError::NotFound(
+                    "Cart reminder not found".to_string(),
+                ));
+            }
+            Err(e) => return AppResponse::Error(e),
+        };
+
+        let snapshot: Vec<SnapshotItem> = match serde_json::from_value(reminder.items) {
+            Ok(items) => items,
+            Err(e) => {
+                return AppResponse::Error(AppError::InternalServerError(format!(
+                    "Failed to read cart snapshot: {}",
+                    e
+                )));
+            }
+        };
+
+        let current_cart = match CartService::get_cart_with_items(user_id).await {
+            AppResponse::Success(cart) => cart,
+            AppResponse::Error(e) => return AppResponse::Error(e),
+        };
+
+        let missing_items: Vec<GuestCartItem> = snapshot
+            .into_iter()
+            .filter(|item| {
+                !current_cart
+                    .items
+                    .iter()
+                    .any(|existing| existing.product_id == item.product_id)
+            })
+            .map(|item| GuestCartItem {
+                product_id: item.product_id,
+                quantity: item.quantity,
+            })
+            .collect();
+
+        if let Err(e) = sqlx::query(
+            "UPDATE abandoned_cart_reminders SET restored_at = COALESCE(restored_at, NOW()) WHERE id = $1",
+        )
+        .bind(reminder.id)
+        .execute(pool())
+        .await
+        {
+            warn!("Failed to mark reminder {} as restored: {}", reminder.id, e);
+        }
+
+        if missing_items.is_empty() {
+            return AppResponse::Success(current_cart);
+        }
+
+        CartService::merge_guest_cart(user_id, missing_items).await
+    }
+
+    /// Mark the open reminders of a user as recovered by the given order. Only reminders
+    /// sent before the order with at least one of the ordered products in their cart count.
+    pub async fn mark_recovered(user_id: Uuid, order_id: Uuid) -> Result<u64, AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE abandoned_cart_reminders acr
+            SET recovered_at = NOW(), recovered_order_id = o.id
+            FROM orders o
+            WHERE o.id = $2
+              AND acr.user_id = $1
+              AND acr.recovered_at IS NULL
+              AND acr.created_at <= o.created_at
+              AND EXISTS (
+                  SELECT 1
+                  FROM jsonb_array_elements(acr.items) AS item
+                  JOIN order_line ol ON ol.order_id = o.id
+                   AND ol.product_id = (item->>'product_id')::uuid
+              )
+            "#,
+        )
+        .bind(user_id)
+        .bind(order_id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to mark cart reminders recovered: {}", e))
+        })?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Opt a user in or out of cart reminders
+    pub async fn set_reminders_enabled(user_id: Uuid, enabled: bool) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("UPDATE users SET cart_reminders_opt_out = $1 WHERE id = $2")
+            .bind(!enabled)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to update reminder preference: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Opt out using the token from a reminder email, so no login is needed
+    pub async fn unsubscribe_by_token(token: &str) -> Result<(), AppError> {
+        match Self::get_reminder_by_token(token).await? {
+            Some(reminder) => Self::set_reminders_enabled(reminder.user_id, false).await,
+            None => Err(AppError::NotFound("Cart reminder not found".to_string())),
+        }
+    }
+
+    /// Reminder and recovery figures for the admin dashboard
+    pub async fn get_stats() -> Result<AbandonedCartStats, AppError> {
+        let pool = pool();
+
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS reminders_queued,
+                COUNT(r.restored_at) AS carts_restored,
+                COUNT(r.recovered_at) AS carts_recovered,
+                COALESCE(SUM(o.total_amount), 0) AS recovered_revenue
+            FROM abandoned_cart_reminders r
+            LEFT JOIN orders o ON o.id = r.recovered_order_id
+            "#,
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch cart reminder stats: {}", e))
+        })?;
+
+        let reminders_queued: i64 = row.get("reminders_queued");
+        let carts_recovered: i64 = row.get("carts_recovered");
+        let recovery_rate = if reminders_queued > 0 {
+            (Decimal::from(carts_recovered) * Decimal::from(100) / Decimal::from(reminders_queued))
+                .round_dp(2)
+        } else {
+            Decimal::ZERO
+        };
+
+        Ok(AbandonedCartStats {
+            reminders_queued,
+            carts_restored: row.get("carts_restored"),
+            carts_recovered,
+            recovery_rate,
+            recovered_revenue: row.get("recovered_revenue"),
+        })
+    }
+
+    async fn get_reminder_by_token(token: &str) -> Result<Option<AbandonedCartReminder>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, AbandonedCartReminder>(
+            r#"
+            SELECT id, cart_id, user_id, restore_token, cart_last_activity, items, email_id,
+                   created_at, restored_at, recovered_at, recovered_order_id
+            FROM abandoned_cart_reminders
+            WHERE restore_token = $1
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch cart reminder: {}", e)))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct SnapshotItem {
+    product_id: Uuid,
+    quantity: i32,
+}
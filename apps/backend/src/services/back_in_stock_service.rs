@@ -0,0 +1,315 @@
+use crate::actions::get::get_product_by_id;
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::secrets;
+use crate::services::{EmailService, InventoryService, OutgoingEmail};
+use crate::structs::stock_notification::{
+    NotifyMeResponse, ProductSubscriberCount, StockNotification,
+};
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+const NOTIFICATION_SELECT: &str = r#"
+    SELECT id, product_id, user_id, email, verification_token, verified_at,
+           verification_sent_at, created_at, notified_at
+    FROM stock_notifications
+"#;
+
+/// Service for emailing customers when a sold-out product is back in stock
+pub struct BackInStockService;
+
+impl BackInStockService {
+    /// Subscribe to a sold-out product. Logged-in users are subscribed right away with their
+    /// account address; other addresses first have to be confirmed through a verification email.
+    /// Subscribing again while unconfirmed only re-sends that email once the cooldown is over.
+    pub async fn subscribe(
+        product_id: Uuid,
+        user_id: Option<Uuid>,
+        email: &str,
+    ) -> Result<NotifyMeResponse, AppError> {
+        let product = match get_product_by_id(product_id).await {
+            Ok(Some(product)) if product.is_active => product,
+            Ok(_) => return Err(AppError::not_found("Product")),
+            Err(e) => {
+                return Err(AppError::DatabaseError(format!(
+                    "Failed to fetch product {}: {}",
+                    product_id, e
+                )));
+            }
+        };
+
+        let available = InventoryService::get_inventory(product_id)
+            .await?
+            .map(|inventory| inventory.available_quantity())
+            .unwrap_or(Decimal::ZERO);
+        if available > Decimal::ZERO {
+            return Err(AppError::ValidationError(
+                "This product is in stock and can be ordered right away".to_string(),
+            ));
+        }
+
+        let subscription = match Self::open_subscription(product_id, email).await? {
+            Some(subscription) => subscription,
+            None => {
+                let token = Uuid::new_v4().simple().to_string();
+                let inserted = sqlx::query_as::<_, StockNotification>(
+                    r#"
+                    INSERT INTO stock_notifications (
+                        product_id, user_id, email, verification_token, verified_at
+                    )
+                    VALUES ($1, $2, $3, $4, CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END)
+                    ON CONFLICT (product_id, lower(email)) WHERE notified_at IS NULL DO NOTHING
+                    RETURNING id, product_id, user_id, email, verification_token, verified_at,
+                              verification_sent_at, created_at, notified_at
+                    "#,
+                )
+                .bind(product_id)
+                .bind(user_id)
+                .bind(email)
+                .bind(&token)
+                .fetch_optional(pool())
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to subscribe: {}", e)))?;
+
+                // Nothing is inserted when the same address subscribed at the same moment
+                match inserted {
+                    Some(subscription) => subscription,
+                    None => Self::open_subscription(product_id, email)
+                        .await?
+                        .ok_or_else(|| {
+                            AppError::Conflict(
+                                "This subscription just changed, please try again".to_string(),
+                            )
+                        })?,
+                }
+            }
+        };
+
+        // Logging in proves the address, so an unconfirmed subscription is confirmed
+        let subscription = match user_id {
+            Some(_) if subscription.verified_at.is_none() => {
+                Self::confirm(&subscription.verification_token, user_id).await?
+            }
+            _ => subscription,
+        };
+
+        let verification_required = subscription.verified_at.is_none();
+        if verification_required && Self::claim_verification_email(subscription.id).await? {
+            let email = Self::build_verification_email(
+                &subscription,
+                &product.name,
+                &secrets::get_frontend_url(),
+            );
+            EmailService::queue_email(&email).await?;
+        }
+
+        Ok(NotifyMeResponse {
+            product_id,
+            email: subscription.email,
+            verification_required,
+        })
+    }
+
+    /// Confirm a guest subscription from the link in the verification email. When the
+    /// product came back in stock in the meantime, the notification goes out right away.
+    pub async fn verify(token: &str) -> Result<NotifyMeResponse, AppError> {
+        let subscription = Self::confirm(token, None).await?;
+
+        let available = InventoryService::get_inventory(subscription.product_id)
+            .await?
+            .map(|inventory| inventory.available_quantity())
+            .unwrap_or(Decimal::ZERO);
+        if available > Decimal::ZERO {
+            let pool = pool();
+            let mut tx = pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to start transaction: {}", e))
+            })?;
+            Self::notify_subscribers_with(&mut tx, subscription.product_id).await?;
+            tx.commit().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to commit stock notifications: {}", e))
+            })?;
+        }
+
+        Ok(NotifyMeResponse {
+            product_id: subscription.product_id,
+            email: subscription.email,
+            verification_required: false,
+        })
+    }
+
+    /// Email every confirmed subscriber of a product and close their subscriptions.
+    /// Runs on the connection of the stock change, so a subscriber is only marked as
+    /// notified when the email is queued.
+    pub async fn notify_subscribers_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<usize, AppError> {
+        let subscribers = sqlx::query_as::<_, (String, String)>(
+            r#"
+            UPDATE stock_notifications s
+            SET notified_at = NOW()
+            FROM products p
+            WHERE p.id = s.product_id
+            AND s.product_id = $1
+            AND s.verified_at IS NOT NULL
+            AND s.notified_at IS NULL
+            RETURNING s.email, p.name
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to close stock subscriptions: {}", e))
+        })?;
+
+        let base_url = secrets::get_frontend_url();
+        for (email, product_name) in &subscribers {
+            let email = Self::build_back_in_stock_email(email, product_name, product_id, &base_url);
+            EmailService::queue_email_with(&mut *conn, &email).await?;
+        }
+
+        if !subscribers.is_empty() {
+            info!(
+                "Queued {} back-in-stock emails for product {}",
+                subscribers.len(),
+                product_id
+            );
+        }
+        Ok(subscribers.len())
+    }
+
+    /// Open subscriptions per product, most requested first
+    pub async fn subscriber_counts() -> Result<Vec<ProductSubscriberCount>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, ProductSubscriberCount>(
+            r#"
+            SELECT
+                p.id AS product_id,
+                p.name,
+                p.sku,
+                COALESCE(i.quantity_on_hand - i.quantity_reserved, 0) AS available_quantity,
+                COUNT(*) FILTER (WHERE s.verified_at IS NOT NULL) AS subscribers,
+                COUNT(*) FILTER (WHERE s.verified_at IS NULL) AS pending_verification
+            FROM stock_notifications s
+            JOIN products p ON p.id = s.product_id
+            LEFT JOIN product_stock i ON i.product_id = s.product_id
+            WHERE s.notified_at IS NULL
+            GROUP BY p.id, p.name, p.sku, i.quantity_on_hand, i.quantity_reserved
+            ORDER BY subscribers DESC, p.name ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch subscriber counts: {}", e)))
+    }
+
+    /// The subscription of an address to a product that has not been notified yet
+    async fn open_subscription(
+        product_id: Uuid,
+        email: &str,
+    ) -> Result<Option<StockNotification>, AppError> {
+        sqlx::query_as::<_, StockNotification>(&format!(
+            "{} WHERE product_id = $1 AND lower(email) = lower($2) AND notified_at IS NULL",
+            NOTIFICATION_SELECT
+        ))
+        .bind(product_id)
+        .bind(email)
+        .fetch_optional(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch subscription: {}", e)))
+    }
+
+    /// Whether a verification email may be sent for a subscription now. Asking again
+    /// within the cooldown keeps the subscription pending without sending another one.
+    async fn claim_verification_email(subscription_id: Uuid) -> Result<bool, AppError> {
+        let pool = pool();
+
+        let claimed = sqlx::query(
+            r#"
+            UPDATE stock_notifications
+            SET verification_sent_at = NOW()
+            WHERE id = $1
+            AND (
+                verification_sent_at IS NULL
+                OR verification_sent_at < NOW() - make_interval(mins => $2)
+            )
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(secrets::get_stock_notification_resend_minutes())
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to update verification email: {}", e))
+        })?;
+
+        Ok(claimed.rows_affected() > 0)
+    }
+
+    async fn confirm(token: &str, user_id: Option<Uuid>) -> Result<StockNotification, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, StockNotification>(
+            r#"
+            UPDATE stock_notifications
+            SET verified_at = COALESCE(verified_at, NOW()), user_id = COALESCE(user_id, $2)
+            WHERE verification_token = $1 AND notified_at IS NULL
+            RETURNING id, product_id, user_id, email, verification_token, verified_at,
+                      verification_sent_at, created_at, notified_at
+            "#,
+        )
+        .bind(token)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to confirm subscription: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Subscription"))
+    }
+
+    /// Build the email asking a guest to confirm their subscription
+    pub fn build_verification_email(
+        subscription: &StockNotification,
+        product_name: &str,
+        base_url: &str,
+    ) -> OutgoingEmail {
+        let verify_link = format!(
+            "{}/products/notify-me/verify?token={}",
+            base_url.trim_end_matches('/'),
+            subscription.verification_token
+        );
+
+        OutgoingEmail {
+            recipient: subscription.email.clone(),
+            subject: format!("Bevestig je melding voor {}", product_name),
+            body: format!(
+                "Hallo,\n\nJe wilt een bericht ontvangen zodra {} weer op voorraad is. Bevestig je e-mailadres via onderstaande link:\n\n{}\n\nHeb je dit niet aangevraagd? Dan kun je deze e-mail negeren.\n\nMet bloemige groet,\nMamabloemetjes",
+                product_name, verify_link
+            ),
+            template: "back_in_stock_verification".to_string(),
+        }
+    }
+
+    /// Build the email telling a subscriber the product can be ordered again
+    pub fn build_back_in_stock_email(
+        recipient: &str,
+        product_name: &str,
+        product_id: Uuid,
+        base_url: &str,
+    ) -> OutgoingEmail {
+        let product_link = format!("{}/products/{}", base_url.trim_end_matches('/'), product_id);
+
+        OutgoingEmail {
+            recipient: recipient.to_string(),
+            subject: format!("{} is weer op voorraad", product_name),
+            body: format!(
+                "Hallo,\n\nGoed nieuws: {} is weer op voorraad. Bestel snel, want op is op:\n\n{}\n\nMet bloemige groet,\nMamabloemetjes",
+                product_name, product_link
+            ),
+            template: "back_in_stock".to_string(),
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::bill_of_materials::{
+    BouquetComponent, BouquetRecipe, ProductionBouquet, ProductionComponent, ProductionList,
+    SetRecipeRequest, expand_components,
+};
+use crate::structs::enums::ProductType;
+use crate::structs::inventory::{InventoryReservation, InventoryUpdate};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+/// Service for bouquet recipes. A bouquet with a recipe is assembled to order: its stock is
+/// derived from its components, and reserving, releasing or fulfilling it moves the stock
+/// of the components instead.
+pub struct BillOfMaterialsService;
+
+impl BillOfMaterialsService {
+    /// Get the recipe of a bouquet
+    pub async fn get_recipe(bouquet_id: Uuid) -> Result<BouquetRecipe, AppError> {
+        let pool = pool();
+
+        let name = sqlx::query_scalar::<_, String>("SELECT name FROM products WHERE id = $1")
+            .bind(bouquet_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Product"))?;
+
+        let components = sqlx::query_as::<_, BouquetComponent>(
+            r#"
+            SELECT
+                bc.component_id,
+                p.name,
+                p.sku,
+                bc.quantity,
+                COALESCE(i.quantity_on_hand - i.quantity_reserved, 0) AS available_quantity
+            FROM bouquet_components bc
+            JOIN products p ON p.id = bc.component_id
+            LEFT JOIN inventory i ON i.product_id = bc.component_id
+            WHERE bc.bouquet_id = $1
+            ORDER BY p.name ASC
+            "#,
+        )
+        .bind(bouquet_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch recipe: {}", e)))?;
+
+        Ok(BouquetRecipe {
+            bouquet_id,
+            name,
+            components,
+        })
+    }
+
+    /// Replace the recipe of a bouquet. Components have to be products that are not bouquets
+    /// themselves. The recipe cannot change while open orders contain the bouquet, since
+    /// their reservations were made with the current recipe.
+    pub async fn set_recipe(
+        bouquet_id: Uuid,
+        request: &SetRecipeRequest,
+    ) -> Result<BouquetRecipe, AppError> {
+        request
+            .validate(bouquet_id)
+            .map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let product_type = sqlx::query_scalar::<_, ProductType>(
+            "SELECT product_type FROM products WHERE id = $1 FOR UPDATE",
+        )
+        .bind(bouquet_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        if product_type != ProductType::Bouquet {
+            return Err(AppError::ValidationError(
+                "Only bouquets can have a recipe".to_string(),
+            ));
+        }
+
+        let component_ids: Vec<Uuid> = request
+            .components
+            .iter()
+            .map(|component| component.component_id)
+            .collect();
+        let component_types = sqlx::query_as::<_, (Uuid, ProductType)>(
+            "SELECT id, product_type FROM products WHERE id = ANY($1)",
+        )
+        .bind(&component_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch components: {}", e)))?;
+
+        for component_id in &component_ids {
+            match component_types.iter().find(|(id, _)| id == component_id) {
+                Some((_, ProductType::Bouquet)) => {
+                    return Err(AppError::ValidationError(format!(
+                        "Component {} is a bouquet; recipes can only use flowers and materials",
+                        component_id
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(AppError::NotFound(format!(
+                        "Component {} not found",
+                        component_id
+                    )));
+                }
+            }
+        }
+
+        let open_orders = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(DISTINCT o.id)
+            FROM orders o
+            JOIN order_line ol ON ol.order_id = o.id
+            WHERE ol.product_id = $1
+            AND o.status IN ('pending', 'processing')
+            "#,
+        )
+        .bind(bouquet_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check open orders: {}", e)))?;
+
+        if open_orders > 0 {
+            return Err(AppError::Conflict(format!(
+                "The recipe cannot change while {} open orders contain this bouquet",
+                open_orders
+            )));
+        }
+
+        sqlx::query("DELETE FROM bouquet_components WHERE bouquet_id = $1")
+            .bind(bouquet_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to clear recipe: {}", e)))?;
+
+        for component in &request.components {
+            sqlx::query(
+                "INSERT INTO bouquet_components (bouquet_id, component_id, quantity) VALUES ($1, $2, $3)",
+            )
+            .bind(bouquet_id)
+            .bind(component.component_id)
+            .bind(component.quantity)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to save recipe component: {}", e))
+            })?;
+        }
+
+        // Products are listed through their inventory row, so a bouquet needs one even
+        // though its stock comes from its components
+        sqlx::query(
+            "INSERT INTO inventory (product_id, quantity_on_hand, quantity_reserved, updated_at) VALUES ($1, 0, 0, NOW()) ON CONFLICT (product_id) DO NOTHING",
+        )
+        .bind(bouquet_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to initialize inventory: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit recipe: {}", e)))?;
+
+        info!(
+            "Recipe of bouquet {} set to {} components",
+            bouquet_id,
+            request.components.len()
+        );
+        Self::get_recipe(bouquet_id).await
+    }
+
+    /// Whether a product is assembled from components
+    pub async fn has_recipe_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<bool, AppError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM bouquet_components WHERE bouquet_id = $1)",
+        )
+        .bind(product_id)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check recipe: {}", e)))
+    }
+
+    /// Reject stock changes on a bouquet that is assembled from components
+    pub async fn ensure_not_composed_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<(), AppError> {
+        if Self::has_recipe_with(conn, product_id).await? {
+            return Err(AppError::ValidationError(format!(
+                "Product {} is assembled from its recipe; change the stock of its components instead",
+                product_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Bouquets using a component that can currently be made at least once
+    pub async fn available_bouquets_with(
+        conn: &mut PgConnection,
+        component_id: Uuid,
+    ) -> Result<Vec<Uuid>, AppError> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT s.product_id
+            FROM product_stock s
+            JOIN bouquet_components bc ON bc.bouquet_id = s.product_id
+            WHERE bc.component_id = $1
+            AND s.quantity_on_hand - s.quantity_reserved > 0
+            "#,
+        )
+        .bind(component_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch bouquets: {}", e)))
+    }
+
+    /// Replace bouquets with a recipe by the components they are made of
+    pub async fn expand_reservations_with(
+        conn: &mut PgConnection,
+        reservations: &[InventoryReservation],
+    ) -> Result<Vec<InventoryReservation>, AppError> {
+        let items: Vec<(Uuid, Decimal)> = reservations
+            .iter()
+            .map(|reservation| (reservation.product_id, reservation.quantity_to_reserve))
+            .collect();
+
+        Ok(Self::expand_with(conn, &items)
+            .await?
+            .into_iter()
+            .map(|(product_id, quantity_to_reserve)| InventoryReservation {
+                product_id,
+                quantity_to_reserve,
+            })
+            .collect())
+    }
+
+    /// Replace bouquets with a recipe by the components they are made of
+    pub async fn expand_updates_with(
+        conn: &mut PgConnection,
+        updates: &[InventoryUpdate],
+    ) -> Result<Vec<InventoryUpdate>, AppError> {
+        let items: Vec<(Uuid, Decimal)> = updates
+            .iter()
+            .map(|update| (update.product_id, update.quantity_change))
+            .collect();
+
+        Ok(Self::expand_with(conn, &items)
+            .await?
+            .into_iter()
+            .map(|(product_id, quantity_change)| InventoryUpdate {
+                product_id,
+                quantity_change,
+            })
+            .collect())
+    }
+
+    /// Replace products with a recipe by their components, see `expand_components`
+    pub async fn expand_with(
+        conn: &mut PgConnection,
+        items: &[(Uuid, Decimal)],
+    ) -> Result<Vec<(Uuid, Decimal)>, AppError> {
+        let product_ids: Vec<Uuid> = items.iter().map(|(product_id, _)| *product_id).collect();
+        let recipes = Self::recipes_with(conn, &product_ids).await?;
+
+        Ok(expand_components(items, &recipes))
+    }
+
+    /// The components of the given products that have a recipe, per product
+    pub async fn recipes_with(
+        conn: &mut PgConnection,
+        product_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<(Uuid, Decimal)>>, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, Uuid, Decimal)>(
+            "SELECT bouquet_id, component_id, quantity FROM bouquet_components WHERE bouquet_id = ANY($1)",
+        )
+        .bind(product_ids)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch recipes: {}", e)))?;
+
+        let mut recipes: HashMap<Uuid, Vec<(Uuid, Decimal)>> = HashMap::new();
+        for (bouquet_id, component_id, quantity) in rows {
+            recipes
+                .entry(bouquet_id)
+                .or_default()
+                .push((component_id, quantity));
+        }
+
+        Ok(recipes)
+    }
+
+    /// Bouquets to assemble for open orders delivered on `date`, and the components to pick
+    /// for them. Orders without a delivery date are made on the day they were placed.
+    pub async fn production_list(date: NaiveDate) -> Result<ProductionList, AppError> {
+        let pool = pool();
+
+        let bouquets = sqlx::query_as::<_, ProductionBouquet>(
+            r#"
+            SELECT
+                p.id AS product_id,
+                p.name,
+                p.sku,
+                SUM(ol.quantity) AS quantity,
+                COUNT(DISTINCT o.id) AS orders
+            FROM orders o
+            JOIN order_line ol ON ol.order_id = o.id
+            JOIN products p ON p.id = ol.product_id
+            WHERE o.status IN ('pending', 'processing')
+            AND COALESCE(o.delivery_date, o.created_at::date) = $1
+            AND EXISTS (SELECT 1 FROM bouquet_components bc WHERE bc.bouquet_id = p.id)
+            GROUP BY p.id, p.name, p.sku
+            ORDER BY p.name ASC
+            "#,
+        )
+        .bind(date)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch production list: {}", e)))?;
+
+        let components = sqlx::query_as::<_, ProductionComponent>(
+            r#"
+            SELECT
+                c.id AS component_id,
+                c.name,
+                c.sku,
+                SUM(ol.quantity * bc.quantity) AS quantity
+            FROM orders o
+            JOIN order_line ol ON ol.order_id = o.id
+            JOIN bouquet_components bc ON bc.bouquet_id = ol.product_id
+            JOIN products c ON c.id = bc.component_id
+            WHERE o.status IN ('pending', 'processing')
+            AND COALESCE(o.delivery_date, o.created_at::date) = $1
+            GROUP BY c.id, c.name, c.sku
+            ORDER BY c.name ASC
+            "#,
+        )
+        .bind(date)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch production components: {}", e))
+        })?;
+
+        Ok(ProductionList {
+            date,
+            bouquets,
+            components,
+        })
+    }
+}
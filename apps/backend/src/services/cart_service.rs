@@ -1,8 +1,10 @@
 use crate::pool::connect::pool;
 use crate::response::{AppResponse, error::AppError};
+use crate::services::InventoryService;
 use crate::structs::cart::{
     AddCartItemRequest, Cart, CartItem, CartItemWithProduct, CartResponse, GuestCartItem,
 };
+use crate::structs::inventory::{InventoryReservation, StockCheck};
 use crate::structs::product::Product;
 use crate::utils::tax::Tax;
 use rust_decimal::Decimal;
@@ -77,7 +79,7 @@ impl CartService {
 
         let pool = pool();
 
-        match sqlx::query_as::<_, CartItemWithProduct>(
+        let mut items = match sqlx::query_as::<_, CartItemWithProduct>(
             "SELECT
                 ci.id, ci.cart_id, ci.product_id, ci.quantity, ci.unit_price_cents,
                 ci.unit_tax_cents, ci.unit_subtotal_cents,
@@ -92,12 +94,32 @@ impl CartService {
         .fetch_all(pool)
         .await
         {
-            Ok(items) => AppResponse::Success(CartResponse::new(cart, items)),
-            Err(e) => AppResponse::Error(AppError::DatabaseError(format!(
-                "Failed to fetch cart items: {}",
-                e
-            ))),
+            Ok(items) => items,
+            Err(e) => {
+                return AppResponse::Error(AppError::DatabaseError(format!(
+                    "Failed to fetch cart items: {}",
+                    e
+                )));
+            }
+        };
+
+        let requested: Vec<InventoryReservation> = items
+            .iter()
+            .map(|item| InventoryReservation {
+                product_id: item.product_id,
+                quantity_to_reserve: Decimal::from(item.quantity),
+            })
+            .collect();
+        match InventoryService::check_stock_for(&requested).await {
+            Ok(checks) => {
+                for (item, check) in items.iter_mut().zip(&checks) {
+                    Self::apply_stock_check(item, check);
+                }
+            }
+            Err(e) => return AppResponse::Error(e),
         }
+
+        AppResponse::Success(CartResponse::new(cart, items))
     }
 
     /// Add item to cart or update quantity if already exists
@@ -120,12 +142,11 @@ impl CartService {
         };
 
         // Validate stock availability
-        if product.stock < Decimal::from(request.quantity) {
-            return AppResponse::Error(AppError::ValidationError(format!(
-                "Insufficient stock. Available: {}, requested: {}",
-                product.stock, request.quantity
-            )));
-        }
+        let stock_check =
+            match Self::check_stock(request.product_id, request.quantity, product.stock).await {
+                Ok(check) => check,
+                Err(e) => return AppResponse::Error(e),
+            };
 
         // Calculate unit prices in cents
         let unit_price_cents = (product.price * Decimal::from(100))
@@ -160,14 +181,7 @@ impl CartService {
                 // Update existing item quantity
                 let new_quantity = item.quantity + request.quantity;
 
-                // Validate total quantity against stock
-                if product.stock < Decimal::from(new_quantity) {
-                    return AppResponse::Error(AppError::ValidationError(format!(
-                        "Insufficient stock. Available: {}, total requested: {}",
-                        product.stock, new_quantity
-                    )));
-                }
-
+                // Validate total quantity against stock in update_cart_item_quantity
                 Self::update_cart_item_quantity(item.id, new_quantity).await
             }
             Ok(_) => {
@@ -187,7 +201,7 @@ impl CartService {
                 .await
                 {
                     Ok(cart_item) => {
-                        AppResponse::Success(CartItemWithProduct {
+                        let mut item = CartItemWithProduct {
                             id: cart_item.id,
                             cart_id: cart_item.cart_id,
                             product_id: cart_item.product_id,
@@ -200,7 +214,11 @@ impl CartService {
                             metadata: cart_item.metadata,
                             product_name: product.name,
                             product_sku: product.sku,
-                        })
+                            awaiting_stock: false,
+                            expected_ship_date: None,
+                        };
+                        Self::apply_stock_check(&mut item, &stock_check);
+                        AppResponse::Success(item)
                     }
                     Err(e) => AppResponse::Error(AppError::DatabaseError(format!(
                         "Failed to create cart item: {}",
@@ -255,12 +273,10 @@ impl CartService {
             AppResponse::Error(e) => return AppResponse::Error(e),
         };
 
-        if product.stock < Decimal::from(quantity) {
-            return AppResponse::Error(AppError::ValidationError(format!(
-                "Insufficient stock. Available: {}, requested: {}",
-                product.stock, quantity
-            )));
-        }
+        let stock_check = match Self::check_stock(product.id, quantity, product.stock).await {
+            Ok(check) => check,
+            Err(e) => return AppResponse::Error(e),
+        };
 
         // Update quantity
         match sqlx::query("UPDATE cart_items SET quantity = $1 WHERE id = $2")
@@ -269,10 +285,14 @@ impl CartService {
             .execute(pool)
             .await
         {
-            Ok(_) => AppResponse::Success(CartItemWithProduct {
-                quantity,
-                ..item_with_product
-            }),
+            Ok(_) => {
+                let mut item = CartItemWithProduct {
+                    quantity,
+                    ..item_with_product
+                };
+                Self::apply_stock_check(&mut item, &stock_check);
+                AppResponse::Success(item)
+            }
             Err(e) => AppResponse::Error(AppError::DatabaseError(format!(
                 "Failed to update cart item: {}",
                 e
@@ -377,9 +397,16 @@ impl CartService {
 
             let total_quantity = existing_quantity + guest_item.quantity;
 
-            // Cap at product stock
-            let final_quantity =
-                std::cmp::min(total_quantity, product.stock.round().to_i32().unwrap_or(0));
+            // Cap at product stock, unless the product can be ordered on backorder
+            let final_quantity = match InventoryService::check_stock(
+                product.id,
+                Decimal::from(total_quantity),
+            )
+            .await
+            {
+                Ok(check) if check.is_available() => total_quantity,
+                _ => std::cmp::min(total_quantity, product.stock.round().to_i32().unwrap_or(0)),
+            };
 
             if final_quantity <= 0 {
                 continue;
@@ -421,6 +448,29 @@ impl CartService {
         Self::get_cart_with_items(user_id).await
     }
 
+    /// Helper: Check a cart quantity against the stock, allowing backorders and pre-orders
+    async fn check_stock(
+        product_id: Uuid,
+        quantity: i32,
+        stock: Decimal,
+    ) -> Result<StockCheck, AppError> {
+        let check = InventoryService::check_stock(product_id, Decimal::from(quantity)).await?;
+        if !check.is_available() {
+            return Err(AppError::ValidationError(format!(
+                "Insufficient stock. Available: {}, requested: {}",
+                stock.max(Decimal::ZERO),
+                quantity
+            )));
+        }
+        Ok(check)
+    }
+
+    /// Helper: Show when a backordered item is expected to ship
+    fn apply_stock_check(item: &mut CartItemWithProduct, check: &StockCheck) {
+        item.awaiting_stock = check.is_backordered();
+        item.expected_ship_date = check.expected_available_on();
+    }
+
     /// Helper: Get product by ID
     async fn get_product_by_id(product_id: Uuid) -> AppResponse<Product> {
         match crate::actions::get::get_product_by_id(product_id).await {
@@ -0,0 +1,53 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// An email waiting to be delivered by the mail worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutgoingEmail {
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub template: String,
+}
+
+/// Service for queueing transactional emails in the `email_outbox` table
+pub struct EmailService;
+
+impl EmailService {
+    /// Queue an email for delivery
+    pub async fn queue_email(email: &OutgoingEmail) -> Result<Uuid, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::queue_email_with(&mut conn, email).await
+    }
+
+    /// Queue an email on an existing connection, so it can share a transaction with the
+    /// change that triggered it
+    pub async fn queue_email_with(
+        conn: &mut PgConnection,
+        email: &OutgoingEmail,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO email_outbox (recipient, subject, body, template) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(&email.recipient)
+        .bind(&email.subject)
+        .bind(&email.body)
+        .bind(&email.template)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to queue '{}' email for {}: {}",
+                email.template, email.recipient, e
+            ))
+        })
+    }
+}
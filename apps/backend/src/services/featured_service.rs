@@ -0,0 +1,138 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::featured::{FeaturedPlacement, FeaturedRequest, FeaturedType};
+use uuid::Uuid;
+
+const PLACEMENT_COLUMNS: &str = "f.id, f.product_id, p.name AS product_name, \
+     f.featured_type::text AS featured_type, f.position, f.start_date, f.end_date, \
+     ((f.start_date IS NULL OR f.start_date <= now()) AND (f.end_date IS NULL OR f.end_date > now())) AS is_live, \
+     f.created_at, f.updated_at";
+
+/// Service for scheduling products in featured spots
+pub struct FeaturedService;
+
+impl FeaturedService {
+    /// Placements by type and position. Placements whose window has ended are left out
+    /// unless asked for.
+    pub async fn list_placements(
+        featured_type: Option<FeaturedType>,
+        include_ended: bool,
+    ) -> Result<Vec<FeaturedPlacement>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, FeaturedPlacement>(&format!(
+            r#"
+            SELECT {}
+            FROM featured_products f
+            JOIN products p ON p.id = f.product_id
+            WHERE ($1::text IS NULL OR f.featured_type = $1)
+              AND ($2 OR f.end_date IS NULL OR f.end_date > now())
+            ORDER BY f.featured_type, f.position, f.start_date NULLS FIRST
+            "#,
+            PLACEMENT_COLUMNS
+        ))
+        .bind(featured_type)
+        .bind(include_ended)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch featured products: {}", e)))
+    }
+
+    /// Place a product in a featured spot. A product can be in each type of spot once.
+    pub async fn create_placement(
+        request: &FeaturedRequest,
+    ) -> Result<FeaturedPlacement, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, FeaturedPlacement>(&format!(
+            r#"
+            WITH f AS (
+                INSERT INTO featured_products (product_id, featured_type, position, start_date, end_date)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            )
+            SELECT {}
+            FROM f
+            JOIN products p ON p.id = f.product_id
+            "#,
+            PLACEMENT_COLUMNS
+        ))
+        .bind(request.product_id)
+        .bind(request.featured_type)
+        .bind(request.position)
+        .bind(request.start_date)
+        .bind(request.end_date)
+        .fetch_one(pool)
+        .await
+        .map_err(Self::map_write_error)
+    }
+
+    /// Replace a placement: its product, type, position and window
+    pub async fn update_placement(
+        placement_id: Uuid,
+        request: &FeaturedRequest,
+    ) -> Result<FeaturedPlacement, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, FeaturedPlacement>(&format!(
+            r#"
+            WITH f AS (
+                UPDATE featured_products
+                SET product_id = $2, featured_type = $3, position = $4, start_date = $5,
+                    end_date = $6, updated_at = now()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {}
+            FROM f
+            JOIN products p ON p.id = f.product_id
+            "#,
+            PLACEMENT_COLUMNS
+        ))
+        .bind(placement_id)
+        .bind(request.product_id)
+        .bind(request.featured_type)
+        .bind(request.position)
+        .bind(request.start_date)
+        .bind(request.end_date)
+        .fetch_optional(pool)
+        .await
+        .map_err(Self::map_write_error)?
+        .ok_or_else(|| AppError::not_found("Featured placement"))
+    }
+
+    pub async fn delete_placement(placement_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("DELETE FROM featured_products WHERE id = $1")
+            .bind(placement_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to delete featured placement: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Featured placement"));
+        }
+        Ok(())
+    }
+
+    fn map_write_error(error: sqlx::Error) -> AppError {
+        match error {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict(
+                    "This product is already featured in this type of spot".to_string(),
+                )
+            }
+            sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() => {
+                AppError::not_found("Product")
+            }
+            e => AppError::DatabaseError(format!("Failed to save featured placement: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::secrets;
+use crate::services::BillOfMaterialsService;
+use crate::structs::forecast::{
+    ProductForecast, SeasonalEvent, SeasonalEventRequest, forecast_demand,
+};
+use chrono::{Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const SEASONAL_EVENT_COLUMNS: &str = "id, name, starts_on, ends_on, multiplier, created_at";
+
+/// Service for predicting demand from order history and seasonal events
+pub struct ForecastService;
+
+impl ForecastService {
+    /// Configured events, latest first
+    pub async fn list_events() -> Result<Vec<SeasonalEvent>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, SeasonalEvent>(&format!(
+            "SELECT {} FROM seasonal_events ORDER BY starts_on DESC",
+            SEASONAL_EVENT_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch seasonal events: {}", e)))
+    }
+
+    pub async fn create_event(request: &SeasonalEventRequest) -> Result<SeasonalEvent, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, SeasonalEvent>(&format!(
+            r#"
+            INSERT INTO seasonal_events (name, starts_on, ends_on, multiplier)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            SEASONAL_EVENT_COLUMNS
+        ))
+        .bind(request.name.trim())
+        .bind(request.starts_on)
+        .bind(request.ends_on)
+        .bind(request.multiplier)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to save seasonal event: {}", e)))
+    }
+
+    pub async fn delete_event(event_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("DELETE FROM seasonal_events WHERE id = $1")
+            .bind(event_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to delete seasonal event: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Seasonal event"));
+        }
+        Ok(())
+    }
+
+    /// Forecast the next `days` days for every active product sold in the history window,
+    /// or for a single product. Highest expected demand first.
+    pub async fn product_forecasts(
+        days: i32,
+        product_id: Option<Uuid>,
+    ) -> Result<Vec<ProductForecast>, AppError> {
+        let pool = pool();
+        let today = Utc::now().date_naive();
+        let history_start = today
+            .checked_sub_days(Days::new(secrets::get_forecast_history_days().max(1) as u64))
+            .unwrap_or(today);
+        let horizon = today
+            .checked_add_days(Days::new(days.max(0) as u64))
+            .unwrap_or(today);
+
+        let events = Self::events_between(history_start, horizon).await?;
+
+        // Orders count for the day the flowers were needed
+        let rows = sqlx::query_as::<_, (Uuid, String, String, NaiveDate, Decimal)>(
+            r#"
+            SELECT
+                p.id, p.name, p.sku,
+                COALESCE(o.delivery_date, o.created_at::date) AS day,
+                SUM(ol.quantity) AS quantity
+            FROM order_line ol
+            JOIN orders o ON o.id = ol.order_id
+            JOIN products p ON p.id = ol.product_id
+            WHERE o.status NOT IN ('cancelled', 'deleted')
+            AND p.is_active = true
+            AND COALESCE(o.delivery_date, o.created_at::date) >= $1
+            AND COALESCE(o.delivery_date, o.created_at::date) < $2
+            AND ($3::uuid IS NULL OR p.id = $3)
+            GROUP BY p.id, p.name, p.sku, day
+            ORDER BY p.id, day
+            "#,
+        )
+        .bind(history_start)
+        .bind(today)
+        .bind(product_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch order history: {}", e)))?;
+
+        let mut products: Vec<(Uuid, String, String)> = Vec::new();
+        let mut history: HashMap<Uuid, Vec<(NaiveDate, Decimal)>> = HashMap::new();
+        for (id, name, sku, day, quantity) in rows {
+            if !history.contains_key(&id) {
+                products.push((id, name, sku));
+            }
+            history.entry(id).or_default().push((day, quantity));
+        }
+
+        let alpha = secrets::get_forecast_smoothing_factor();
+        let mut forecasts: Vec<ProductForecast> = products
+            .into_iter()
+            .map(|(product_id, name, sku)| ProductForecast {
+                forecast: forecast_demand(
+                    history
+                        .get(&product_id)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default(),
+                    history_start,
+                    today,
+                    days.max(0) as u64,
+                    &events,
+                    alpha,
+                ),
+                product_id,
+                name,
+                sku,
+            })
+            .collect();
+        forecasts.sort_by_key(|forecast| std::cmp::Reverse(forecast.forecast.total));
+
+        Ok(forecasts)
+    }
+
+    /// Forecast demand over the next `days` days per stocked product. Bouquets with a recipe
+    /// are counted as the components they are made of.
+    pub async fn stock_demand(days: i32) -> Result<HashMap<Uuid, Decimal>, AppError> {
+        let items: Vec<(Uuid, Decimal)> = Self::product_forecasts(days, None)
+            .await?
+            .into_iter()
+            .map(|forecast| (forecast.product_id, forecast.forecast.total))
+            .collect();
+
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Ok(BillOfMaterialsService::expand_with(&mut conn, &items)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    async fn events_between(
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<SeasonalEvent>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, SeasonalEvent>(&format!(
+            "SELECT {} FROM seasonal_events WHERE starts_on <= $2 AND ends_on >= $1",
+            SEASONAL_EVENT_COLUMNS
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch seasonal events: {}", e)))
+    }
+}
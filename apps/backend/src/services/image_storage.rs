@@ -0,0 +1,89 @@
+use crate::response::error::AppError;
+use crate::secrets;
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Where product images are kept. Keys are relative paths like
+/// `products/<product id>/<image id>/medium.webp`.
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    /// Store the bytes under `key`, replacing anything there, and return its public URL
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<String, AppError>;
+
+    /// Remove the object under `key`; removing something that is not there is not an error
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Keeps images in a directory that is served under a public URL
+pub struct LocalImageStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalImageStorage {
+    pub fn new(root: impl Into<PathBuf>, public_url: &str) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// The file for a key. Keys may not leave the storage directory.
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(AppError::internal_error("Invalid image storage key"));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl ImageStorage for LocalImageStorage {
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<String, AppError> {
+        let path = self.path(key)?;
+        if let Some(directory) = path.parent() {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+        tokio::fs::write(&path, bytes).await?;
+
+        Ok(format!("{}/{}", self.public_url, key))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // Tidy up the image's directory once its last file is gone
+        if let Some(directory) = path.parent() {
+            let _ = tokio::fs::remove_dir(directory).await;
+        }
+        Ok(())
+    }
+}
+
+static IMAGE_STORAGE: OnceLock<LocalImageStorage> = OnceLock::new();
+
+/// The configured image storage
+pub fn image_storage() -> &'static dyn ImageStorage {
+    IMAGE_STORAGE.get_or_init(|| {
+        LocalImageStorage::new(
+            secrets::get_image_storage_dir(),
+            &secrets::get_image_public_url(),
+        )
+    })
+}
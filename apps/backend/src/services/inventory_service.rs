@@ -1,72 +1,254 @@
 use crate::pool::connect::pool;
 use crate::response::error::AppError;
-use crate::structs::inventory::{Inventory, InventoryReservation, InventoryUpdate};
+use crate::services::{
+    BackInStockService, BillOfMaterialsService, StockBatchService, StockLocationService,
+    StockMovementService,
+};
+use crate::structs::bill_of_materials::expand_components;
+use crate::structs::inventory::{
+    BackorderPolicy, Inventory, InventoryReservation, InventoryUpdate, StockCheck,
+};
+use crate::structs::stock_location::LocationStock;
+use crate::structs::stock_movement::{MovementContext, StockMovementReason};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
-use sqlx::Row;
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, Row};
+use std::collections::HashMap;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+const BACKORDER_COLUMNS: &str = "backorder_mode, backorder_limit, expected_available_on";
+
+/// Expired units of an `inventory` row's product that have not been written off yet. They
+/// can't be sold; reserving writes them off first.
+const EXPIRED_UNITS: &str = r#"(
+    SELECT COALESCE(SUM(b.quantity_remaining - b.quantity_reserved), 0)
+    FROM stock_batches b
+    WHERE b.product_id = inventory.product_id
+    AND b.expires_on < CURRENT_DATE
+    AND b.quantity_remaining > b.quantity_reserved
+)"#;
+
 /// Service for handling inventory operations
 pub struct InventoryService;
 
 impl InventoryService {
-    /// Check if we have sufficient inventory for a product
+    /// Check if we have sufficient inventory for a product, or can take the order on backorder
     pub async fn check_availability(
         product_id: Uuid,
         requested_quantity: Decimal,
     ) -> Result<bool, AppError> {
+        Ok(Self::check_stock(product_id, requested_quantity)
+            .await?
+            .is_available())
+    }
+
+    /// Check whether a quantity of a product can be ordered now, on backorder or not at all.
+    /// Bouquets with a recipe are checked against their components.
+    pub async fn check_stock(
+        product_id: Uuid,
+        requested_quantity: Decimal,
+    ) -> Result<StockCheck, AppError> {
+        let checks = Self::check_stock_for(&[InventoryReservation {
+            product_id,
+            quantity_to_reserve: requested_quantity,
+        }])
+        .await?;
+
+        Ok(checks
+            .into_iter()
+            .next()
+            .unwrap_or(StockCheck::Insufficient))
+    }
+
+    /// Check several products at once, e.g. the items of a cart, with one query for their
+    /// recipes and one for their stock. Each item is checked on its own and the checks are
+    /// returned in the same order.
+    pub async fn check_stock_for(
+        items: &[InventoryReservation],
+    ) -> Result<Vec<StockCheck>, AppError> {
         let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
 
-        let row = sqlx::query(
-            "SELECT quantity_on_hand, quantity_reserved FROM inventory WHERE product_id = $1",
-        )
-        .bind(product_id)
-        .fetch_optional(pool)
+        let product_ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+        let recipes = BillOfMaterialsService::recipes_with(&mut conn, &product_ids).await?;
+        let expanded: Vec<Vec<(Uuid, Decimal)>> = items
+            .iter()
+            .map(|item| expand_components(&[(item.product_id, item.quantity_to_reserve)], &recipes))
+            .collect();
+
+        let stock_ids: Vec<Uuid> = expanded
+            .iter()
+            .flatten()
+            .map(|(product_id, _)| *product_id)
+            .collect();
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT
+                product_id,
+                quantity_on_hand - quantity_reserved - {} AS available,
+                {}
+            FROM inventory
+            WHERE product_id = ANY($1)
+            "#,
+            EXPIRED_UNITS, BACKORDER_COLUMNS
+        ))
+        .bind(&stock_ids)
+        .fetch_all(&mut *conn)
         .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!(
-                "Failed to check inventory for product {}: {}",
-                product_id, e
-            ))
-        })?;
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check inventory: {}", e)))?;
 
-        match row {
-            Some(row) => {
-                let quantity_on_hand: Decimal = row.get("quantity_on_hand");
-                let quantity_reserved: Decimal = row.get("quantity_reserved");
-                let available = quantity_on_hand - quantity_reserved;
-                Ok(available >= requested_quantity)
-            }
-            _ => Ok(false), // Product not found in inventory
-        }
+        let stock: HashMap<Uuid, ProductStock> = rows
+            .iter()
+            .map(|row| {
+                let available: Decimal = row.get("available");
+                (
+                    row.get("product_id"),
+                    (available, available, backorder_policy(row)),
+                )
+            })
+            .collect();
+
+        Ok(check_expanded(&expanded, &stock))
+    }
+
+    /// Check several products against the stock at the location an order will be reserved
+    /// at. Stock at other locations can't be reserved for it, so it doesn't count here.
+    /// The checks are returned in the same order as the items.
+    pub async fn check_stock_at(
+        items: &[InventoryReservation],
+        location_id: Uuid,
+    ) -> Result<Vec<StockCheck>, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        let product_ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+        let recipes = BillOfMaterialsService::recipes_with(&mut conn, &product_ids).await?;
+        let expanded: Vec<Vec<(Uuid, Decimal)>> = items
+            .iter()
+            .map(|item| expand_components(&[(item.product_id, item.quantity_to_reserve)], &recipes))
+            .collect();
+
+        let stock_ids: Vec<Uuid> = expanded
+            .iter()
+            .flatten()
+            .map(|(product_id, _)| *product_id)
+            .collect();
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT
+                inventory.product_id,
+                COALESCE(li.quantity_on_hand, 0) - COALESCE(li.quantity_reserved, 0) - (
+                    SELECT COALESCE(SUM(b.quantity_remaining - b.quantity_reserved), 0)
+                    FROM stock_batches b
+                    WHERE b.product_id = inventory.product_id
+                    AND b.location_id = $2
+                    AND b.expires_on < CURRENT_DATE
+                    AND b.quantity_remaining > b.quantity_reserved
+                ) AS location_available,
+                inventory.quantity_on_hand - inventory.quantity_reserved - {} AS product_available,
+                {}
+            FROM inventory
+            LEFT JOIN location_inventory li
+                ON li.product_id = inventory.product_id AND li.location_id = $2
+            WHERE inventory.product_id = ANY($1)
+            "#,
+            EXPIRED_UNITS, BACKORDER_COLUMNS
+        ))
+        .bind(&stock_ids)
+        .bind(location_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check location stock: {}", e)))?;
+
+        let stock: HashMap<Uuid, ProductStock> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("product_id"),
+                    (
+                        row.get("location_available"),
+                        row.get("product_available"),
+                        backorder_policy(row),
+                    ),
+                )
+            })
+            .collect();
+
+        Ok(check_expanded(&expanded, &stock))
     }
 
     /// STAGE 1: Reserve inventory for an order (increase quantity_reserved)
     /// This happens when an order is placed - items are marked as "spoken for" but remain in warehouse
     /// Expected result: on_hand stays same, reserved increases, available decreases
-    pub async fn reserve_inventory(reservations: &[InventoryReservation]) -> Result<(), AppError> {
-        debug!(
-            "Starting inventory reservation for {} products",
-            reservations.len()
-        );
-
+    /// All units are reserved at `location_id`. Products that take backorders may go below
+    /// zero available there; the result tells whether the order has to wait for stock.
+    pub async fn reserve_inventory(
+        reservations: &[InventoryReservation],
+        location_id: Uuid,
+        context: &MovementContext,
+    ) -> Result<StockCheck, AppError> {
         let pool = pool();
         let mut tx = pool
             .begin()
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        for reservation in reservations {
+        let check =
+            Self::reserve_inventory_with(&mut tx, reservations, location_id, context).await?;
+
+        tx.commit().await.map_err(|e| {
+            error!("Failed to commit inventory reservations: {}", e);
+            AppError::DatabaseError(format!("Failed to commit inventory reservations: {}", e))
+        })?;
+
+        info!(
+            "Successfully committed reservations for {} products",
+            reservations.len()
+        );
+        Ok(check)
+    }
+
+    /// Reserve inventory within the caller's transaction, e.g. the one that creates the
+    /// order, so the reservations and their stock movements only exist with the order
+    pub async fn reserve_inventory_with(
+        conn: &mut PgConnection,
+        reservations: &[InventoryReservation],
+        location_id: Uuid,
+        context: &MovementContext,
+    ) -> Result<StockCheck, AppError> {
+        debug!(
+            "Starting inventory reservation for {} products at location {}",
+            reservations.len(),
+            location_id
+        );
+
+        let reservations =
+            BillOfMaterialsService::expand_reservations_with(&mut *conn, reservations).await?;
+
+        let mut check = StockCheck::InStock;
+        for reservation in &reservations {
             debug!(
                 "Reserving {} units for product {}",
                 reservation.quantity_to_reserve, reservation.product_id
             );
-            // First, check if we have enough available inventory
-            let row = sqlx::query(
-                "SELECT quantity_on_hand, quantity_reserved FROM inventory WHERE product_id = $1 FOR UPDATE",
-            )
+            // The backorder policy applies to the product as a whole
+            let row = sqlx::query(&format!(
+                "SELECT {} FROM inventory WHERE product_id = $1 FOR UPDATE",
+                BACKORDER_COLUMNS
+            ))
             .bind(reservation.product_id)
-            .fetch_optional(&mut *tx)
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!(
@@ -77,42 +259,94 @@ impl InventoryService {
 
             match row {
                 Some(row) => {
-                    let quantity_on_hand: Decimal = row.get("quantity_on_hand");
-                    let quantity_reserved: Decimal = row.get("quantity_reserved");
-                    let available = quantity_on_hand - quantity_reserved;
-
-                    if available < reservation.quantity_to_reserve {
-                        tx.rollback().await.ok();
-                        return Err(AppError::ValidationError(format!(
-                            "Insufficient inventory for product {}. Available: {}, Requested: {}",
-                            reservation.product_id, available, reservation.quantity_to_reserve
-                        )));
-                    }
+                    // Expired units can't be sold, so they go before counting what is available
+                    StockBatchService::write_off_expired_with(
+                        &mut *conn,
+                        reservation.product_id,
+                        location_id,
+                    )
+                    .await?;
 
-                    // Reserve the inventory
-                    let result = sqlx::query(
-                        "UPDATE inventory SET quantity_reserved = quantity_reserved + $1, updated_at = NOW() WHERE product_id = $2",
+                    // First, check if we have enough available inventory at the location
+                    let (quantity_on_hand, quantity_reserved) = StockLocationService::lock_with(
+                        &mut *conn,
+                        reservation.product_id,
+                        location_id,
                     )
-                    .bind(reservation.quantity_to_reserve)
+                    .await?;
+                    let available = quantity_on_hand - quantity_reserved;
+
+                    // Backorders are counted against what the product has over all locations
+                    let product_available = sqlx::query_scalar::<_, Decimal>(&format!(
+                        "SELECT quantity_on_hand - quantity_reserved - {} FROM inventory WHERE product_id = $1",
+                        EXPIRED_UNITS
+                    ))
                     .bind(reservation.product_id)
-                    .execute(&mut *tx)
+                    .fetch_one(&mut *conn)
                     .await
                     .map_err(|e| {
                         AppError::DatabaseError(format!(
-                            "Failed to reserve inventory for product {}: {}",
+                            "Failed to check inventory for product {}: {}",
                             reservation.product_id, e
                         ))
                     })?;
 
-                    info!(
-                        "Successfully reserved {} units for product {}. Rows affected: {}",
+                    let item_check = StockCheck::check_at_location(
+                        available,
+                        product_available,
+                        reservation.quantity_to_reserve,
+                        backorder_policy(&row).as_ref(),
+                    );
+
+                    if !item_check.is_available() {
+                        return Err(AppError::ValidationError(format!(
+                            "Insufficient inventory for product {}. Available: {}, Requested: {}",
+                            reservation.product_id, available, reservation.quantity_to_reserve
+                        )));
+                    }
+
+                    // Reserve the inventory
+                    StockLocationService::apply_with(
+                        &mut *conn,
+                        reservation.product_id,
+                        location_id,
+                        Decimal::ZERO,
                         reservation.quantity_to_reserve,
+                    )
+                    .await?;
+
+                    // Backordered units are assigned to a batch when the stock arrives
+                    let in_stock = reservation
+                        .quantity_to_reserve
+                        .min(available.max(Decimal::ZERO));
+                    if in_stock > Decimal::ZERO {
+                        StockBatchService::reserve(
+                            &mut *conn,
+                            reservation.product_id,
+                            location_id,
+                            in_stock,
+                        )
+                        .await?;
+                    }
+
+                    StockMovementService::record(
+                        &mut *conn,
                         reservation.product_id,
-                        result.rows_affected()
+                        location_id,
+                        Decimal::ZERO,
+                        reservation.quantity_to_reserve,
+                        StockMovementReason::Reservation,
+                        context,
+                    )
+                    .await?;
+
+                    info!(
+                        "Successfully reserved {} units for product {} at location {}",
+                        reservation.quantity_to_reserve, reservation.product_id, location_id
                     );
+                    check = check.combine(item_check);
                 }
                 _ => {
-                    tx.rollback().await.ok();
                     return Err(AppError::NotFound(format!(
                         "Product {} not found in inventory",
                         reservation.product_id
@@ -121,22 +355,17 @@ impl InventoryService {
             }
         }
 
-        tx.commit().await.map_err(|e| {
-            error!("Failed to commit inventory reservations: {}", e);
-            AppError::DatabaseError(format!("Failed to commit inventory reservations: {}", e))
-        })?;
-
-        info!(
-            "Successfully committed reservations for {} products",
-            reservations.len()
-        );
-        Ok(())
+        Ok(check)
     }
 
     /// STAGE 2: Fulfill order (decrease both quantity_on_hand and quantity_reserved)
     /// This happens when an order ships - items physically leave the warehouse
     /// Expected result: on_hand decreases, reserved decreases, available stays same
-    pub async fn fulfill_order(updates: &[InventoryUpdate]) -> Result<(), AppError> {
+    pub async fn fulfill_order(
+        updates: &[InventoryUpdate],
+        location_id: Uuid,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
         debug!("Starting order fulfillment for {} products", updates.len());
 
         let pool = pool();
@@ -145,36 +374,19 @@ impl InventoryService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        for update in updates {
+        let updates = BillOfMaterialsService::expand_updates_with(&mut tx, updates).await?;
+
+        for update in &updates {
             debug!(
                 "Fulfilling {} units for product {}",
                 update.quantity_change, update.product_id
             );
-            // Update inventory: decrease both on_hand and reserved quantities
-            let result = sqlx::query(
-                r#"
-                UPDATE inventory
-                SET
-                    quantity_on_hand = quantity_on_hand - $1,
-                    quantity_reserved = quantity_reserved - $1,
-                    updated_at = NOW()
-                WHERE product_id = $2
-                AND quantity_on_hand >= $1
-                AND quantity_reserved >= $1
-                "#,
-            )
-            .bind(update.quantity_change)
-            .bind(update.product_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!(
-                    "Failed to fulfill order for product {}: {}",
-                    update.product_id, e
-                ))
-            })?;
+            let (quantity_on_hand, quantity_reserved) =
+                StockLocationService::lock_with(&mut tx, update.product_id, location_id).await?;
 
-            if result.rows_affected() == 0 {
+            if quantity_on_hand < update.quantity_change
+                || quantity_reserved < update.quantity_change
+            {
                 warn!(
                     "Failed to fulfill order for product {} - insufficient inventory or reservation",
                     update.product_id
@@ -186,11 +398,38 @@ impl InventoryService {
                 )));
             }
 
-            info!(
-                "Successfully fulfilled {} units for product {}. Rows affected: {}",
+            // Decrease both on_hand and reserved quantities
+            StockLocationService::apply_with(
+                &mut tx,
+                update.product_id,
+                location_id,
+                -update.quantity_change,
+                -update.quantity_change,
+            )
+            .await?;
+
+            StockBatchService::fulfil(
+                &mut tx,
+                update.product_id,
+                location_id,
                 update.quantity_change,
+            )
+            .await?;
+
+            StockMovementService::record(
+                &mut tx,
                 update.product_id,
-                result.rows_affected()
+                location_id,
+                -update.quantity_change,
+                -update.quantity_change,
+                StockMovementReason::Fulfillment,
+                context,
+            )
+            .await?;
+
+            info!(
+                "Successfully fulfilled {} units for product {} at location {}",
+                update.quantity_change, update.product_id, location_id
             );
         }
 
@@ -207,43 +446,59 @@ impl InventoryService {
     }
 
     /// Decrease only on_hand quantity without affecting reservations
-    pub async fn decrease_on_hand_only(updates: &[InventoryUpdate]) -> Result<(), AppError> {
+    pub async fn decrease_on_hand_only(
+        updates: &[InventoryUpdate],
+        location_id: Uuid,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
         let pool = pool();
         let mut tx = pool
             .begin()
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        for update in updates {
-            // Update inventory: decrease only on_hand quantity
-            let result = sqlx::query(
-                r#"
-                UPDATE inventory
-                SET
-                    quantity_on_hand = quantity_on_hand - $1,
-                    updated_at = NOW()
-                WHERE product_id = $2
-                AND quantity_on_hand >= $1
-                "#,
-            )
-            .bind(update.quantity_change)
-            .bind(update.product_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!(
-                    "Failed to decrease on-hand inventory for product {}: {}",
-                    update.product_id, e
-                ))
-            })?;
+        let updates = BillOfMaterialsService::expand_updates_with(&mut tx, updates).await?;
 
-            if result.rows_affected() == 0 {
+        for update in &updates {
+            let (quantity_on_hand, _) =
+                StockLocationService::lock_with(&mut tx, update.product_id, location_id).await?;
+
+            if quantity_on_hand < update.quantity_change {
                 tx.rollback().await.ok();
                 return Err(AppError::ValidationError(format!(
                     "Cannot decrease on-hand inventory for product {}. Insufficient on-hand quantity.",
                     update.product_id
                 )));
             }
+
+            // Decrease only the on_hand quantity
+            StockLocationService::apply_with(
+                &mut tx,
+                update.product_id,
+                location_id,
+                -update.quantity_change,
+                Decimal::ZERO,
+            )
+            .await?;
+
+            StockBatchService::remove_available(
+                &mut tx,
+                update.product_id,
+                location_id,
+                update.quantity_change,
+            )
+            .await?;
+
+            StockMovementService::record(
+                &mut tx,
+                update.product_id,
+                location_id,
+                -update.quantity_change,
+                Decimal::ZERO,
+                StockMovementReason::Adjustment,
+                context,
+            )
+            .await?;
         }
 
         tx.commit().await.map_err(|e| {
@@ -259,7 +514,11 @@ impl InventoryService {
     /// STAGE 2 ALT: Release reserved inventory (when order is cancelled)
     /// This happens when an order is cancelled - reserved items are freed up
     /// Expected result: on_hand stays same, reserved decreases, available increases
-    pub async fn release_reservations(updates: &[InventoryUpdate]) -> Result<(), AppError> {
+    pub async fn release_reservations(
+        updates: &[InventoryUpdate],
+        location_id: Uuid,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
         debug!(
             "Starting reservation release for {} products",
             updates.len()
@@ -271,30 +530,44 @@ impl InventoryService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        for update in updates {
+        let updates = BillOfMaterialsService::expand_updates_with(&mut tx, updates).await?;
+
+        for update in &updates {
             debug!(
                 "Releasing {} reserved units for product {}",
                 update.quantity_change, update.product_id
             );
-            let result = sqlx::query(
-                "UPDATE inventory SET quantity_reserved = quantity_reserved - $1, updated_at = NOW() WHERE product_id = $2",
+            StockLocationService::apply_with(
+                &mut tx,
+                update.product_id,
+                location_id,
+                Decimal::ZERO,
+                -update.quantity_change,
             )
-            .bind(update.quantity_change)
-            .bind(update.product_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!(
-                    "Failed to release reservation for product {}: {}",
-                    update.product_id, e
-                ))
-            })?;
+            .await?;
 
-            info!(
-                "Successfully released {} reserved units for product {}. Rows affected: {}",
+            StockBatchService::release(
+                &mut tx,
+                update.product_id,
+                location_id,
                 update.quantity_change,
+            )
+            .await?;
+
+            StockMovementService::record(
+                &mut tx,
                 update.product_id,
-                result.rows_affected()
+                location_id,
+                Decimal::ZERO,
+                -update.quantity_change,
+                StockMovementReason::ReservationRelease,
+                context,
+            )
+            .await?;
+
+            info!(
+                "Successfully released {} reserved units for product {} at location {}",
+                update.quantity_change, update.product_id, location_id
             );
         }
 
@@ -315,7 +588,7 @@ impl InventoryService {
         let pool = pool();
 
         let row = sqlx::query(
-            "SELECT product_id, quantity_on_hand, quantity_reserved, updated_at FROM inventory WHERE product_id = $1",
+            "SELECT product_id, quantity_on_hand, quantity_reserved, updated_at FROM product_stock WHERE product_id = $1",
         )
         .bind(product_id)
         .fetch_optional(pool)
@@ -338,10 +611,14 @@ impl InventoryService {
         }
     }
 
-    /// Add inventory for a product (used for restocking)
+    /// Add inventory for a product at a location (used for restocking). The delivery is
+    /// stored as a batch that expires on `expires_on`.
     pub async fn add_inventory(
         product_id: Uuid,
+        location_id: Uuid,
         quantity_to_add: Decimal,
+        expires_on: Option<NaiveDate>,
+        context: &MovementContext,
     ) -> Result<Inventory, AppError> {
         let pool = pool();
         let mut tx = pool
@@ -349,38 +626,85 @@ impl InventoryService {
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        // Try to update existing inventory first
-        let result = sqlx::query(
-            "UPDATE inventory SET quantity_on_hand = quantity_on_hand + $1, updated_at = NOW() WHERE product_id = $2",
+        let inventory = Self::add_inventory_with(
+            &mut tx,
+            product_id,
+            location_id,
+            quantity_to_add,
+            expires_on,
+            context,
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit inventory addition: {}", e))
+        })?;
+
+        Ok(inventory)
+    }
+
+    /// Add inventory on an existing connection, so a delivery of several products can be
+    /// booked in one transaction
+    pub async fn add_inventory_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity_to_add: Decimal,
+        expires_on: Option<NaiveDate>,
+        context: &MovementContext,
+    ) -> Result<Inventory, AppError> {
+        BillOfMaterialsService::ensure_not_composed_with(&mut *conn, product_id).await?;
+        StockLocationService::ensure_active_with(&mut *conn, location_id).await?;
+
+        // Products without an inventory record start at zero
+        sqlx::query(
+            "INSERT INTO inventory (product_id, quantity_on_hand, quantity_reserved, updated_at) VALUES ($1, 0, 0, NOW()) ON CONFLICT (product_id) DO NOTHING",
         )
-        .bind(quantity_to_add)
         .bind(product_id)
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await
         .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to update inventory: {}", e))
+            AppError::DatabaseError(format!("Failed to insert inventory record: {}", e))
         })?;
 
-        if result.rows_affected() == 0 {
-            // Product doesn't exist in inventory table, insert new record
-            sqlx::query(
-                "INSERT INTO inventory (product_id, quantity_on_hand, quantity_reserved, updated_at) VALUES ($1, $2, 0, NOW())",
-            )
-            .bind(product_id)
-            .bind(quantity_to_add)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!("Failed to insert inventory record: {}", e))
-            })?;
-        }
+        StockLocationService::apply_with(
+            &mut *conn,
+            product_id,
+            location_id,
+            quantity_to_add,
+            Decimal::ZERO,
+        )
+        .await?;
+
+        StockBatchService::receive(
+            &mut *conn,
+            product_id,
+            location_id,
+            quantity_to_add,
+            expires_on,
+            context.note.as_deref(),
+        )
+        .await?;
+
+        StockMovementService::record(
+            &mut *conn,
+            product_id,
+            location_id,
+            quantity_to_add,
+            Decimal::ZERO,
+            StockMovementReason::Restock,
+            context,
+        )
+        .await?;
+
+        Self::clear_awaiting_stock_with(&mut *conn).await?;
 
         // Get the updated inventory
         let row = sqlx::query(
             "SELECT product_id, quantity_on_hand, quantity_reserved, updated_at FROM inventory WHERE product_id = $1",
         )
         .bind(product_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch updated inventory: {}", e))
@@ -393,37 +717,237 @@ impl InventoryService {
             updated_at: row.get("updated_at"),
         };
 
-        tx.commit().await.map_err(|e| {
-            AppError::DatabaseError(format!("Failed to commit inventory addition: {}", e))
-        })?;
+        if inventory.available_quantity() > Decimal::ZERO {
+            BackInStockService::notify_subscribers_with(&mut *conn, product_id).await?;
+
+            // Restocking a component can make the bouquets it is used in available again
+            for bouquet_id in
+                BillOfMaterialsService::available_bouquets_with(&mut *conn, product_id).await?
+            {
+                BackInStockService::notify_subscribers_with(&mut *conn, bouquet_id).await?;
+            }
+        }
 
         Ok(inventory)
     }
 
-    /// Initialize inventory for a new product
+    /// Initialize inventory for a new product. The initial stock goes to the default location.
     pub async fn initialize_inventory(
         product_id: Uuid,
         initial_quantity: Decimal,
     ) -> Result<(), AppError> {
         let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
 
-        sqlx::query(
-            "INSERT INTO inventory (product_id, quantity_on_hand, quantity_reserved, updated_at) VALUES ($1, $2, 0, NOW()) ON CONFLICT (product_id) DO NOTHING",
+        Self::initialize_inventory_with(&mut tx, product_id, initial_quantity).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit inventory initialization: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Initialize inventory on an existing connection, so a product and its stock are
+    /// created together
+    pub async fn initialize_inventory_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        initial_quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "INSERT INTO inventory (product_id, quantity_on_hand, quantity_reserved, updated_at) VALUES ($1, 0, 0, NOW()) ON CONFLICT (product_id) DO NOTHING",
         )
         .bind(product_id)
-        .bind(initial_quantity)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to initialize inventory: {}", e))
         })?;
 
+        if result.rows_affected() > 0 {
+            let location_id = StockLocationService::default_location_with(&mut *conn).await?;
+
+            StockLocationService::apply_with(
+                &mut *conn,
+                product_id,
+                location_id,
+                initial_quantity,
+                Decimal::ZERO,
+            )
+            .await?;
+
+            if initial_quantity > Decimal::ZERO {
+                StockBatchService::receive(
+                    &mut *conn,
+                    product_id,
+                    location_id,
+                    initial_quantity,
+                    None,
+                    None,
+                )
+                .await?;
+            }
+
+            StockMovementService::record(
+                &mut *conn,
+                product_id,
+                location_id,
+                initial_quantity,
+                Decimal::ZERO,
+                StockMovementReason::InitialStock,
+                &MovementContext::default(),
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
-    /// Get low stock products (below threshold)
+    /// Set the on-hand quantity of a product at a location to the counted quantity. The
+    /// difference with the recorded quantity is logged as a stock count movement, also when
+    /// there is none.
+    pub async fn record_stock_count(
+        product_id: Uuid,
+        location_id: Uuid,
+        counted_quantity: Decimal,
+        context: &MovementContext,
+    ) -> Result<Decimal, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        BillOfMaterialsService::ensure_not_composed_with(&mut tx, product_id).await?;
+        StockLocationService::ensure_active_with(&mut tx, location_id).await?;
+
+        let (quantity_on_hand, quantity_reserved) =
+            StockLocationService::lock_with(&mut tx, product_id, location_id).await?;
+
+        // Reserved units belong to open orders and have to be released before they can go
+        if counted_quantity < quantity_reserved {
+            return Err(AppError::ValidationError(format!(
+                "Counted quantity {} is below the {} units reserved for open orders",
+                counted_quantity, quantity_reserved
+            )));
+        }
+
+        let difference = counted_quantity - quantity_on_hand;
+        StockLocationService::apply_with(
+            &mut tx,
+            product_id,
+            location_id,
+            difference,
+            Decimal::ZERO,
+        )
+        .await?;
+
+        if difference < Decimal::ZERO {
+            StockBatchService::remove_available(&mut tx, product_id, location_id, -difference)
+                .await?;
+        } else if difference > Decimal::ZERO {
+            // Surplus stock of unknown origin, so no expiry date
+            StockBatchService::receive(
+                &mut tx,
+                product_id,
+                location_id,
+                difference,
+                None,
+                Some("Stock count surplus"),
+            )
+            .await?;
+        }
+
+        StockMovementService::record(
+            &mut tx,
+            product_id,
+            location_id,
+            difference,
+            Decimal::ZERO,
+            StockMovementReason::StockCount,
+            context,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit stock count: {}", e)))?;
+
+        if !difference.is_zero() {
+            info!(
+                "Stock count for product {} at location {} corrected on-hand by {}",
+                product_id, location_id, difference
+            );
+        }
+        Ok(difference)
+    }
+
+    /// Remove unsellable stock from a location. Only available stock can be written off,
+    /// reserved units stay with their orders.
+    pub async fn write_off(
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        BillOfMaterialsService::ensure_not_composed_with(&mut tx, product_id).await?;
+
+        let (quantity_on_hand, quantity_reserved) =
+            StockLocationService::lock_with(&mut tx, product_id, location_id).await?;
+
+        if quantity_on_hand - quantity_reserved < quantity {
+            tx.rollback().await.ok();
+            return Err(AppError::ValidationError(format!(
+                "Cannot write off {} units of product {}. Insufficient available quantity.",
+                quantity, product_id
+            )));
+        }
+
+        StockLocationService::apply_with(
+            &mut tx,
+            product_id,
+            location_id,
+            -quantity,
+            Decimal::ZERO,
+        )
+        .await?;
+
+        StockBatchService::remove_available(&mut tx, product_id, location_id, quantity).await?;
+
+        StockMovementService::record(
+            &mut tx,
+            product_id,
+            location_id,
+            -quantity,
+            Decimal::ZERO,
+            StockMovementReason::WriteOff,
+            context,
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit inventory write-off: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Get low stock products: active products whose available quantity is at or below
+    /// their reorder threshold. Products without their own threshold use
+    /// `default_threshold`; `override_threshold` replaces all thresholds.
     pub async fn get_low_stock_products(
-        threshold: Decimal,
+        default_threshold: Decimal,
+        override_threshold: Option<Decimal>,
     ) -> Result<Vec<LowStockProduct>, AppError> {
         let pool = pool();
 
@@ -435,14 +959,18 @@ impl InventoryService {
                 p.sku,
                 i.quantity_on_hand,
                 i.quantity_reserved,
-                (i.quantity_on_hand - i.quantity_reserved) as available_quantity
+                (i.quantity_on_hand - i.quantity_reserved) as available_quantity,
+                COALESCE($2, i.reorder_threshold, $1) as reorder_threshold
             FROM inventory i
             JOIN products p ON i.product_id = p.id
-            WHERE (i.quantity_on_hand - i.quantity_reserved) <= $1 AND p.is_active = true
+            WHERE (i.quantity_on_hand - i.quantity_reserved) <= COALESCE($2, i.reorder_threshold, $1)
+            AND p.is_active = true
+            AND NOT EXISTS (SELECT 1 FROM bouquet_components bc WHERE bc.bouquet_id = i.product_id)
             ORDER BY (i.quantity_on_hand - i.quantity_reserved) ASC
             "#,
         )
-        .bind(threshold)
+        .bind(default_threshold)
+        .bind(override_threshold)
         .fetch_all(pool)
         .await
         .map_err(|e| {
@@ -458,12 +986,114 @@ impl InventoryService {
                 quantity_on_hand: row.get("quantity_on_hand"),
                 quantity_reserved: row.get("quantity_reserved"),
                 available_quantity: row.get("available_quantity"),
+                reorder_threshold: row.get("reorder_threshold"),
             })
             .collect();
 
         Ok(products)
     }
 
+    /// Set the reorder threshold of a product; `None` falls back to the default threshold
+    pub async fn set_reorder_threshold(
+        product_id: Uuid,
+        reorder_threshold: Option<Decimal>,
+    ) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result =
+            sqlx::query("UPDATE inventory SET reorder_threshold = $1 WHERE product_id = $2")
+                .bind(reorder_threshold)
+                .bind(product_id)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to update reorder threshold: {}", e))
+                })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Product {} not found in inventory",
+                product_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set the backorder policy of a product; `None` only sells what is in stock. Bouquets
+    /// with a recipe follow the policies of their components.
+    pub async fn set_backorder_policy(
+        product_id: Uuid,
+        policy: Option<&BackorderPolicy>,
+    ) -> Result<(), AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        BillOfMaterialsService::ensure_not_composed_with(&mut conn, product_id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE inventory
+            SET backorder_mode = $1, backorder_limit = $2, expected_available_on = $3
+            WHERE product_id = $4
+            "#,
+        )
+        .bind(policy.map(|policy| policy.mode))
+        .bind(policy.and_then(|policy| policy.limit))
+        .bind(policy.and_then(|policy| policy.expected_available_on))
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to update backorder policy: {}", e))
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Product {} not found in inventory",
+                product_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Open orders stop waiting once none of their products, or bouquet components, is
+    /// short of stock anymore at the location they are reserved at
+    async fn clear_awaiting_stock_with(conn: &mut PgConnection) -> Result<(), AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE orders o
+            SET awaiting_stock = false, expected_ship_date = NULL, updated_at = NOW()
+            WHERE o.awaiting_stock = true
+            AND o.status IN ('pending', 'processing')
+            AND NOT EXISTS (
+                SELECT 1
+                FROM order_line ol
+                JOIN location_inventory li ON li.location_id = o.stock_location_id
+                    AND (
+                        li.product_id = ol.product_id
+                        OR li.product_id IN (
+                            SELECT bc.component_id FROM bouquet_components bc
+                            WHERE bc.bouquet_id = ol.product_id
+                        )
+                    )
+                WHERE ol.order_id = o.id
+                AND li.quantity_on_hand < li.quantity_reserved
+            )
+            "#,
+        )
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update awaiting orders: {}", e)))?;
+
+        if result.rows_affected() > 0 {
+            info!("{} orders no longer await stock", result.rows_affected());
+        }
+        Ok(())
+    }
+
     /// Get all inventory with low stock alert
     pub async fn get_inventory_status() -> Result<Vec<InventoryStatus>, AppError> {
         let pool = pool();
@@ -477,6 +1107,10 @@ impl InventoryService {
                 i.quantity_on_hand,
                 i.quantity_reserved,
                 (i.quantity_on_hand - i.quantity_reserved) as available_quantity,
+                i.reorder_threshold,
+                i.backorder_mode,
+                i.backorder_limit,
+                i.expected_available_on,
                 i.updated_at
             FROM inventory i
             JOIN products p ON i.product_id = p.id
@@ -488,6 +1122,11 @@ impl InventoryService {
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch inventory status: {}", e)))?;
 
+        let mut locations: HashMap<Uuid, Vec<LocationStock>> = HashMap::new();
+        for stock in StockLocationService::location_stock(None).await? {
+            locations.entry(stock.product_id).or_default().push(stock);
+        }
+
         let inventory_status = rows
             .into_iter()
             .map(|row| InventoryStatus {
@@ -497,16 +1136,112 @@ impl InventoryService {
                 quantity_on_hand: row.get("quantity_on_hand"),
                 quantity_reserved: row.get("quantity_reserved"),
                 available_quantity: row.get("available_quantity"),
+                reorder_threshold: row.get("reorder_threshold"),
+                backorder_policy: backorder_policy(&row),
+                locations: locations.remove(&row.get("product_id")).unwrap_or_default(),
                 updated_at: row.get("updated_at"),
             })
             .collect();
 
         Ok(inventory_status)
     }
+
+    /// Get the inventory status of a single product, including inactive products
+    pub async fn get_product_status(product_id: Uuid) -> Result<Option<InventoryStatus>, AppError> {
+        let pool = pool();
+
+        let row = sqlx::query(
+            r#"
+            SELECT
+                i.product_id,
+                p.name,
+                p.sku,
+                i.quantity_on_hand,
+                i.quantity_reserved,
+                (i.quantity_on_hand - i.quantity_reserved) as available_quantity,
+                i.reorder_threshold,
+                i.backorder_mode,
+                i.backorder_limit,
+                i.expected_available_on,
+                i.updated_at
+            FROM inventory i
+            JOIN products p ON i.product_id = p.id
+            WHERE i.product_id = $1
+            "#,
+        )
+        .bind(product_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to fetch inventory status for product {}: {}",
+                product_id, e
+            ))
+        })?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let locations = StockLocationService::location_stock(Some(product_id)).await?;
+
+        Ok(Some(InventoryStatus {
+            product_id: row.get("product_id"),
+            name: row.get("name"),
+            sku: row.get("sku"),
+            quantity_on_hand: row.get("quantity_on_hand"),
+            quantity_reserved: row.get("quantity_reserved"),
+            available_quantity: row.get("available_quantity"),
+            reorder_threshold: row.get("reorder_threshold"),
+            backorder_policy: backorder_policy(&row),
+            locations,
+            updated_at: row.get("updated_at"),
+        }))
+    }
+}
+
+/// What is available of a product where it is checked and over all locations, and its
+/// backorder policy
+type ProductStock = (Decimal, Decimal, Option<BackorderPolicy>);
+
+/// Check each item, expanded into its stocked products, against what is available of them
+fn check_expanded(
+    expanded: &[Vec<(Uuid, Decimal)>],
+    stock: &HashMap<Uuid, ProductStock>,
+) -> Vec<StockCheck> {
+    expanded
+        .iter()
+        .map(|components| {
+            components
+                .iter()
+                .fold(StockCheck::InStock, |check, (product_id, quantity)| {
+                    let item_check = match stock.get(product_id) {
+                        Some((available, product_available, policy)) => {
+                            StockCheck::check_at_location(
+                                *available,
+                                *product_available,
+                                *quantity,
+                                policy.as_ref(),
+                            )
+                        }
+                        None => StockCheck::Insufficient, // Product not found in inventory
+                    };
+                    check.combine(item_check)
+                })
+        })
+        .collect()
+}
+
+fn backorder_policy(row: &PgRow) -> Option<BackorderPolicy> {
+    row.get::<Option<_>, _>("backorder_mode")
+        .map(|mode| BackorderPolicy {
+            mode,
+            limit: row.get("backorder_limit"),
+            expected_available_on: row.get("expected_available_on"),
+        })
 }
 
 /// Information about a product with low stock
-#[derive(Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 pub struct LowStockProduct {
     pub product_id: Uuid,
     pub name: String,
@@ -514,10 +1249,11 @@ pub struct LowStockProduct {
     pub quantity_on_hand: Decimal,
     pub quantity_reserved: Decimal,
     pub available_quantity: Decimal,
+    pub reorder_threshold: Decimal, // Threshold that applied to this product
 }
 
 /// Complete inventory status for a product
-#[derive(Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 pub struct InventoryStatus {
     pub product_id: Uuid,
     pub name: String,
@@ -525,5 +1261,8 @@ pub struct InventoryStatus {
     pub quantity_on_hand: Decimal,
     pub quantity_reserved: Decimal,
     pub available_quantity: Decimal,
+    pub reorder_threshold: Option<Decimal>, // None when the default threshold applies
+    pub backorder_policy: Option<BackorderPolicy>,
+    pub locations: Vec<LocationStock>, // Sums up to the quantities above
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
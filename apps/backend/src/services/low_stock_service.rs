@@ -0,0 +1,163 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::secrets;
+use crate::services::{
+    EmailService, InventoryService, LowStockProduct, NotificationService, OutgoingEmail,
+};
+use crate::structs::notification::NotificationKind;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tracing::{info, warn};
+
+#[derive(Serialize, Debug, Default)]
+pub struct LowStockCheckReport {
+    pub alerts_raised: usize,
+    pub alerts_cleared: u64,
+}
+
+/// Service for alerting staff when products drop to their reorder threshold
+pub struct LowStockService;
+
+impl LowStockService {
+    /// Raise an alert for every product that dropped to its threshold since the last check.
+    /// A product is alerted once; the alert is cleared when its stock recovers, so the
+    /// next drop alerts again.
+    pub async fn check_low_stock() -> Result<LowStockCheckReport, AppError> {
+        let default_threshold = secrets::get_low_stock_default_threshold();
+        let staff_email = secrets::get_staff_notification_email();
+
+        let alerts_cleared = Self::clear_recovered_alerts(default_threshold).await?;
+
+        let mut report = LowStockCheckReport {
+            alerts_cleared,
+            ..LowStockCheckReport::default()
+        };
+
+        let products = InventoryService::get_low_stock_products(default_threshold, None).await?;
+        for product in &products {
+            match Self::raise_alert(product, staff_email.as_deref()).await {
+                Ok(true) => report.alerts_raised += 1,
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "Failed to raise low stock alert for product {}: {}",
+                    product.product_id, e
+                ),
+            }
+        }
+
+        if report.alerts_raised > 0 {
+            info!("Raised {} low stock alerts", report.alerts_raised);
+        }
+        Ok(report)
+    }
+
+    /// Record the alert, add it to the admin feed and queue the staff email in one
+    /// transaction. Returns false when the product was already alerted.
+    async fn raise_alert(
+        product: &LowStockProduct,
+        staff_email: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO low_stock_alerts (product_id, available_quantity, reorder_threshold)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (product_id) DO NOTHING
+            "#,
+        )
+        .bind(product.product_id)
+        .bind(product.available_quantity)
+        .bind(product.reorder_threshold)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record low stock alert: {}", e)))?
+        .rows_affected()
+            > 0;
+
+        if !inserted {
+            tx.rollback().await.ok();
+            return Ok(false);
+        }
+
+        let title = format!("Low stock: {}", product.name);
+        let notification_id = NotificationService::notify_with(
+            &mut tx,
+            NotificationKind::LowStock,
+            &title,
+            &Self::alert_message(product),
+            Some(product.product_id),
+        )
+        .await?;
+
+        sqlx::query("UPDATE low_stock_alerts SET notification_id = $1 WHERE product_id = $2")
+            .bind(notification_id)
+            .bind(product.product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to link low stock notification: {}", e))
+            })?;
+
+        if let Some(recipient) = staff_email {
+            let email = Self::build_alert_email(product, recipient);
+            EmailService::queue_email_with(&mut tx, &email).await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit low stock alert: {}", e))
+        })?;
+
+        Ok(true)
+    }
+
+    async fn clear_recovered_alerts(default_threshold: Decimal) -> Result<u64, AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM low_stock_alerts a
+            USING inventory i
+            WHERE a.product_id = i.product_id
+            AND (i.quantity_on_hand - i.quantity_reserved) > COALESCE(i.reorder_threshold, $1)
+            "#,
+        )
+        .bind(default_threshold)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to clear recovered low stock alerts: {}", e))
+        })?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub fn alert_message(product: &LowStockProduct) -> String {
+        format!(
+            "{} ({}) has {} available ({} on hand, {} reserved), at or below its reorder threshold of {}.",
+            product.name,
+            product.sku,
+            product.available_quantity,
+            product.quantity_on_hand,
+            product.quantity_reserved,
+            product.reorder_threshold
+        )
+    }
+
+    /// Build the staff email for a low stock alert
+    pub fn build_alert_email(product: &LowStockProduct, recipient: &str) -> OutgoingEmail {
+        OutgoingEmail {
+            recipient: recipient.to_string(),
+            subject: format!("Low stock: {} ({})", product.name, product.sku),
+            body: format!(
+                "{}\n\nTime to reorder. You will not get another alert for this product until its stock recovers.",
+                Self::alert_message(product)
+            ),
+            template: "low_stock_alert".to_string(),
+        }
+    }
+}
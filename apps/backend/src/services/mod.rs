@@ -1,17 +1,60 @@
+pub mod abandoned_cart_service;
 pub mod auth;
+pub mod back_in_stock_service;
+pub mod bill_of_materials_service;
 pub mod cart_service;
+pub mod email_service;
+pub mod featured_service;
+pub mod forecast_service;
+pub mod image_storage;
 pub mod inventory_service;
+pub mod low_stock_service;
+pub mod notification_service;
+pub mod price_history_service;
 pub mod pricing_service;
+pub mod product_image_service;
 pub mod product_service;
 pub mod promotion_service;
+pub mod purchase_order_service;
+pub mod quote_service;
+pub mod recommendation_service;
+pub mod reconciliation_service;
+pub mod review_service;
 pub mod search;
+pub mod stock_batch_service;
+pub mod stock_location_service;
+pub mod stock_movement_service;
+pub mod supplier_service;
+pub mod taxonomy_service;
+pub mod wishlist_service;
 
+pub use abandoned_cart_service::AbandonedCartService;
 pub use auth::AuthService;
+pub use back_in_stock_service::BackInStockService;
+pub use bill_of_materials_service::BillOfMaterialsService;
 pub use cart_service::CartService;
+pub use email_service::{EmailService, OutgoingEmail};
+pub use featured_service::FeaturedService;
+pub use forecast_service::ForecastService;
 pub use inventory_service::{InventoryService, InventoryStatus, LowStockProduct};
+pub use low_stock_service::{LowStockCheckReport, LowStockService};
+pub use notification_service::NotificationService;
+pub use price_history_service::PriceHistoryService;
 pub use pricing_service::{PricingResult, PricingService, ProductDiscountInfo};
+pub use product_image_service::ProductImageService;
 pub use product_service::{ProductPriceInfo, ProductService};
 pub use promotion_service::PromotionService;
+pub use purchase_order_service::PurchaseOrderService;
+pub use quote_service::QuoteService;
+pub use recommendation_service::RecommendationService;
+pub use reconciliation_service::ReconciliationService;
+pub use review_service::ReviewService;
 pub use search::{
     ProductSearchService, SearchAnalyticsService, SearchService, SearchSuggestionsService,
 };
+pub use stock_batch_service::StockBatchService;
+pub use stock_location_service::StockLocationService;
+pub use stock_movement_service::StockMovementService;
+pub use supplier_service::SupplierService;
+pub use taxonomy_service::TaxonomyService;
+pub use wishlist_service::WishlistService;
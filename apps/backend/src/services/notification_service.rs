@@ -0,0 +1,83 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::notification::{AdminNotification, NotificationKind};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+const DEFAULT_NOTIFICATION_LIMIT: i64 = 50;
+const MAX_NOTIFICATION_LIMIT: i64 = 200;
+
+/// Service for the admin notification feed
+pub struct NotificationService;
+
+impl NotificationService {
+    /// Add a notification on an existing connection, so it can share a transaction with the
+    /// change that triggered it
+    pub async fn notify_with(
+        conn: &mut PgConnection,
+        kind: NotificationKind,
+        title: &str,
+        message: &str,
+        product_id: Option<Uuid>,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO admin_notifications (kind, title, message, product_id) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(kind)
+        .bind(title)
+        .bind(message)
+        .bind(product_id)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to create notification: {}", e)))
+    }
+
+    /// Notifications, newest first
+    pub async fn list_notifications(
+        unread_only: bool,
+        limit: Option<i64>,
+    ) -> Result<Vec<AdminNotification>, AppError> {
+        let pool = pool();
+        let limit = limit
+            .unwrap_or(DEFAULT_NOTIFICATION_LIMIT)
+            .clamp(1, MAX_NOTIFICATION_LIMIT);
+
+        sqlx::query_as::<_, AdminNotification>(
+            r#"
+            SELECT id, kind, title, message, product_id, created_at, read_at, read_by
+            FROM admin_notifications
+            WHERE NOT $1 OR read_at IS NULL
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(unread_only)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch notifications: {}", e)))
+    }
+
+    /// Mark a notification as read. Reading it again keeps the first reader.
+    pub async fn mark_read(
+        notification_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<AdminNotification, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, AdminNotification>(
+            r#"
+            UPDATE admin_notifications
+            SET read_at = COALESCE(read_at, NOW()), read_by = COALESCE(read_by, $2)
+            WHERE id = $1
+            RETURNING id, kind, title, message, product_id, created_at, read_at, read_by
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update notification: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Notification"))
+    }
+}
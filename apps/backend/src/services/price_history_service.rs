@@ -0,0 +1,351 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::price_history::{
+    PriceChange, PriceChangeSource, PriceHistory, ReferencePrice, SchedulePriceChangeRequest,
+    ScheduledPriceChange, lowest_price_since,
+};
+use crate::structs::product::price_components;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tracing::{error, info};
+use uuid::Uuid;
+
+const SCHEDULED_COLUMNS: &str = "id, product_id, new_price, effective_at, status, created_by, \
+     created_at, applied_at, cancelled_at";
+
+/// Service for the list price history of products and scheduled price changes
+pub struct PriceHistoryService;
+
+impl PriceHistoryService {
+    /// Record a change of a product's list price. Nothing is recorded when the price stays
+    /// the same.
+    pub async fn record_change_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        old_price: Option<Decimal>,
+        new_price: Decimal,
+        source: PriceChangeSource,
+        changed_by: Option<Uuid>,
+        scheduled_change_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        if old_price == Some(new_price) {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO product_price_history (
+                product_id, old_price, new_price, source, changed_by, scheduled_change_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(product_id)
+        .bind(old_price)
+        .bind(new_price)
+        .bind(source)
+        .bind(changed_by)
+        .bind(scheduled_change_id)
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record price change: {}", e)))?;
+        Ok(())
+    }
+
+    /// The price changes of a product, newest first, with its pending scheduled changes
+    pub async fn price_history(product_id: Uuid) -> Result<PriceHistory, AppError> {
+        let pool = pool();
+
+        let current_price = Self::current_price(product_id).await?;
+
+        let changes = Self::changes_since(product_id, None).await?;
+
+        let scheduled = sqlx::query_as::<_, ScheduledPriceChange>(&format!(
+            r#"
+            SELECT {}
+            FROM scheduled_price_changes
+            WHERE product_id = $1 AND status = 'pending'
+            ORDER BY effective_at ASC
+            "#,
+            SCHEDULED_COLUMNS
+        ))
+        .bind(product_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch scheduled price changes: {}", e))
+        })?;
+
+        let reference_days = crate::secrets::get_reference_price_days();
+        let since = Utc::now() - Duration::days(reference_days);
+
+        Ok(PriceHistory {
+            product_id,
+            current_price,
+            reference_price: lowest_price_since(current_price, &changes, since),
+            reference_days,
+            changes,
+            scheduled,
+        })
+    }
+
+    /// The lowest list price of an active product over the reference period
+    pub async fn reference_price(product_id: Uuid) -> Result<ReferencePrice, AppError> {
+        let current_price = sqlx::query_scalar::<_, Decimal>(
+            "SELECT price FROM products WHERE id = $1 AND is_active = true",
+        )
+        .bind(product_id)
+        .fetch_optional(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        let reference_days = crate::secrets::get_reference_price_days();
+        let since = Utc::now() - Duration::days(reference_days);
+        let changes = Self::changes_since(product_id, Some(since)).await?;
+
+        Ok(ReferencePrice {
+            product_id,
+            current_price,
+            reference_price: lowest_price_since(current_price, &changes, since),
+            reference_days,
+        })
+    }
+
+    /// The reference price of several products at once, e.g. the promoted products in a
+    /// basket, in one query. Inactive products are included; products that are not found
+    /// are left out.
+    pub async fn reference_prices(
+        product_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Decimal>, AppError> {
+        if product_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let since = Utc::now() - Duration::days(crate::secrets::get_reference_price_days());
+
+        // The lowest of the current price and every price in effect since then, as in
+        // `lowest_price_since`
+        let rows = sqlx::query_as::<_, (Uuid, Decimal)>(
+            r#"
+            SELECT p.id, LEAST(p.price, MIN(h.old_price), MIN(h.new_price))
+            FROM products p
+            LEFT JOIN product_price_history h ON h.product_id = p.id AND h.changed_at >= $2
+            WHERE p.id = ANY($1)
+            GROUP BY p.id, p.price
+            "#,
+        )
+        .bind(product_ids)
+        .bind(since)
+        .fetch_all(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch reference prices: {}", e)))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Schedule a new list price for a product
+    pub async fn schedule_change(
+        product_id: Uuid,
+        request: &SchedulePriceChangeRequest,
+        created_by: Uuid,
+    ) -> Result<ScheduledPriceChange, AppError> {
+        request
+            .validate(Utc::now())
+            .map_err(AppError::ValidationError)?;
+
+        Self::current_price(product_id).await?;
+
+        sqlx::query_as::<_, ScheduledPriceChange>(&format!(
+            r#"
+            INSERT INTO scheduled_price_changes (product_id, new_price, effective_at, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            SCHEDULED_COLUMNS
+        ))
+        .bind(product_id)
+        .bind(request.new_price)
+        .bind(request.effective_at)
+        .bind(created_by)
+        .fetch_one(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to schedule price change: {}", e)))
+    }
+
+    /// Cancel a scheduled price change that has not been applied yet
+    pub async fn cancel_change(
+        product_id: Uuid,
+        change_id: Uuid,
+    ) -> Result<ScheduledPriceChange, AppError> {
+        let pool = pool();
+
+        let cancelled = sqlx::query_as::<_, ScheduledPriceChange>(&format!(
+            r#"
+            UPDATE scheduled_price_changes
+            SET status = 'cancelled', cancelled_at = now()
+            WHERE id = $1 AND product_id = $2 AND status = 'pending'
+            RETURNING {}
+            "#,
+            SCHEDULED_COLUMNS
+        ))
+        .bind(change_id)
+        .bind(product_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to cancel price change: {}", e)))?;
+
+        if let Some(cancelled) = cancelled {
+            return Ok(cancelled);
+        }
+
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM scheduled_price_changes WHERE id = $1 AND product_id = $2)",
+        )
+        .bind(change_id)
+        .bind(product_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch price change: {}", e)))?;
+
+        if exists {
+            Err(AppError::Conflict(
+                "This price change was already applied or cancelled".to_string(),
+            ))
+        } else {
+            Err(AppError::not_found("Scheduled price change"))
+        }
+    }
+
+    /// Apply the scheduled price changes that are due, oldest first. Returns how many were
+    /// applied; a change that fails is left pending and tried again on the next run.
+    pub async fn apply_due_changes() -> Result<u64, AppError> {
+        let due = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM scheduled_price_changes
+            WHERE status = 'pending' AND effective_at <= now()
+            ORDER BY effective_at ASC, created_at ASC
+            "#,
+        )
+        .fetch_all(pool())
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch due price changes: {}", e))
+        })?;
+
+        let mut applied = 0;
+        for change_id in due {
+            match Self::apply_change(change_id).await {
+                Ok(true) => applied += 1,
+                Ok(false) => {}
+                Err(e) => error!("Failed to apply price change {}: {}", change_id, e),
+            }
+        }
+
+        if applied > 0 {
+            info!("Applied {} scheduled price changes", applied);
+        }
+        Ok(applied)
+    }
+
+    /// Apply one scheduled change. Returns false when it was cancelled or applied meanwhile.
+    async fn apply_change(change_id: Uuid) -> Result<bool, AppError> {
+        let mut tx = pool()
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let change = sqlx::query_as::<_, ScheduledPriceChange>(&format!(
+            r#"
+            SELECT {}
+            FROM scheduled_price_changes
+            WHERE id = $1 AND status = 'pending'
+            FOR UPDATE SKIP LOCKED
+            "#,
+            SCHEDULED_COLUMNS
+        ))
+        .bind(change_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch price change: {}", e)))?;
+        let Some(change) = change else {
+            return Ok(false);
+        };
+
+        let old_price =
+            sqlx::query_scalar::<_, Decimal>("SELECT price FROM products WHERE id = $1 FOR UPDATE")
+                .bind(change.product_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?;
+
+        let (subtotal, tax) = price_components(change.new_price);
+        sqlx::query(
+            "UPDATE products SET price = $2, subtotal = $3, tax = $4, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(change.product_id)
+        .bind(change.new_price)
+        .bind(subtotal)
+        .bind(tax)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update price: {}", e)))?;
+
+        Self::record_change_with(
+            &mut tx,
+            change.product_id,
+            Some(old_price),
+            change.new_price,
+            PriceChangeSource::Scheduled,
+            change.created_by,
+            Some(change.id),
+        )
+        .await?;
+
+        sqlx::query(
+            "UPDATE scheduled_price_changes SET status = 'applied', applied_at = now() WHERE id = $1",
+        )
+        .bind(change.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update price change: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit price change: {}", e))
+        })?;
+        Ok(true)
+    }
+
+    /// Price changes of a product, newest first, optionally only those since a moment
+    async fn changes_since(
+        product_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PriceChange>, AppError> {
+        sqlx::query_as::<_, PriceChange>(
+            r#"
+            SELECT h.id, h.product_id, h.old_price, h.new_price, h.source, h.changed_by,
+                   u.email AS changed_by_email, h.scheduled_change_id, h.changed_at
+            FROM product_price_history h
+            LEFT JOIN users u ON u.id = h.changed_by
+            WHERE h.product_id = $1 AND ($2::timestamptz IS NULL OR h.changed_at >= $2)
+            ORDER BY h.changed_at DESC
+            "#,
+        )
+        .bind(product_id)
+        .bind(since)
+        .fetch_all(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch price history: {}", e)))
+    }
+
+    async fn current_price(product_id: Uuid) -> Result<Decimal, AppError> {
+        sqlx::query_scalar::<_, Decimal>("SELECT price FROM products WHERE id = $1")
+            .bind(product_id)
+            .fetch_optional(pool())
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Product"))
+    }
+}
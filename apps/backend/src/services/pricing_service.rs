@@ -1,6 +1,6 @@
 use crate::response::AppResponse;
 use crate::services::product_service::{ProductPriceInfo, ProductService};
-use crate::structs::order::IncomingOrder;
+use crate::structs::order::{IncomingOrder, OrderContent};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -55,6 +55,22 @@ impl PricingService {
         AppResponse::Success(pricing_result)
     }
 
+    /// Price a basket with the currently active promotions, without an expected total
+    pub async fn price_items(items: &[OrderContent]) -> AppResponse<PricingResult> {
+        let products = match ProductService::fetch_items_with_pricing(items).await {
+            AppResponse::Success(products) => products,
+            AppResponse::Error(err) => return AppResponse::Error(err),
+        };
+
+        AppResponse::Success(Self::pricing_from_products(&products))
+    }
+
+    /// Rebuild a pricing result from previously calculated product prices (e.g. a quote)
+    pub fn pricing_from_products(products: &[ProductPriceInfo]) -> PricingResult {
+        let final_total = ProductService::calculate_total_from_products(products);
+        Self::build_pricing_result(products, final_total)
+    }
+
     /// Simple price validation without discount calculations
     /// Use this for basic order validation when discounts aren't needed
     pub async fn validate_order_pricing(order: &IncomingOrder) -> AppResponse<PricingResult> {
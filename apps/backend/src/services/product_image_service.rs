@@ -0,0 +1,520 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::services::image_storage::{ImageStorage, image_storage};
+use crate::structs::product::ProductImage;
+use crate::structs::product_image::{
+    ImageFormat, ImageRendition, ImageSize, ReorderImagesRequest, UpdateImageRequest,
+    validate_alt_text,
+};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader, Limits};
+use sqlx::PgConnection;
+use sqlx::types::Json;
+use std::collections::HashSet;
+use std::io::Cursor;
+use uuid::Uuid;
+
+const IMAGE_COLUMNS: &str = "id, product_id, url, alt_text, is_primary, sort_order, renditions";
+const MAX_IMAGE_DIMENSION: u32 = 8000;
+
+/// An image encoded in one size and format, ready to be stored
+#[derive(Debug)]
+pub struct RenderedImage {
+    pub size: ImageSize,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Decode an upload and encode every size both as WebP and in a widely supported format:
+/// JPEG for photos, PNG for images with transparency. Uploads in one of those formats are
+/// kept as they are for the original size.
+pub fn render_image(bytes: &[u8]) -> Result<Vec<RenderedImage>, String> {
+    let upload_format = match image::guess_format(bytes) {
+        Ok(image::ImageFormat::Jpeg) => ImageFormat::Jpeg,
+        Ok(image::ImageFormat::Png) => ImageFormat::Png,
+        Ok(image::ImageFormat::WebP) => ImageFormat::Webp,
+        _ => return Err("Only JPEG, PNG and WebP images are supported".to_string()),
+    };
+
+    // Check the size from the header before decoding anything, so a small file that
+    // claims huge dimensions is never expanded in memory
+    let (width, height) = image_reader(bytes)?
+        .into_dimensions()
+        .map_err(|_| "The image could not be read")?;
+    if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        return Err(format!(
+            "Images can be at most {0}x{0} pixels",
+            MAX_IMAGE_DIMENSION
+        ));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    let mut reader = image_reader(bytes)?;
+    reader.limits(limits);
+    let image = reader.decode().map_err(|_| "The image could not be read")?;
+
+    let fallback_format = match upload_format {
+        ImageFormat::Webp if image.color().has_alpha() => ImageFormat::Png,
+        ImageFormat::Webp => ImageFormat::Jpeg,
+        format => format,
+    };
+
+    let mut rendered = Vec::new();
+    for size in ImageSize::ALL {
+        let resized = match size.max_dimension() {
+            Some(max) if width > max || height > max => {
+                image.resize(max, max, FilterType::Lanczos3)
+            }
+            _ => image.clone(),
+        };
+
+        for format in [fallback_format, ImageFormat::Webp] {
+            let bytes = if size == ImageSize::Original && format == upload_format {
+                bytes.to_vec()
+            } else {
+                encode(&resized, format)?
+            };
+            rendered.push(RenderedImage {
+                size,
+                format,
+                width: resized.width(),
+                height: resized.height(),
+                bytes,
+            });
+        }
+    }
+
+    Ok(rendered)
+}
+
+fn image_reader(bytes: &[u8]) -> Result<ImageReader<Cursor<&[u8]>>, String> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|_| "The image could not be read".to_string())
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, String> {
+    let mut bytes = Cursor::new(Vec::new());
+    let result = match format {
+        // JPEG has no alpha channel and WebP is only written from 8-bit RGBA
+        ImageFormat::Jpeg => {
+            DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut bytes, image::ImageFormat::Jpeg)
+        }
+        ImageFormat::Png => image.write_to(&mut bytes, image::ImageFormat::Png),
+        ImageFormat::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_to(&mut bytes, image::ImageFormat::WebP),
+    };
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(bytes.into_inner())
+}
+
+/// Service for uploading and arranging product images
+pub struct ProductImageService;
+
+impl ProductImageService {
+    /// The images of a product, primary first and then in their display order
+    pub async fn list_images(product_id: Uuid) -> Result<Vec<ProductImage>, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::ensure_product_with(&mut conn, product_id).await?;
+        Self::list_images_with(&mut conn, product_id).await
+    }
+
+    /// Store an uploaded image in all its sizes and add it after the product's other images.
+    /// The first image of a product always becomes its primary image.
+    pub async fn upload_image(
+        product_id: Uuid,
+        bytes: Vec<u8>,
+        alt_text: Option<String>,
+        is_primary: bool,
+    ) -> Result<ProductImage, AppError> {
+        let alt_text = alt_text
+            .map(|alt_text| alt_text.trim().to_string())
+            .filter(|alt_text| !alt_text.is_empty());
+        if let Some(alt_text) = &alt_text {
+            validate_alt_text(alt_text).map_err(AppError::ValidationError)?;
+        }
+
+        let pool = pool();
+        {
+            let mut conn = pool.acquire().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to acquire connection: {}", e))
+            })?;
+            Self::ensure_product_with(&mut conn, product_id).await?;
+        }
+
+        // Decoding and resizing is CPU heavy, keep it off the async workers
+        let rendered = tokio::task::spawn_blocking(move || render_image(&bytes))
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Image processing failed: {}", e)))?
+            .map_err(AppError::ValidationError)?;
+
+        let storage = image_storage();
+        let image_id = Uuid::new_v4();
+        let mut storage_keys = Vec::new();
+        let mut renditions = Vec::new();
+        for image in rendered {
+            let key = format!(
+                "products/{}/{}/{}.{}",
+                product_id,
+                image_id,
+                image.size.as_str(),
+                image.format.extension()
+            );
+            let url = match storage
+                .put(&key, image.bytes, image.format.content_type())
+                .await
+            {
+                Ok(url) => url,
+                Err(e) => {
+                    Self::remove_files(storage, &storage_keys).await;
+                    return Err(e);
+                }
+            };
+            storage_keys.push(key);
+            renditions.push(ImageRendition {
+                size: image.size,
+                format: image.format,
+                width: image.width,
+                height: image.height,
+                url,
+            });
+        }
+
+        match Self::insert_image(
+            product_id,
+            image_id,
+            alt_text,
+            is_primary,
+            &renditions,
+            &storage_keys,
+        )
+        .await
+        {
+            Ok(image) => Ok(image),
+            Err(e) => {
+                Self::remove_files(storage, &storage_keys).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Change the alt text of an image or make it the primary image
+    pub async fn update_image(
+        product_id: Uuid,
+        image_id: Uuid,
+        request: &UpdateImageRequest,
+    ) -> Result<ProductImage, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_product_with(&mut tx, product_id).await?;
+
+        if request.is_primary == Some(true) {
+            Self::clear_primary_with(&mut tx, product_id).await?;
+        }
+
+        let image = sqlx::query_as::<_, ProductImage>(&format!(
+            r#"
+            UPDATE product_images
+            SET alt_text = COALESCE($3, alt_text),
+                is_primary = is_primary OR COALESCE($4, false)
+            WHERE id = $1 AND product_id = $2
+            RETURNING {}
+            "#,
+            IMAGE_COLUMNS
+        ))
+        .bind(image_id)
+        .bind(product_id)
+        .bind(request.alt_text.as_deref().map(str::trim))
+        .bind(request.is_primary)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update image: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Image"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit image: {}", e)))?;
+
+        Ok(image)
+    }
+
+    /// Put the images of a product in the given order
+    pub async fn reorder_images(
+        product_id: Uuid,
+        request: &ReorderImagesRequest,
+    ) -> Result<Vec<ProductImage>, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_product_with(&mut tx, product_id).await?;
+
+        let current: HashSet<Uuid> =
+            sqlx::query_scalar::<_, Uuid>("SELECT id FROM product_images WHERE product_id = $1")
+                .bind(product_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch images: {}", e)))?
+                .into_iter()
+                .collect();
+        let requested: HashSet<Uuid> = request.image_ids.iter().copied().collect();
+        if requested.len() != request.image_ids.len() || requested != current {
+            return Err(AppError::ValidationError(
+                "List every image of the product exactly once".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE product_images pi
+            SET sort_order = o.position::int
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS o(id, position)
+            WHERE pi.id = o.id AND pi.product_id = $1
+            "#,
+        )
+        .bind(product_id)
+        .bind(&request.image_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to reorder images: {}", e)))?;
+
+        let images = Self::list_images_with(&mut tx, product_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit images: {}", e)))?;
+
+        Ok(images)
+    }
+
+    /// Remove an image and its files. When it was the primary image, the next one in line
+    /// takes its place.
+    pub async fn delete_image(product_id: Uuid, image_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_product_with(&mut tx, product_id).await?;
+
+        let (storage_keys, was_primary) = sqlx::query_as::<_, (Vec<String>, bool)>(
+            r#"
+            DELETE FROM product_images
+            WHERE id = $1 AND product_id = $2
+            RETURNING storage_keys, is_primary
+            "#,
+        )
+        .bind(image_id)
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to delete image: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Image"))?;
+
+        if was_primary {
+            sqlx::query(
+                r#"
+                UPDATE product_images SET is_primary = true
+                WHERE id = (
+                    SELECT id FROM product_images
+                    WHERE product_id = $1
+                    ORDER BY sort_order ASC, created_at ASC
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to update images: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit image: {}", e)))?;
+
+        Self::remove_files(image_storage(), &storage_keys).await;
+        Ok(())
+    }
+
+    /// Remove all images of a product and their files. Returns how many were removed.
+    pub async fn delete_all_images(product_id: Uuid) -> Result<u64, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_product_with(&mut tx, product_id).await?;
+
+        let storage_keys = sqlx::query_scalar::<_, Vec<String>>(
+            "DELETE FROM product_images WHERE product_id = $1 RETURNING storage_keys",
+        )
+        .bind(product_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to delete images: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit images: {}", e)))?;
+
+        let removed = storage_keys.len() as u64;
+        Self::remove_files(image_storage(), &storage_keys.concat()).await;
+        Ok(removed)
+    }
+
+    async fn insert_image(
+        product_id: Uuid,
+        image_id: Uuid,
+        alt_text: Option<String>,
+        is_primary: bool,
+        renditions: &[ImageRendition],
+        storage_keys: &[String],
+    ) -> Result<ProductImage, AppError> {
+        let url = renditions
+            .iter()
+            .find(|rendition| {
+                rendition.size == ImageSize::Original && rendition.format != ImageFormat::Webp
+            })
+            .map(|rendition| rendition.url.clone())
+            .ok_or_else(|| AppError::internal_error("Image has no original rendition"))?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_product_with(&mut tx, product_id).await?;
+
+        let has_images = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM product_images WHERE product_id = $1)",
+        )
+        .bind(product_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch images: {}", e)))?;
+
+        let is_primary = is_primary || !has_images;
+        if is_primary {
+            Self::clear_primary_with(&mut tx, product_id).await?;
+        }
+
+        // Without alt text the product name describes the image
+        let image = sqlx::query_as::<_, ProductImage>(&format!(
+            r#"
+            INSERT INTO product_images
+                (id, product_id, url, alt_text, is_primary, sort_order, renditions, storage_keys)
+            SELECT
+                $1, p.id, $3, COALESCE($4, p.name), $5,
+                COALESCE((SELECT MAX(sort_order) + 1 FROM product_images WHERE product_id = p.id), 0),
+                $6, $7
+            FROM products p
+            WHERE p.id = $2
+            RETURNING {}
+            "#,
+            IMAGE_COLUMNS
+        ))
+        .bind(image_id)
+        .bind(product_id)
+        .bind(&url)
+        .bind(alt_text)
+        .bind(is_primary)
+        .bind(Json(renditions))
+        .bind(storage_keys)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to save image: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit image: {}", e)))?;
+
+        Ok(image)
+    }
+
+    async fn list_images_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<Vec<ProductImage>, AppError> {
+        sqlx::query_as::<_, ProductImage>(&format!(
+            r#"
+            SELECT {} FROM product_images
+            WHERE product_id = $1
+            ORDER BY is_primary DESC, sort_order ASC, created_at ASC
+            "#,
+            IMAGE_COLUMNS
+        ))
+        .bind(product_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch images: {}", e)))
+    }
+
+    async fn ensure_product_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<(), AppError> {
+        let exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM products WHERE id = $1)")
+                .bind(product_id)
+                .fetch_one(conn)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?;
+        if !exists {
+            return Err(AppError::not_found("Product"));
+        }
+        Ok(())
+    }
+
+    /// Serialize changes to the images of one product, so it keeps exactly one primary image
+    async fn lock_product_with(conn: &mut PgConnection, product_id: Uuid) -> Result<(), AppError> {
+        sqlx::query_scalar::<_, Uuid>("SELECT id FROM products WHERE id = $1 FOR UPDATE")
+            .bind(product_id)
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to lock product: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Product"))?;
+        Ok(())
+    }
+
+    async fn clear_primary_with(conn: &mut PgConnection, product_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE product_images SET is_primary = false WHERE product_id = $1 AND is_primary",
+        )
+        .bind(product_id)
+        .execute(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update images: {}", e)))?;
+        Ok(())
+    }
+
+    /// Best effort: the images are already gone from the catalogue, so a file that cannot
+    /// be removed is logged rather than reported
+    async fn remove_files(storage: &dyn ImageStorage, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = storage.delete(key).await {
+                tracing::warn!("Failed to remove stored image {}: {:?}", key, e);
+            }
+        }
+    }
+}
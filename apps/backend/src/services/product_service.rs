@@ -1,11 +1,24 @@
 use crate::actions::get::get_product_by_id;
+use crate::actions::get::product::{attach_variants, get_product_by_id_including_inactive};
 use crate::pool::connect::pool;
 use crate::response::{AppResponse, error::AppError};
-use crate::structs::order::{IncomingOrder, ProductEntry};
+use crate::services::{InventoryService, PriceHistoryService, PromotionService};
+use crate::structs::enums::{Colors, Size};
+use crate::structs::locale::Locale;
+use crate::structs::order::{IncomingOrder, OrderContent, ProductEntry};
+use crate::structs::price_history::{PriceChangeSource, was_price};
+use crate::structs::product::{
+    CreateProductRequest, Product, ProductTranslation, ProductTranslationRequest, SlugLookup,
+    UpdateProductRequest, normalize_sku, price_components,
+};
+use crate::structs::promotion::DiscountPromotionWithProducts;
+use crate::utils::slug::{first_free_slug, is_slug_of, slugify};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
 use uuid::Uuid;
 
 /// Product data with pricing information for calculations
@@ -19,13 +32,9 @@ pub struct ProductPriceInfo {
     pub best_discount_percentage: Decimal,
     pub discounted_price: Decimal,
     pub final_line_total: Decimal,
-}
-
-/// Discount promotion data from database
-#[derive(Debug, FromRow)]
-struct DiscountPromotionRow {
-    discount_percentage: Decimal,
-    product_ids: Vec<Uuid>,
+    pub applied_promotion_id: Option<Uuid>,
+    #[serde(default)]
+    pub reference_price: Option<Decimal>, // "Was" price to show with a promotion, display only
 }
 
 /// Service for handling product data fetching and price calculations
@@ -37,19 +46,45 @@ impl ProductService {
     pub async fn fetch_products_with_pricing(
         order: &IncomingOrder,
     ) -> AppResponse<Vec<ProductPriceInfo>> {
-        let pool = pool();
+        Self::fetch_items_with_pricing(&order.items).await
+    }
+
+    /// Fetch pricing information for a basket that is not (yet) an order
+    pub async fn fetch_items_with_pricing(
+        items: &[OrderContent],
+    ) -> AppResponse<Vec<ProductPriceInfo>> {
         let mut product_infos = Vec::new();
 
-        // Fetch all active promotions once
-        let promotions = match Self::fetch_active_promotions(pool).await {
-            Ok(promos) => promos,
+        // Fetch the active promotions for all products in the basket once
+        let product_ids: Vec<Uuid> = items
+            .iter()
+            .flat_map(|content| content.product.iter().map(|entry| entry.product_id))
+            .collect();
+        let promotions =
+            match PromotionService::get_active_promotions_for_products(&product_ids).await {
+                AppResponse::Success(promos) => promos,
+                AppResponse::Error(err) => return AppResponse::Error(err),
+            };
+
+        // The "was" prices of the promoted products, loaded once as well
+        let promoted_ids: Vec<Uuid> = product_ids
+            .iter()
+            .filter(|product_id| {
+                promotions
+                    .iter()
+                    .any(|promotion| promotion.applies_to_product(product_id))
+            })
+            .copied()
+            .collect();
+        let reference_prices = match PriceHistoryService::reference_prices(&promoted_ids).await {
+            Ok(reference_prices) => reference_prices,
             Err(err) => return AppResponse::Error(err),
         };
 
-        // Process each product in the order
-        for content in &order.items {
+        // Process each product in the basket
+        for content in items {
             for entry in &content.product {
-                match Self::process_product_entry(entry, &promotions).await {
+                match Self::process_product_entry(entry, &promotions, &reference_prices).await {
                     Ok(product_info) => product_infos.push(product_info),
                     Err(err) => return AppResponse::Error(err),
                 }
@@ -104,6 +139,8 @@ impl ProductService {
                             best_discount_percentage: dec!(0),
                             discounted_price: product.price,
                             final_line_total: line_total,
+                            applied_promotion_id: None,
+                            reference_price: None,
                         };
 
                         product_infos.push(product_info);
@@ -127,26 +164,535 @@ impl ProductService {
         AppResponse::Success(product_infos)
     }
 
-    /// Internal: Fetch active discount promotions
-    async fn fetch_active_promotions(
-        pool: &sqlx::PgPool,
-    ) -> Result<Vec<DiscountPromotionRow>, AppError> {
-        sqlx::query_as::<_, DiscountPromotionRow>(
+    /// A product as the admin sees it, also when it or its variants are deactivated
+    pub async fn get_admin_product(product_id: Uuid) -> Result<Product, AppError> {
+        let mut product = get_product_by_id_including_inactive(product_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Product"))?;
+
+        attach_variants(std::slice::from_mut(&mut product), true)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch variants: {}", e)))?;
+
+        Ok(product)
+    }
+
+    /// Add a product to the catalogue together with its inventory record. The starting price
+    /// is the first entry of its price history.
+    pub async fn create_product(
+        request: &CreateProductRequest,
+        created_by: Uuid,
+    ) -> Result<Product, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+        let sku = normalize_sku(&request.sku).map_err(AppError::ValidationError)?;
+        let (subtotal, tax) = price_components(request.price);
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::ensure_unique_sku_with(&mut tx, &sku, None).await?;
+        if let Some(parent_id) = request.parent_id {
+            Self::ensure_parent_with(&mut tx, parent_id).await?;
+            Self::ensure_unique_variant_with(
+                &mut tx,
+                parent_id,
+                &request.size,
+                &request.colors,
+                None,
+            )
+            .await?;
+        }
+
+        let slug = Self::free_slug_with(&mut tx, request.name.trim(), None).await?;
+
+        let product_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO products (
+                name, sku, price, tax, subtotal, description, size, colors, product_type,
+                parent_id, slug
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+        )
+        .bind(request.name.trim())
+        .bind(&sku)
+        .bind(request.price)
+        .bind(tax)
+        .bind(subtotal)
+        .bind(request.description.trim())
+        .bind(&request.size)
+        .bind(&request.colors)
+        .bind(&request.product_type)
+        .bind(request.parent_id)
+        .bind(&slug)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_write_error)?;
+
+        InventoryService::initialize_inventory_with(
+            &mut tx,
+            product_id,
+            request.initial_stock.unwrap_or(Decimal::ZERO),
+        )
+        .await?;
+
+        PriceHistoryService::record_change_with(
+            &mut tx,
+            product_id,
+            None,
+            request.price,
+            PriceChangeSource::Created,
+            Some(created_by),
+            None,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit product: {}", e)))?;
+
+        info!("Created product {} ({})", product_id, sku);
+        Self::get_admin_product(product_id).await
+    }
+
+    /// Update a product. A new price is split into subtotal and tax again and recorded in
+    /// the price history.
+    pub async fn update_product(
+        product_id: Uuid,
+        request: &UpdateProductRequest,
+        updated_by: Uuid,
+    ) -> Result<Product, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+        let sku = request
+            .sku
+            .as_deref()
+            .map(normalize_sku)
+            .transpose()
+            .map_err(AppError::ValidationError)?;
+        let components = request.price.map(price_components);
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let (parent_id, size, colors, current_slug, current_price) = sqlx::query_as::<
+            _,
+            (Option<Uuid>, Size, Vec<Colors>, String, Decimal),
+        >(
+            "SELECT parent_id, size, colors, slug, price FROM products WHERE id = $1 FOR UPDATE",
+        )
+        .bind(product_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        if let Some(sku) = &sku {
+            Self::ensure_unique_sku_with(&mut tx, sku, Some(product_id)).await?;
+        }
+
+        // A variant's size and colours must stay distinct within its family
+        if request.size.is_some() || request.colors.is_some() {
+            Self::ensure_unique_variant_with(
+                &mut tx,
+                parent_id.unwrap_or(product_id),
+                request.size.as_ref().unwrap_or(&size),
+                request.colors.as_deref().unwrap_or(&colors),
+                Some(product_id),
+            )
+            .await?;
+        }
+
+        // A new name gets a new slug; the old one keeps leading to the product
+        let slug = match request.name.as_deref().map(str::trim) {
+            Some(name) if !is_slug_of(&current_slug, &slugify(name)) => {
+                let slug = Self::free_slug_with(&mut tx, name, Some(product_id)).await?;
+                Self::move_slug_with(&mut tx, product_id, &current_slug, &slug).await?;
+                Some(slug)
+            }
+            _ => None,
+        };
+
+        let result = sqlx::query(
             r#"
-            SELECT discount_percentage, product_ids
-            FROM discount_promotions
-            WHERE NOW() BETWEEN start_date AND end_date
+            UPDATE products
+            SET
+                name = COALESCE($2, name),
+                sku = COALESCE($3, sku),
+                price = COALESCE($4, price),
+                subtotal = COALESCE($5, subtotal),
+                tax = COALESCE($6, tax),
+                description = COALESCE($7, description),
+                size = COALESCE($8, size),
+                colors = COALESCE($9, colors),
+                product_type = COALESCE($10, product_type),
+                slug = COALESCE($11, slug),
+                updated_at = NOW()
+            WHERE id = $1
             "#,
         )
+        .bind(product_id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(sku)
+        .bind(request.price)
+        .bind(components.map(|(subtotal, _)| subtotal))
+        .bind(components.map(|(_, tax)| tax))
+        .bind(request.description.as_deref().map(str::trim))
+        .bind(&request.size)
+        .bind(&request.colors)
+        .bind(&request.product_type)
+        .bind(slug)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_write_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Product"));
+        }
+
+        if let Some(price) = request.price {
+            PriceHistoryService::record_change_with(
+                &mut tx,
+                product_id,
+                Some(current_price),
+                price,
+                PriceChangeSource::Manual,
+                Some(updated_by),
+                None,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit product: {}", e)))?;
+
+        Self::get_admin_product(product_id).await
+    }
+
+    /// Deactivate or reactivate a product. Deactivated products are hidden from the shop but
+    /// keep their stock and order history. The variants of a parent follow along.
+    pub async fn set_active(product_id: Uuid, is_active: bool) -> Result<Product, AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            "UPDATE products SET is_active = $2, updated_at = NOW() WHERE id = $1 OR parent_id = $1",
+        )
+        .bind(product_id)
+        .bind(is_active)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update product: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Product"));
+        }
+
+        info!(
+            "Product {} {}",
+            product_id,
+            if is_active {
+                "reactivated"
+            } else {
+                "deactivated"
+            }
+        );
+        Self::get_admin_product(product_id).await
+    }
+
+    async fn ensure_unique_sku_with(
+        conn: &mut PgConnection,
+        sku: &str,
+        product_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM products WHERE lower(sku) = lower($1) AND ($2::uuid IS NULL OR id <> $2))",
+        )
+        .bind(sku)
+        .bind(product_id)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check SKU: {}", e)))?;
+
+        if taken {
+            return Err(AppError::Conflict(format!(
+                "A product with SKU {} already exists",
+                sku
+            )));
+        }
+        Ok(())
+    }
+
+    /// Variants hang directly under a parent, which cannot be a variant itself
+    async fn ensure_parent_with(conn: &mut PgConnection, parent_id: Uuid) -> Result<(), AppError> {
+        let grandparent_id =
+            sqlx::query_scalar::<_, Option<Uuid>>("SELECT parent_id FROM products WHERE id = $1")
+                .bind(parent_id)
+                .fetch_optional(conn)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to fetch parent product: {}", e))
+                })?
+                .ok_or_else(|| AppError::not_found("Parent product"))?;
+
+        if grandparent_id.is_some() {
+            return Err(AppError::ValidationError(
+                "A variant cannot have variants of its own".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// No two products in a family (the parent and its variants) share a size and colours.
+    /// The parent row stays locked until the transaction ends, so two variants with the same
+    /// size and colours can't be added to a family at the same time.
+    async fn ensure_unique_variant_with(
+        conn: &mut PgConnection,
+        parent_id: Uuid,
+        size: &Size,
+        colors: &[Colors],
+        product_id: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        sqlx::query("SELECT id FROM products WHERE id = $1 FOR UPDATE")
+            .bind(parent_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to lock product: {}", e)))?;
+
+        let taken = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM products
+                WHERE (id = $1 OR parent_id = $1)
+                AND size = $2
+                AND colors @> $3 AND colors <@ $3
+                AND ($4::uuid IS NULL OR id <> $4)
+            )
+            "#,
+        )
+        .bind(parent_id)
+        .bind(size)
+        .bind(colors)
+        .bind(product_id)
+        .fetch_one(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check variants: {}", e)))?;
+
+        if taken {
+            return Err(AppError::Conflict(
+                "This product already has a variant with this size and colours".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Find an active product by its slug, or where an earlier slug of it leads
+    pub async fn resolve_slug(slug: &str) -> Result<SlugLookup, AppError> {
+        let pool = pool();
+        let slug = slug.trim().to_lowercase();
+
+        let product_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM products WHERE slug = $1 AND is_active = true",
+        )
+        .bind(&slug)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?;
+        if let Some(product_id) = product_id {
+            return Ok(SlugLookup::Current(product_id));
+        }
+
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT p.slug
+            FROM product_slug_redirects r
+            JOIN products p ON p.id = r.product_id
+            WHERE r.slug = $1 AND p.is_active = true
+            "#,
+        )
+        .bind(&slug)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .map(SlugLookup::Moved)
+        .ok_or_else(|| AppError::not_found("Product"))
+    }
+
+    /// The translations of a product, e.g. its English name and description
+    pub async fn list_translations(product_id: Uuid) -> Result<Vec<ProductTranslation>, AppError> {
+        let pool = pool();
+
+        Self::ensure_product_exists(product_id).await?;
+
+        sqlx::query_as::<_, ProductTranslation>(
+            r#"
+            SELECT product_id, locale, name, description, updated_at
+            FROM product_translations
+            WHERE product_id = $1
+            ORDER BY locale
+            "#,
+        )
+        .bind(product_id)
         .fetch_all(pool)
         .await
-        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch discount promotions: {}", e)))
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch translations: {}", e)))
+    }
+
+    /// Add or replace the translation of a product in a locale other than the default
+    pub async fn set_translation(
+        product_id: Uuid,
+        locale: Locale,
+        request: &ProductTranslationRequest,
+    ) -> Result<ProductTranslation, AppError> {
+        request
+            .validate(locale)
+            .map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        Self::ensure_product_exists(product_id).await?;
+
+        sqlx::query_as::<_, ProductTranslation>(
+            r#"
+            INSERT INTO product_translations (product_id, locale, name, description)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (product_id, locale) DO UPDATE
+            SET name = EXCLUDED.name, description = EXCLUDED.description, updated_at = now()
+            RETURNING product_id, locale, name, description, updated_at
+            "#,
+        )
+        .bind(product_id)
+        .bind(locale)
+        .bind(request.name.trim())
+        .bind(request.description.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to save translation: {}", e)))
+    }
+
+    /// Remove a translation; the product is then shown in the default locale there
+    pub async fn delete_translation(product_id: Uuid, locale: Locale) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result =
+            sqlx::query("DELETE FROM product_translations WHERE product_id = $1 AND locale = $2")
+                .bind(product_id)
+                .bind(locale)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to delete translation: {}", e))
+                })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Translation"));
+        }
+        Ok(())
+    }
+
+    async fn ensure_product_exists(product_id: Uuid) -> Result<(), AppError> {
+        let exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM products WHERE id = $1)")
+                .bind(product_id)
+                .fetch_one(pool())
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?;
+
+        if !exists {
+            return Err(AppError::not_found("Product"));
+        }
+        Ok(())
+    }
+
+    /// The slug for a product with this name: the name's slug, numbered when another product
+    /// has it or had it before. A product may take back one of its own earlier slugs.
+    async fn free_slug_with(
+        conn: &mut PgConnection,
+        name: &str,
+        product_id: Option<Uuid>,
+    ) -> Result<String, AppError> {
+        let base = slugify(name);
+
+        let taken: HashSet<String> = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT slug FROM products
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND ($2::uuid IS NULL OR id <> $2)
+            UNION
+            SELECT slug FROM product_slug_redirects
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND ($2::uuid IS NULL OR product_id <> $2)
+            "#,
+        )
+        .bind(&base)
+        .bind(product_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check slugs: {}", e)))?
+        .into_iter()
+        .collect();
+
+        Ok(first_free_slug(&base, &taken))
+    }
+
+    /// Keep the old slug as a redirect, and drop the redirect for the new slug if the product
+    /// is taking an earlier slug back
+    async fn move_slug_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO product_slug_redirects (slug, product_id)
+            VALUES ($1, $2)
+            ON CONFLICT (slug) DO UPDATE SET product_id = EXCLUDED.product_id, created_at = now()
+            "#,
+        )
+        .bind(old_slug)
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to keep old slug: {}", e)))?;
+
+        sqlx::query("DELETE FROM product_slug_redirects WHERE slug = $1 AND product_id = $2")
+            .bind(new_slug)
+            .bind(product_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to update slugs: {}", e)))?;
+        Ok(())
+    }
+
+    fn map_write_error(error: sqlx::Error) -> AppError {
+        match error {
+            // Two products named alike at the same moment
+            sqlx::Error::Database(db_error)
+                if db_error.is_unique_violation()
+                    && db_error.constraint() == Some("idx_products_slug") =>
+            {
+                AppError::Conflict(
+                    "Another product was just given the same slug, please try again".to_string(),
+                )
+            }
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict("A product with this SKU already exists".to_string())
+            }
+            e => AppError::DatabaseError(format!("Failed to save product: {}", e)),
+        }
     }
 
     /// Internal: Process a single product entry with discount calculation
     async fn process_product_entry(
         entry: &ProductEntry,
-        promotions: &[DiscountPromotionRow],
+        promotions: &[DiscountPromotionWithProducts],
+        reference_prices: &HashMap<Uuid, Decimal>,
     ) -> Result<ProductPriceInfo, AppError> {
         // Fetch product data
         let product = match get_product_by_id(entry.product_id).await {
@@ -168,16 +714,33 @@ impl ProductService {
         let quantity_decimal = Decimal::from(entry.quantity);
         let line_total = product.price * quantity_decimal;
 
-        // Find best applicable discount
-        let best_discount = Self::find_best_discount(entry.product_id, promotions);
+        // Find best applicable promotion
+        let best_promotion = Self::find_best_promotion(entry.product_id, product.price, promotions)
+            .filter(|_| product.price > dec!(0));
 
         // Calculate discounted price
-        let discounted_price = if best_discount > dec!(0) {
-            product.price * (Decimal::ONE - best_discount / dec!(100))
-        } else {
-            product.price
+        let (discounted_price, best_discount) = match best_promotion {
+            Some(promotion) => {
+                let discount_amount = promotion.calculate_discount_amount(product.price);
+                (
+                    promotion.calculate_discounted_price(product.price),
+                    (discount_amount / product.price * dec!(100)).round_dp(2),
+                )
+            }
+            None => (product.price, dec!(0)),
         };
 
+        // A promotion is shown against the reference price (the lowest list price of the
+        // last days), not against a list price that may just have been raised. The discount
+        // itself stays the difference with the list price.
+        let reference_price = best_promotion.map(|_| {
+            let reference_price = reference_prices
+                .get(&product.id)
+                .copied()
+                .unwrap_or(product.price);
+            was_price(reference_price, discounted_price)
+        });
+
         let final_line_total = discounted_price * quantity_decimal;
 
         Ok(ProductPriceInfo {
@@ -189,16 +752,20 @@ impl ProductService {
             best_discount_percentage: best_discount,
             discounted_price: discounted_price.round_dp(2),
             final_line_total: final_line_total.round_dp(2),
+            applied_promotion_id: best_promotion.map(|promotion| promotion.id),
+            reference_price,
         })
     }
 
-    /// Internal: Find the best discount percentage for a product
-    fn find_best_discount(product_id: Uuid, promotions: &[DiscountPromotionRow]) -> Decimal {
+    /// Internal: Find the promotion that gives the highest discount for a product
+    fn find_best_promotion(
+        product_id: Uuid,
+        price: Decimal,
+        promotions: &[DiscountPromotionWithProducts],
+    ) -> Option<&DiscountPromotionWithProducts> {
         promotions
             .iter()
-            .filter(|promo| promo.product_ids.contains(&product_id))
-            .map(|promo| promo.discount_percentage)
-            .max()
-            .unwrap_or(dec!(0))
+            .filter(|promo| promo.applies_to_product(&product_id))
+            .max_by_key(|promo| promo.calculate_discount_amount(price))
     }
 }
@@ -100,6 +100,33 @@ impl PromotionService {
         AppResponse::Success(best_promotion)
     }
 
+    /// Get the price each product currently sells for, with the best active promotion applied
+    pub async fn get_current_prices(
+        products: &[(Uuid, Decimal)],
+    ) -> AppResponse<HashMap<Uuid, Decimal>> {
+        let product_ids: Vec<Uuid> = products.iter().map(|(id, _)| *id).collect();
+
+        let promotions = match Self::get_active_promotions_for_products(&product_ids).await {
+            AppResponse::Success(promotions) => promotions,
+            AppResponse::Error(e) => return AppResponse::Error(e),
+        };
+
+        let prices = products
+            .iter()
+            .map(|(product_id, price)| {
+                let current_price = promotions
+                    .iter()
+                    .filter(|p| p.applies_to_product(product_id))
+                    .map(|p| p.calculate_discounted_price(*price))
+                    .min()
+                    .unwrap_or(*price);
+                (*product_id, current_price.round_dp(2))
+            })
+            .collect();
+
+        AppResponse::Success(prices)
+    }
+
     /// Get all active promotions
     pub async fn get_all_active_promotions() -> AppResponse<Vec<DiscountPromotionWithProducts>> {
         let pool = pool();
@@ -307,6 +334,10 @@ impl PromotionService {
                 p.size,
                 p.colors,
                 p.product_type,
+                p.parent_id,
+                p.slug,
+                rs.average_rating,
+                COALESCE(rs.review_count, 0) AS review_count,
                 (i.quantity_on_hand - i.quantity_reserved) AS available_stock,
                 -- discounted price calculation
                 COALESCE((
@@ -327,7 +358,8 @@ impl PromotionService {
                     LIMIT 1
                 ), p.price) AS discounted_price
             FROM products p
-            JOIN inventory i ON p.id = i.product_id
+            JOIN product_stock i ON p.id = i.product_id
+            LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
             WHERE p.id = ANY($1)
               AND p.is_active = true;
         "#;
@@ -353,6 +385,11 @@ impl PromotionService {
                         product_type: row.get("product_type"),
                         stock: row.get("available_stock"),
                         images: None,
+                        parent_id: row.get("parent_id"),
+                        slug: row.get("slug"),
+                        average_rating: row.get("average_rating"),
+                        review_count: row.get("review_count"),
+                        variants: None,
                     };
                     products.push(product);
                 }
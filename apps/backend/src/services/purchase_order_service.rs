@@ -0,0 +1,553 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::secrets;
+use crate::services::{ForecastService, InventoryService, StockLocationService};
+use crate::structs::purchase_order::{
+    PurchaseOrder, PurchaseOrderLine, PurchaseOrderRequest, PurchaseOrderStatus,
+    PurchaseOrderWithLines, ReceivePurchaseOrderRequest, SuggestedPurchase, suggested_quantity,
+};
+use crate::structs::stock_movement::MovementContext;
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+const PURCHASE_ORDER_SELECT: &str = r#"
+    SELECT po.id, po.reference, po.supplier_id, s.name AS supplier_name, po.status,
+           po.expected_on, po.notes, po.created_by, po.created_at, po.updated_at, po.sent_at,
+           po.received_at
+    FROM purchase_orders po
+    JOIN suppliers s ON s.id = po.supplier_id
+"#;
+
+/// Service for purchase orders: what we bought, from whom, and what has come in
+pub struct PurchaseOrderService;
+
+impl PurchaseOrderService {
+    /// Purchase orders, newest first
+    pub async fn list_purchase_orders(
+        status: Option<PurchaseOrderStatus>,
+    ) -> Result<Vec<PurchaseOrder>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, PurchaseOrder>(&format!(
+            "{} WHERE $1::text IS NULL OR po.status = $1 ORDER BY po.created_at DESC",
+            PURCHASE_ORDER_SELECT
+        ))
+        .bind(status)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch purchase orders: {}", e)))
+    }
+
+    pub async fn get_purchase_order(
+        purchase_order_id: Uuid,
+    ) -> Result<PurchaseOrderWithLines, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::get_purchase_order_with(&mut conn, purchase_order_id).await
+    }
+
+    /// Create a draft purchase order
+    pub async fn create_purchase_order(
+        request: &PurchaseOrderRequest,
+        user_id: Uuid,
+    ) -> Result<PurchaseOrderWithLines, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::ensure_active_supplier_with(&mut tx, request.supplier_id).await?;
+
+        let purchase_order_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO purchase_orders (reference, supplier_id, expected_on, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(PurchaseOrder::generate_reference())
+        .bind(request.supplier_id)
+        .bind(request.expected_on)
+        .bind(&request.notes)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to create purchase order: {}", e)))?;
+
+        Self::insert_lines_with(&mut tx, purchase_order_id, request).await?;
+        let purchase_order = Self::get_purchase_order_with(&mut tx, purchase_order_id).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit purchase order: {}", e))
+        })?;
+
+        info!(
+            "Created purchase order {} with {} lines",
+            purchase_order.purchase_order.reference,
+            purchase_order.lines.len()
+        );
+        Ok(purchase_order)
+    }
+
+    /// Replace the supplier, date, notes and lines of a draft
+    pub async fn update_draft(
+        purchase_order_id: Uuid,
+        request: &PurchaseOrderRequest,
+    ) -> Result<PurchaseOrderWithLines, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_with_status(&mut tx, purchase_order_id, &[PurchaseOrderStatus::Draft]).await?;
+        Self::ensure_active_supplier_with(&mut tx, request.supplier_id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE purchase_orders
+            SET supplier_id = $2, expected_on = $3, notes = $4, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(purchase_order_id)
+        .bind(request.supplier_id)
+        .bind(request.expected_on)
+        .bind(&request.notes)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update purchase order: {}", e)))?;
+
+        sqlx::query("DELETE FROM purchase_order_lines WHERE purchase_order_id = $1")
+            .bind(purchase_order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to clear purchase order lines: {}", e))
+            })?;
+
+        Self::insert_lines_with(&mut tx, purchase_order_id, request).await?;
+        let purchase_order = Self::get_purchase_order_with(&mut tx, purchase_order_id).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit purchase order: {}", e))
+        })?;
+
+        Ok(purchase_order)
+    }
+
+    /// Delete a draft that was never sent
+    pub async fn delete_draft(purchase_order_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("DELETE FROM purchase_orders WHERE id = $1 AND status = 'draft'")
+            .bind(purchase_order_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to delete purchase order: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Draft purchase order not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Mark a draft as sent to the supplier. From then on its stock counts as on order.
+    pub async fn mark_sent(purchase_order_id: Uuid) -> Result<PurchaseOrderWithLines, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_with_status(&mut tx, purchase_order_id, &[PurchaseOrderStatus::Draft]).await?;
+
+        sqlx::query(
+            "UPDATE purchase_orders SET status = 'sent', sent_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(purchase_order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update purchase order: {}", e)))?;
+
+        let purchase_order = Self::get_purchase_order_with(&mut tx, purchase_order_id).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit purchase order: {}", e))
+        })?;
+
+        Ok(purchase_order)
+    }
+
+    /// Book a delivery against a purchase order. The received units are added to the stock
+    /// in the same transaction as the purchase order update.
+    pub async fn receive(
+        purchase_order_id: Uuid,
+        request: &ReceivePurchaseOrderRequest,
+        user_id: Uuid,
+    ) -> Result<PurchaseOrderWithLines, AppError> {
+        if request.lines.is_empty() {
+            return Err(AppError::ValidationError("Nothing to receive".to_string()));
+        }
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        Self::lock_with_status(
+            &mut tx,
+            purchase_order_id,
+            &[
+                PurchaseOrderStatus::Sent,
+                PurchaseOrderStatus::PartiallyReceived,
+            ],
+        )
+        .await?;
+        let purchase_order = Self::get_purchase_order_with(&mut tx, purchase_order_id).await?;
+
+        let note = match &request.note {
+            Some(note) => format!("{}: {}", purchase_order.purchase_order.reference, note),
+            None => format!(
+                "{} from {}",
+                purchase_order.purchase_order.reference,
+                purchase_order.purchase_order.supplier_name
+            ),
+        };
+        let context = MovementContext::by_user(user_id).with_note(note);
+        let location_id = match request.location_id {
+            Some(location_id) => location_id,
+            None => StockLocationService::default_location_with(&mut tx).await?,
+        };
+
+        for received in &request.lines {
+            if !purchase_order
+                .lines
+                .iter()
+                .any(|line| line.product_id == received.product_id)
+            {
+                return Err(AppError::ValidationError(format!(
+                    "Product {} is not on this purchase order",
+                    received.product_id
+                )));
+            }
+            if received.quantity <= Decimal::ZERO {
+                return Err(AppError::ValidationError(format!(
+                    "Received quantity of product {} must be greater than zero",
+                    received.product_id
+                )));
+            }
+        }
+
+        // A product received on several lines is checked on its total, so together they
+        // can't exceed what is outstanding
+        for (product_id, quantity) in request.quantity_per_product() {
+            let outstanding = purchase_order
+                .lines
+                .iter()
+                .find(|line| line.product_id == product_id)
+                .map(|line| line.quantity_outstanding())
+                .unwrap_or_default();
+            if quantity > outstanding {
+                return Err(AppError::ValidationError(format!(
+                    "Received {} units of product {}, but only {} are outstanding",
+                    quantity, product_id, outstanding
+                )));
+            }
+        }
+
+        for received in &request.lines {
+            let line = purchase_order
+                .lines
+                .iter()
+                .find(|line| line.product_id == received.product_id)
+                .ok_or_else(|| AppError::not_found("Purchase order line"))?;
+
+            sqlx::query(
+                "UPDATE purchase_order_lines SET quantity_received = quantity_received + $1 WHERE id = $2",
+            )
+            .bind(received.quantity)
+            .bind(line.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to update purchase order line: {}", e))
+            })?;
+
+            InventoryService::add_inventory_with(
+                &mut tx,
+                received.product_id,
+                location_id,
+                received.quantity,
+                received.expires_on,
+                &context,
+            )
+            .await?;
+        }
+
+        let lines = Self::get_lines_with(&mut tx, purchase_order_id).await?;
+        let status = PurchaseOrderWithLines::status_after_receipt(&lines);
+
+        sqlx::query(
+            r#"
+            UPDATE purchase_orders
+            SET
+                status = $2,
+                received_at = CASE WHEN $2 = 'received' THEN NOW() ELSE received_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(purchase_order_id)
+        .bind(status)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update purchase order: {}", e)))?;
+
+        let purchase_order = Self::get_purchase_order_with(&mut tx, purchase_order_id).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit purchase order receipt: {}", e))
+        })?;
+
+        info!(
+            "Received {} lines on purchase order {}",
+            request.lines.len(),
+            purchase_order.purchase_order.reference
+        );
+        Ok(purchase_order)
+    }
+
+    /// Products to buy: active products whose available stock plus what is on order will not
+    /// cover their reorder threshold and the forecast demand for the next `days` days. Orders
+    /// for delivery within the window are shown as upcoming demand; they are already reserved,
+    /// so they lower the available stock.
+    pub async fn suggested_purchases(days: i32) -> Result<Vec<SuggestedPurchase>, AppError> {
+        let pool = pool();
+        let forecast = ForecastService::stock_demand(days).await?;
+
+        let mut suggestions = sqlx::query_as::<_, SuggestedPurchase>(
+            r#"
+            WITH on_order AS (
+                SELECT l.product_id, SUM(l.quantity - l.quantity_received) AS quantity
+                FROM purchase_order_lines l
+                JOIN purchase_orders po ON po.id = l.purchase_order_id
+                WHERE po.status IN ('sent', 'partially_received')
+                GROUP BY l.product_id
+            ),
+            upcoming AS (
+                SELECT
+                    COALESCE(bc.component_id, ol.product_id) AS product_id,
+                    SUM(ol.quantity * COALESCE(bc.quantity, 1)) AS quantity
+                FROM orders o
+                JOIN order_line ol ON ol.order_id = o.id
+                LEFT JOIN bouquet_components bc ON bc.bouquet_id = ol.product_id
+                WHERE o.status IN ('pending', 'processing')
+                AND COALESCE(o.delivery_date, o.created_at::date)
+                    BETWEEN CURRENT_DATE AND CURRENT_DATE + $2
+                GROUP BY 1
+            ),
+            last_purchase AS (
+                SELECT DISTINCT ON (l.product_id) l.product_id, po.supplier_id, l.cost_price
+                FROM purchase_order_lines l
+                JOIN purchase_orders po ON po.id = l.purchase_order_id
+                WHERE po.status <> 'draft'
+                ORDER BY l.product_id, po.created_at DESC
+            )
+            SELECT
+                i.product_id,
+                p.name,
+                p.sku,
+                (i.quantity_on_hand - i.quantity_reserved) AS available_quantity,
+                COALESCE(oo.quantity, 0) AS on_order,
+                COALESCE(u.quantity, 0) AS upcoming_demand,
+                COALESCE(i.reorder_threshold, $1) AS reorder_threshold,
+                lp.supplier_id AS last_supplier_id,
+                lp.cost_price AS last_cost_price
+            FROM inventory i
+            JOIN products p ON p.id = i.product_id
+            LEFT JOIN on_order oo ON oo.product_id = i.product_id
+            LEFT JOIN upcoming u ON u.product_id = i.product_id
+            LEFT JOIN last_purchase lp ON lp.product_id = i.product_id
+            WHERE p.is_active = true
+            AND NOT EXISTS (SELECT 1 FROM bouquet_components bc WHERE bc.bouquet_id = i.product_id)
+            ORDER BY p.name ASC
+            "#,
+        )
+        .bind(secrets::get_low_stock_default_threshold())
+        .bind(days)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to compute suggested purchases: {}", e))
+        })?;
+
+        for suggestion in &mut suggestions {
+            suggestion.forecast_demand = forecast
+                .get(&suggestion.product_id)
+                .copied()
+                .unwrap_or_default()
+                .round_dp(2);
+            suggestion.suggested_quantity = suggested_quantity(
+                suggestion.available_quantity,
+                suggestion.on_order,
+                suggestion.reorder_threshold,
+                suggestion.upcoming_demand,
+                suggestion.forecast_demand,
+            );
+        }
+        suggestions.retain(|suggestion| suggestion.suggested_quantity > Decimal::ZERO);
+        suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.suggested_quantity));
+
+        Ok(suggestions)
+    }
+
+    async fn get_purchase_order_with(
+        conn: &mut PgConnection,
+        purchase_order_id: Uuid,
+    ) -> Result<PurchaseOrderWithLines, AppError> {
+        let purchase_order = sqlx::query_as::<_, PurchaseOrder>(&format!(
+            "{} WHERE po.id = $1",
+            PURCHASE_ORDER_SELECT
+        ))
+        .bind(purchase_order_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch purchase order: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Purchase order"))?;
+
+        let lines = Self::get_lines_with(conn, purchase_order_id).await?;
+        Ok(PurchaseOrderWithLines::new(purchase_order, lines))
+    }
+
+    async fn get_lines_with(
+        conn: &mut PgConnection,
+        purchase_order_id: Uuid,
+    ) -> Result<Vec<PurchaseOrderLine>, AppError> {
+        sqlx::query_as::<_, PurchaseOrderLine>(
+            r#"
+            SELECT
+                l.id, l.purchase_order_id, l.product_id, p.name AS product_name,
+                p.sku AS product_sku, l.quantity, l.quantity_received, l.cost_price
+            FROM purchase_order_lines l
+            JOIN products p ON p.id = l.product_id
+            WHERE l.purchase_order_id = $1
+            ORDER BY p.name ASC
+            "#,
+        )
+        .bind(purchase_order_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch purchase order lines: {}", e))
+        })
+    }
+
+    /// Lines can only hold products we keep stock of; bouquets with a recipe are bought
+    /// as their components
+    async fn insert_lines_with(
+        conn: &mut PgConnection,
+        purchase_order_id: Uuid,
+        request: &PurchaseOrderRequest,
+    ) -> Result<(), AppError> {
+        let product_ids: Vec<Uuid> = request.lines.iter().map(|line| line.product_id).collect();
+        let stocked = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT p.id FROM products p
+            WHERE p.id = ANY($1)
+            AND NOT EXISTS (SELECT 1 FROM bouquet_components bc WHERE bc.bouquet_id = p.id)
+            "#,
+        )
+        .bind(&product_ids)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch products: {}", e)))?;
+
+        if let Some(missing) = product_ids.iter().find(|id| !stocked.contains(id)) {
+            return Err(AppError::ValidationError(format!(
+                "Product {} does not exist or is assembled from a recipe",
+                missing
+            )));
+        }
+
+        for line in &request.lines {
+            sqlx::query(
+                r#"
+                INSERT INTO purchase_order_lines (purchase_order_id, product_id, quantity, cost_price)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(purchase_order_id)
+            .bind(line.product_id)
+            .bind(line.quantity)
+            .bind(line.cost_price.round_dp(2))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to save purchase order line: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_active_supplier_with(
+        conn: &mut PgConnection,
+        supplier_id: Uuid,
+    ) -> Result<(), AppError> {
+        let is_active =
+            sqlx::query_scalar::<_, bool>("SELECT is_active FROM suppliers WHERE id = $1")
+                .bind(supplier_id)
+                .fetch_optional(conn)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch supplier: {}", e)))?
+                .ok_or_else(|| AppError::not_found("Supplier"))?;
+
+        if !is_active {
+            return Err(AppError::ValidationError(
+                "This supplier is no longer active".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Lock a purchase order, failing unless it has one of the given statuses
+    async fn lock_with_status(
+        conn: &mut PgConnection,
+        purchase_order_id: Uuid,
+        allowed: &[PurchaseOrderStatus],
+    ) -> Result<(), AppError> {
+        let status = sqlx::query_scalar::<_, PurchaseOrderStatus>(
+            "SELECT status FROM purchase_orders WHERE id = $1 FOR UPDATE",
+        )
+        .bind(purchase_order_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch purchase order: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Purchase order"))?;
+
+        if !allowed.contains(&status) {
+            return Err(AppError::Conflict(format!(
+                "This is not possible for a purchase order that is {}",
+                status
+            )));
+        }
+        Ok(())
+    }
+}
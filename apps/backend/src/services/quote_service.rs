@@ -0,0 +1,204 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::secrets;
+use crate::services::{PricingResult, PricingService, ProductService};
+use crate::structs::order::{IncomingOrder, OrderContent};
+use crate::structs::quote::{PriceQuote, QuoteClaims, QuoteResponse};
+use crate::utils::tax::Tax;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use uuid::Uuid;
+
+/// Service for locking basket prices between the price check and placing the order
+pub struct QuoteService;
+
+impl QuoteService {
+    /// Price a basket and store the result as a quote that is valid for
+    /// `QUOTE_VALIDITY_MINUTES`
+    pub async fn create_quote(
+        user_id: Uuid,
+        items: &[OrderContent],
+    ) -> Result<QuoteResponse, AppError> {
+        let pricing = PricingService::price_items(items).await.into_result()?;
+        let total_with_tax = Tax::total_with_tax(pricing.final_total);
+        let expires_at = Utc::now() + Duration::minutes(secrets::get_quote_validity_minutes());
+
+        let pool = pool();
+
+        let quote_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO price_quotes (
+                user_id, products, subtotal_before_discount, total_discount_amount,
+                final_total, total_with_tax, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(sqlx::types::Json(&pricing.products))
+        .bind(pricing.subtotal_before_discount)
+        .bind(pricing.total_discount_amount)
+        .bind(pricing.final_total)
+        .bind(total_with_tax)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to store price quote: {}", e)))?;
+
+        Ok(QuoteResponse {
+            quote_id: Self::sign_quote_id(quote_id, user_id, expires_at)?,
+            expires_at,
+            total_with_tax,
+            pricing,
+        })
+    }
+
+    /// Price an order with the prices locked in its quote, even when a promotion used by
+    /// the quote has ended since. Returns the quote ID together with the pricing.
+    pub async fn price_order(
+        signed_quote_id: &str,
+        order: &IncomingOrder,
+    ) -> Result<(Uuid, PricingResult), AppError> {
+        let claims = Self::verify_quote_id(signed_quote_id)?;
+        if claims.user_id != order.user_id {
+            return Err(AppError::Forbidden(
+                "This quote belongs to another customer".to_string(),
+            ));
+        }
+
+        let quote = Self::get_quote(claims.quote_id).await?;
+
+        if quote.used_at.is_some() {
+            return Err(AppError::Conflict(
+                "This quote has already been used for an order".to_string(),
+            ));
+        }
+        if quote.expires_at <= Utc::now() {
+            return Err(AppError::ValidationError(
+                "This quote has expired, please request a new one".to_string(),
+            ));
+        }
+        if !quote.covers_items(&order.items) {
+            return Err(AppError::ValidationError(
+                "The order items do not match the quoted items".to_string(),
+            ));
+        }
+
+        ProductService::validate_total_price(quote.total_with_tax, order.price)?;
+
+        Ok((
+            quote.id,
+            PricingService::pricing_from_products(&quote.products),
+        ))
+    }
+
+    /// Mark a quote as used so it cannot be redeemed twice
+    pub async fn claim_quote(quote_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            "UPDATE price_quotes SET used_at = NOW() WHERE id = $1 AND used_at IS NULL AND expires_at > NOW()",
+        )
+        .bind(quote_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to claim price quote: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "This quote has already been used or has expired".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Make a claimed quote available again after the order could not be created
+    pub async fn release_quote(quote_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        sqlx::query("UPDATE price_quotes SET used_at = NULL WHERE id = $1 AND order_id IS NULL")
+            .bind(quote_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to release price quote: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Record which order used the quote
+    pub async fn link_order(quote_id: Uuid, order_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        sqlx::query("UPDATE price_quotes SET order_id = $2 WHERE id = $1")
+            .bind(quote_id)
+            .bind(order_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to link price quote: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_quote(quote_id: Uuid) -> Result<PriceQuote, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, PriceQuote>(
+            r#"
+            SELECT id, user_id, products, subtotal_before_discount, total_discount_amount,
+                   final_total, total_with_tax, expires_at, created_at, used_at, order_id
+            FROM price_quotes
+            WHERE id = $1
+            "#,
+        )
+        .bind(quote_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch price quote: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Price quote"))
+    }
+
+    fn sign_quote_id(
+        quote_id: Uuid,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String, AppError> {
+        let secret = secrets::get_quote_signing_secret().map_err(AppError::InternalServerError)?;
+
+        let claims = QuoteClaims {
+            quote_id,
+            user_id,
+            exp: expires_at.timestamp() as usize,
+            iat: Utc::now().timestamp() as usize,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .map_err(|_| AppError::InternalServerError("Failed to sign price quote".to_string()))
+    }
+
+    fn verify_quote_id(signed_quote_id: &str) -> Result<QuoteClaims, AppError> {
+        let secret = secrets::get_quote_signing_secret().map_err(AppError::InternalServerError)?;
+
+        let mut validation = Validation::default();
+        validation.validate_exp = true;
+        validation.leeway = 0;
+
+        decode::<QuoteClaims>(
+            signed_quote_id,
+            &DecodingKey::from_secret(secret.as_ref()),
+            &validation,
+        )
+        .map(|data| data.claims)
+        .map_err(|_| {
+            AppError::ValidationError(
+                "The quote is invalid or has expired, please request a new one".to_string(),
+            )
+        })
+    }
+}
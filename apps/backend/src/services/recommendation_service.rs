@@ -0,0 +1,209 @@
+use crate::actions::get::get_product_by_id;
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::product::Product;
+use tracing::info;
+use uuid::Uuid;
+
+/// Service for "frequently bought together" and similar product recommendations. Products
+/// are related at family level: a variant counts as its parent.
+pub struct RecommendationService;
+
+impl RecommendationService {
+    /// Recompute the products bought together with each product from the order history,
+    /// keeping the top ones per product. Returns how many pairs were stored.
+    pub async fn recompute_related() -> Result<u64, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM related_products")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to clear related products: {}", e))
+            })?;
+
+        let result = sqlx::query(
+            r#"
+            WITH order_families AS (
+                SELECT DISTINCT ol.order_id, COALESCE(p.parent_id, p.id) AS family_id
+                FROM order_line ol
+                JOIN orders o ON o.id = ol.order_id
+                JOIN products p ON p.id = ol.product_id
+                WHERE o.status NOT IN ('cancelled', 'deleted')
+            ),
+            pairs AS (
+                SELECT
+                    a.family_id AS product_id,
+                    b.family_id AS related_product_id,
+                    COUNT(*) AS orders,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY a.family_id
+                        ORDER BY COUNT(*) DESC, b.family_id
+                    ) AS rank
+                FROM order_families a
+                JOIN order_families b ON b.order_id = a.order_id AND b.family_id <> a.family_id
+                GROUP BY a.family_id, b.family_id
+                HAVING COUNT(*) >= $1
+            )
+            INSERT INTO related_products (product_id, related_product_id, score, rank)
+            SELECT product_id, related_product_id, orders, rank
+            FROM pairs
+            WHERE rank <= $2
+            "#,
+        )
+        .bind(crate::secrets::get_related_products_min_orders())
+        .bind(crate::secrets::get_related_products_limit())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to store related products: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit related products: {}", e))
+        })?;
+
+        info!(
+            "Recomputed related products: {} pairs",
+            result.rows_affected()
+        );
+        Ok(result.rows_affected())
+    }
+
+    /// Products to show on a product page: those often bought with it, topped up with
+    /// similar products when it has little order history
+    pub async fn related_to_product(
+        product_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Product>, AppError> {
+        let family_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT COALESCE(parent_id, id) FROM products WHERE id = $1 AND is_active = true",
+        )
+        .bind(product_id)
+        .fetch_optional(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        Self::recommend_for(&[family_id], limit).await
+    }
+
+    /// Products to suggest for the customer's cart, leaving out what is already in it
+    pub async fn for_user_cart(user_id: Uuid, limit: i64) -> Result<Vec<Product>, AppError> {
+        let product_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT ci.product_id
+            FROM cart_items ci
+            JOIN carts c ON c.id = ci.cart_id
+            WHERE c.user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch cart: {}", e)))?;
+
+        Self::for_cart(&product_ids, limit).await
+    }
+
+    /// Products to suggest for a cart with these products, leaving out their families
+    pub async fn for_cart(product_ids: &[Uuid], limit: i64) -> Result<Vec<Product>, AppError> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let family_ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT DISTINCT COALESCE(parent_id, id) FROM products WHERE id = ANY($1)",
+        )
+        .bind(product_ids)
+        .fetch_all(pool())
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch cart products: {}", e)))?;
+
+        Self::recommend_for(&family_ids, limit).await
+    }
+
+    /// Bought-together products of the given families first, then products sharing their
+    /// categories, type or colours. The families themselves are never recommended.
+    async fn recommend_for(family_ids: &[Uuid], limit: i64) -> Result<Vec<Product>, AppError> {
+        let pool = pool();
+
+        let mut recommended = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT rp.related_product_id
+            FROM related_products rp
+            JOIN products p ON p.id = rp.related_product_id AND p.is_active = true
+            WHERE rp.product_id = ANY($1) AND NOT (rp.related_product_id = ANY($1))
+            GROUP BY rp.related_product_id
+            ORDER BY SUM(rp.score) DESC, MIN(rp.rank) ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(family_ids)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch related products: {}", e)))?;
+
+        let missing = limit - recommended.len() as i64;
+        if missing > 0 {
+            let excluded: Vec<Uuid> = family_ids.iter().chain(&recommended).copied().collect();
+            let similar = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                WITH seeds AS (
+                    SELECT product_type, colors FROM products WHERE id = ANY($1)
+                ),
+                scored AS (
+                    SELECT
+                        p.id,
+                        p.created_at,
+                        CASE WHEN EXISTS (
+                            SELECT 1 FROM product_categories pc
+                            WHERE pc.product_id = p.id
+                              AND pc.category_id IN (
+                                  SELECT category_id FROM product_categories WHERE product_id = ANY($1)
+                              )
+                        ) THEN 3 ELSE 0 END
+                        + CASE WHEN p.product_type IN (SELECT product_type FROM seeds)
+                               THEN 2 ELSE 0 END
+                        + (
+                            SELECT COUNT(*) FROM unnest(p.colors) AS color
+                            WHERE color IN (SELECT unnest(colors) FROM seeds)
+                        ) AS score
+                    FROM products p
+                    WHERE p.is_active = true
+                      AND p.parent_id IS NULL
+                      AND NOT (p.id = ANY($2))
+                )
+                SELECT id FROM scored
+                WHERE score > 0
+                ORDER BY score DESC, created_at DESC
+                LIMIT $3
+                "#,
+            )
+            .bind(family_ids)
+            .bind(&excluded)
+            .bind(missing)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to fetch similar products: {}", e))
+            })?;
+            recommended.extend(similar);
+        }
+
+        let mut products = Vec::with_capacity(recommended.len());
+        for product_id in recommended {
+            // Skipped when it went out of the catalogue in the meantime
+            if let Some(product) = get_product_by_id(product_id)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+            {
+                products.push(product);
+            }
+        }
+        Ok(products)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::actions::get::{get_order_lines_for_orders, get_orders_page};
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::order::{Order, OrderLine, OrderWithLines, TotalsMismatch};
+use crate::structs::reconciliation::{OrderTotalsFlag, ReconciliationReport};
+use std::collections::HashMap;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Number of orders checked per batch
+const RECONCILIATION_BATCH_SIZE: i64 = 500;
+
+const FLAG_SELECT: &str = r#"
+    SELECT
+        f.id, f.order_id, o.order_number, f.mismatches, f.detected_at, f.last_checked_at,
+        f.reviewed_at, f.reviewed_by, f.review_note
+    FROM order_total_flags f
+    JOIN orders o ON o.id = f.order_id
+"#;
+
+/// Service for checking recorded order totals against their order lines
+pub struct ReconciliationService;
+
+impl ReconciliationService {
+    /// Recompute the totals of every order and flag the ones that are off by more than a cent.
+    /// A reviewed flag stays reviewed as long as the differences stay the same; flags that
+    /// were never reviewed are cleared once the order adds up again. Orders are checked in
+    /// batches so the lines of all orders are never loaded at once.
+    pub async fn reconcile_orders() -> Result<ReconciliationReport, AppError> {
+        let mut orders_checked = 0;
+        let mut orders_flagged = 0;
+        let mut after = None;
+
+        loop {
+            let orders = get_orders_page(after, RECONCILIATION_BATCH_SIZE)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch orders: {}", e)))?;
+            let Some(last) = orders.last() else { break };
+            after = last.id.map(|id| (last.created_at, id));
+            let batch_size = orders.len();
+
+            let (checked, flagged) = Self::reconcile_batch(orders).await?;
+            orders_checked += checked;
+            orders_flagged += flagged;
+
+            if (batch_size as i64) < RECONCILIATION_BATCH_SIZE || after.is_none() {
+                break;
+            }
+        }
+
+        if orders_flagged > 0 {
+            info!(
+                "Order reconciliation: {} of {} orders have mismatching totals",
+                orders_flagged, orders_checked
+            );
+        }
+
+        Ok(ReconciliationReport {
+            orders_checked,
+            orders_flagged,
+            flags: Self::list_flags(false).await?,
+        })
+    }
+
+    /// Check one batch of orders. Returns how many were checked and how many flagged.
+    async fn reconcile_batch(orders: Vec<Order>) -> Result<(usize, usize), AppError> {
+        let order_ids: Vec<Uuid> = orders.iter().filter_map(|order| order.id).collect();
+        let lines = get_order_lines_for_orders(&order_ids)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch order lines: {}", e)))?;
+
+        let mut lines_by_order: HashMap<Uuid, Vec<OrderLine>> = HashMap::new();
+        for line in lines {
+            lines_by_order.entry(line.order_id).or_default().push(line);
+        }
+
+        let orders_checked = orders.len();
+        let mut orders_flagged = 0;
+        let mut matching_orders = Vec::new();
+
+        for order in orders {
+            let Some(order_id) = order.id else { continue };
+            let order_lines = lines_by_order.remove(&order_id).unwrap_or_default();
+            let mismatches = OrderWithLines::new(order, order_lines).totals_mismatches();
+
+            if mismatches.is_empty() {
+                matching_orders.push(order_id);
+                continue;
+            }
+
+            orders_flagged += 1;
+            if let Err(e) = Self::upsert_flag(order_id, &mismatches).await {
+                warn!("Failed to flag order {}: {}", order_id, e);
+            }
+        }
+
+        Self::clear_unreviewed_flags(&matching_orders).await?;
+        Ok((orders_checked, orders_flagged))
+    }
+
+    /// Flagged orders, newest first
+    pub async fn list_flags(include_reviewed: bool) -> Result<Vec<OrderTotalsFlag>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, OrderTotalsFlag>(&format!(
+            "{} WHERE $1 OR f.reviewed_at IS NULL ORDER BY f.detected_at DESC",
+            FLAG_SELECT
+        ))
+        .bind(include_reviewed)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch order flags: {}", e)))
+    }
+
+    /// Mark the flag of an order as reviewed
+    pub async fn mark_reviewed(
+        order_id: Uuid,
+        reviewer_id: Uuid,
+        note: Option<String>,
+    ) -> Result<OrderTotalsFlag, AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE order_total_flags
+            SET reviewed_at = NOW(), reviewed_by = $2, review_note = $3
+            WHERE order_id = $1
+            "#,
+        )
+        .bind(order_id)
+        .bind(reviewer_id)
+        .bind(note)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to review order flag: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "No totals flag found for order {}",
+                order_id
+            )));
+        }
+
+        sqlx::query_as::<_, OrderTotalsFlag>(&format!("{} WHERE f.order_id = $1", FLAG_SELECT))
+            .bind(order_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch order flag: {}", e)))
+    }
+
+    async fn upsert_flag(order_id: Uuid, mismatches: &[TotalsMismatch]) -> Result<(), AppError> {
+        let pool = pool();
+
+        // A review only covers the differences that were reviewed, so reopen the flag
+        // when they change
+        sqlx::query(
+            r#"
+            INSERT INTO order_total_flags (order_id, mismatches)
+            VALUES ($1, $2)
+            ON CONFLICT (order_id) DO UPDATE SET
+                mismatches = EXCLUDED.mismatches,
+                last_checked_at = NOW(),
+                reviewed_at = CASE WHEN order_total_flags.mismatches = EXCLUDED.mismatches
+                                   THEN order_total_flags.reviewed_at END,
+                reviewed_by = CASE WHEN order_total_flags.mismatches = EXCLUDED.mismatches
+                                   THEN order_total_flags.reviewed_by END,
+                review_note = CASE WHEN order_total_flags.mismatches = EXCLUDED.mismatches
+                                   THEN order_total_flags.review_note END
+            "#,
+        )
+        .bind(order_id)
+        .bind(sqlx::types::Json(mismatches))
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to save order flag: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn clear_unreviewed_flags(order_ids: &[Uuid]) -> Result<(), AppError> {
+        let pool = pool();
+
+        sqlx::query(
+            "DELETE FROM order_total_flags WHERE reviewed_at IS NULL AND order_id = ANY($1)",
+        )
+        .bind(order_ids)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to clear resolved order flags: {}", e))
+        })?;
+
+        Ok(())
+    }
+}
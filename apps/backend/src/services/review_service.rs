@@ -0,0 +1,224 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::review::{
+    ModerateReviewRequest, ModerationQueueItem, ProductReviews, PublicReview, PublicReviewRow,
+    Review, ReviewStatus, SubmitReviewRequest,
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+const REVIEW_COLUMNS: &str = "r.id, r.product_id, r.user_id, r.order_id, r.rating, r.title, r.body, \
+     r.status, r.moderation_note, r.moderated_by, r.moderated_at, r.created_at, r.updated_at";
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Service for customer reviews and their moderation
+pub struct ReviewService;
+
+impl ReviewService {
+    /// Submit a review for a product the customer has received. Reviews of a variant count
+    /// for the whole product family and wait for moderation before they are shown.
+    pub async fn submit_review(
+        user_id: Uuid,
+        product_id: Uuid,
+        request: &SubmitReviewRequest,
+    ) -> Result<Review, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        let family_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT COALESCE(parent_id, id) FROM products WHERE id = $1 AND is_active = true",
+        )
+        .bind(product_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        let order_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT o.id
+            FROM orders o
+            JOIN order_line ol ON ol.order_id = o.id
+            JOIN products p ON p.id = ol.product_id
+            WHERE o.user_id = $1
+              AND o.status = 'delivered'
+              AND (p.id = $2 OR p.parent_id = $2)
+            ORDER BY o.updated_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(family_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check purchases: {}", e)))?
+        .ok_or_else(|| {
+            AppError::Forbidden(
+                "Only customers who received this product can review it".to_string(),
+            )
+        })?;
+
+        let title = request
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+
+        sqlx::query_as::<_, Review>(&format!(
+            r#"
+            INSERT INTO reviews AS r (product_id, user_id, order_id, rating, title, body)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(family_id)
+        .bind(user_id)
+        .bind(order_id)
+        .bind(request.rating)
+        .bind(title)
+        .bind(request.body.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict("You have already reviewed this product".to_string())
+            }
+            e => AppError::DatabaseError(format!("Failed to save review: {}", e)),
+        })
+    }
+
+    /// Approved reviews of a product family, newest first, with its average rating
+    pub async fn product_reviews(
+        product_id: Uuid,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<ProductReviews, AppError> {
+        let pool = pool();
+
+        let family_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT COALESCE(parent_id, id) FROM products WHERE id = $1 AND is_active = true",
+        )
+        .bind(product_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Product"))?;
+
+        let (average_rating, review_count) = sqlx::query_as::<_, (Option<Decimal>, i64)>(
+            r#"
+            SELECT rs.average_rating, COALESCE(rs.review_count, 0)
+            FROM (SELECT $1::uuid AS product_id) p
+            LEFT JOIN product_review_stats rs ON rs.product_id = p.product_id
+            "#,
+        )
+        .bind(family_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch rating: {}", e)))?;
+
+        let reviews = sqlx::query_as::<_, PublicReviewRow>(
+            r#"
+            SELECT r.id, r.rating, r.title, r.body, u.first_name, u.last_name, r.order_id,
+                   r.created_at
+            FROM reviews r
+            JOIN users u ON u.id = r.user_id
+            WHERE r.product_id = $1 AND r.status = 'approved'
+            ORDER BY r.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(family_id)
+        .bind(limit.unwrap_or(20).clamp(1, MAX_PAGE_SIZE))
+        .bind(offset.unwrap_or(0).max(0))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch reviews: {}", e)))?
+        .into_iter()
+        .map(PublicReview::from)
+        .collect();
+
+        Ok(ProductReviews {
+            average_rating,
+            review_count,
+            reviews,
+        })
+    }
+
+    /// The reviews of the current customer, in any status
+    pub async fn reviews_for_user(user_id: Uuid) -> Result<Vec<Review>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Review>(&format!(
+            "SELECT {} FROM reviews r WHERE r.user_id = $1 ORDER BY r.created_at DESC",
+            REVIEW_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch reviews: {}", e)))
+    }
+
+    /// The moderation queue: reviews in a status, oldest first
+    pub async fn moderation_queue(
+        status: ReviewStatus,
+        limit: Option<i64>,
+    ) -> Result<Vec<ModerationQueueItem>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, ModerationQueueItem>(&format!(
+            r#"
+            SELECT {}, p.name AS product_name, u.email AS author_email
+            FROM reviews r
+            JOIN products p ON p.id = r.product_id
+            JOIN users u ON u.id = r.user_id
+            WHERE r.status = $1
+            ORDER BY r.created_at ASC
+            LIMIT $2
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(status)
+        .bind(limit.unwrap_or(50).clamp(1, MAX_PAGE_SIZE))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch reviews: {}", e)))
+    }
+
+    /// Approve or reject a review. A decision can be changed later.
+    pub async fn moderate_review(
+        review_id: Uuid,
+        moderator_id: Uuid,
+        request: &ModerateReviewRequest,
+    ) -> Result<Review, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        let note = request
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty());
+
+        sqlx::query_as::<_, Review>(&format!(
+            r#"
+            UPDATE reviews r
+            SET status = $2, moderation_note = $3, moderated_by = $4, moderated_at = now(),
+                updated_at = now()
+            WHERE r.id = $1
+            RETURNING {}
+            "#,
+            REVIEW_COLUMNS
+        ))
+        .bind(review_id)
+        .bind(request.status)
+        .bind(note)
+        .bind(moderator_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update review: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Review"))
+    }
+}
@@ -2,10 +2,12 @@ pub mod product_search;
 pub mod search_analytics;
 pub mod search_suggestions;
 
+use crate::structs::locale::Locale;
 use crate::structs::product::Product;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::Error as SqlxError;
+use uuid::Uuid;
 
 // Re-export main search functionality
 pub use product_search::ProductSearchService;
@@ -18,6 +20,8 @@ pub struct SearchQuery {
     pub filters: Option<SearchFilters>,
     pub sort: Option<SearchSort>,
     pub pagination: Option<SearchPagination>,
+    #[serde(default)]
+    pub locale: Locale, // Names and descriptions are searched and shown in this locale
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +32,8 @@ pub struct SearchFilters {
     pub price_min: Option<Decimal>,
     pub price_max: Option<Decimal>,
     pub in_stock: Option<bool>,
+    pub category_id: Option<Uuid>, // Includes the subcategories
+    pub occasion_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +49,7 @@ pub enum SearchSortField {
     Price,
     CreatedAt,
     Stock,
+    Rating, // Average of approved reviews; unreviewed products come last
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +73,21 @@ pub struct SearchResult {
     pub total_pages: u32,
     pub search_time_ms: u64,
     pub suggestions: Option<Vec<String>>,
+    pub facets: Option<SearchFacets>,
+}
+
+/// How many of the matching products are in each category and occasion
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SearchFacets {
+    pub categories: Vec<FacetCount>,
+    pub occasions: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub id: Uuid,
+    pub name: String,
+    pub count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -124,6 +146,7 @@ impl SearchService {
     pub async fn search_with_corrections(
         query: &str,
         filters: Option<SearchFilters>,
+        locale: Locale,
     ) -> Result<SearchResult, SqlxError> {
         let search_query = SearchQuery {
             query: query.to_string(),
@@ -136,6 +159,7 @@ impl SearchService {
                 page: 1,
                 per_page: 20,
             }),
+            locale,
         };
 
         let mut result = Self::search_products(search_query).await?;
@@ -153,6 +177,8 @@ impl SearchService {
                         price_min: None,
                         price_max: None,
                         in_stock: Some(true),
+                        category_id: None,
+                        occasion_id: None,
                     }),
                     sort: Some(SearchSort {
                         field: SearchSortField::Relevance,
@@ -162,6 +188,7 @@ impl SearchService {
                         page: 1,
                         per_page: 20,
                     }),
+                    locale,
                 };
 
                 let corrected_result = Self::search_products(corrected_search).await?;
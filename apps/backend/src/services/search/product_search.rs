@@ -1,14 +1,33 @@
+use crate::actions::get::product::attach_variants;
 use crate::pool::connect::pool;
 use crate::services::search::{
-    SearchFilters, SearchPagination, SearchQuery, SearchResult, SearchSort, SearchSortDirection,
-    SearchSortField,
+    FacetCount, SearchFacets, SearchFilters, SearchPagination, SearchQuery, SearchResult,
+    SearchSort, SearchSortDirection, SearchSortField,
 };
+use crate::structs::locale::Locale;
 use crate::structs::product::{Product, ProductImage};
 use rust_decimal::Decimal;
+use sqlx::postgres::{PgArguments, Postgres};
+use sqlx::query::Query;
 use sqlx::{Error as SqlxError, Row};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Rows of a product's family: the product itself and its active variants. Search terms
+/// and filters are tested against these as `v`, so a product matches through any variant.
+const FAMILY_MEMBERS: &str = "(v.id = p.id OR v.parent_id = p.id) AND v.is_active = true";
+
+/// Matches the search term against names, SKUs, descriptions, colours, types and sizes of
+/// the family row `v`, with `vt` its translation in the locale of `family_search_condition`.
+const SEARCH_MATCH: &str = r#"(
+                    LOWER(COALESCE(vt.name, v.name)) LIKE LOWER('%' || $1 || '%') OR
+                    LOWER(v.sku) LIKE LOWER('%' || $1 || '%') OR
+                    LOWER(COALESCE(vt.description, v.description)) LIKE LOWER('%' || $1 || '%') OR
+                    v.colors::text ILIKE '%' || $1 || '%' OR
+                    LOWER(v.product_type::text) LIKE LOWER('%' || $1 || '%') OR
+                    v.size::text ILIKE '%' || $1 || '%'
+                )"#;
+
 pub struct ProductSearchService;
 
 impl ProductSearchService {
@@ -19,7 +38,13 @@ impl ProductSearchService {
 
         // If no search term, return all products with filters
         if search_term.is_empty() {
-            return Self::get_filtered_products(query.filters, query.sort, query.pagination).await;
+            return Self::get_filtered_products(
+                query.filters,
+                query.sort,
+                query.pagination,
+                query.locale,
+            )
+            .await;
         }
 
         let pagination = query.pagination.unwrap_or_default();
@@ -27,12 +52,12 @@ impl ProductSearchService {
         let offset = (pagination.page - 1) * pagination.per_page;
 
         // Build the search query with full-text search and ranking
-        let mut sql_query = String::from(
+        let mut sql_query = format!(
             r#"
             WITH search_results AS (
                 SELECT
                     p.id,
-                    p.name,
+                    COALESCE(pt.name, p.name) AS name,
                     p.sku,
                     p.price,
                     COALESCE((
@@ -58,100 +83,57 @@ impl ProductSearchService {
                     p.tax,
                     p.subtotal,
                     p.is_active,
-                    p.description,
+                    COALESCE(pt.description, p.description) AS description,
                     p.created_at,
                     p.updated_at,
                     p.size,
                     p.colors,
                     p.product_type,
+                    p.parent_id,
+                    p.slug,
+                    rs.average_rating,
+                    COALESCE(rs.review_count, 0) AS review_count,
                     i.quantity_on_hand,
                     i.quantity_reserved,
                     (i.quantity_on_hand - i.quantity_reserved) as available_stock,
                     -- Full-text search ranking with weights
                     (
                         -- Exact name match gets highest score
-                        CASE WHEN LOWER(p.name) = LOWER($1) THEN 100
-                             WHEN LOWER(p.name) LIKE LOWER($1 || '%') THEN 80
-                             WHEN LOWER(p.name) LIKE LOWER('%' || $1 || '%') THEN 60
+                        CASE WHEN LOWER(COALESCE(pt.name, p.name)) = LOWER($1) THEN 100
+                             WHEN LOWER(COALESCE(pt.name, p.name)) LIKE LOWER($1 || '%') THEN 80
+                             WHEN LOWER(COALESCE(pt.name, p.name)) LIKE LOWER('%' || $1 || '%') THEN 60
                              ELSE 0 END +
                         -- SKU match
                         CASE WHEN LOWER(p.sku) LIKE LOWER('%' || $1 || '%') THEN 40 ELSE 0 END +
                         -- Description match
-                        CASE WHEN LOWER(p.description) LIKE LOWER('%' || $1 || '%') THEN 20 ELSE 0 END +
+                        CASE WHEN LOWER(COALESCE(pt.description, p.description)) LIKE LOWER('%' || $1 || '%') THEN 20 ELSE 0 END +
                         -- Colors array match (if exists)
                         CASE WHEN p.colors::text ILIKE '%' || $1 || '%' THEN 30 ELSE 0 END +
                         -- Product type match
                         CASE WHEN LOWER(p.product_type::text) LIKE LOWER('%' || $1 || '%') THEN 25 ELSE 0 END +
                         -- Size match (if exists)
-                        CASE WHEN p.size::text ILIKE '%' || $1 || '%' THEN 15 ELSE 0 END
+                        CASE WHEN p.size::text ILIKE '%' || $1 || '%' THEN 15 ELSE 0 END +
+                        -- Match on one of its variants
+                        CASE WHEN {} THEN 10 ELSE 0 END
                     ) as search_score
                 FROM products p
-                JOIN inventory i ON p.id = i.product_id
-                WHERE p.is_active = true
-                AND (
-                    LOWER(p.name) LIKE LOWER('%' || $1 || '%') OR
-                    LOWER(p.sku) LIKE LOWER('%' || $1 || '%') OR
-                    LOWER(p.description) LIKE LOWER('%' || $1 || '%') OR
-                    p.colors::text ILIKE '%' || $1 || '%' OR
-                    LOWER(p.product_type::text) LIKE LOWER('%' || $1 || '%') OR
-                    p.size::text ILIKE '%' || $1 || '%'
-                )
+                JOIN product_stock i ON p.id = i.product_id
+                LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
+                {}
+                WHERE p.is_active = true AND p.parent_id IS NULL
+                AND {}
             "#,
+            Self::family_search_condition(
+                query.locale,
+                "v.parent_id = p.id AND v.is_active = true"
+            ),
+            Self::translation_join(query.locale),
+            Self::family_search_condition(query.locale, FAMILY_MEMBERS)
         );
 
         // Add filters
         let mut param_count = 1;
-        if let Some(filters) = &query.filters {
-            if let Some(_) = &filters.product_type {
-                param_count += 1;
-                sql_query.push_str(&format!(
-                    " AND LOWER(p.product_type::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    param_count += 1;
-                    sql_query.push_str(&format!(" AND ("));
-                    for (i, _) in colors.iter().enumerate() {
-                        if i > 0 {
-                            sql_query.push_str(" OR ");
-                        }
-                        param_count += 1;
-                        sql_query.push_str(&format!(
-                            "p.colors::text ILIKE '%' || ${} || '%'",
-                            param_count
-                        ));
-                    }
-                    sql_query.push_str(")");
-                }
-            }
-
-            if let Some(_) = &filters.size {
-                param_count += 1;
-                sql_query.push_str(&format!(
-                    " AND LOWER(p.size::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(_) = &filters.price_min {
-                param_count += 1;
-                sql_query.push_str(&format!(" AND p.price >= ${}", param_count));
-            }
-
-            if let Some(_) = &filters.price_max {
-                param_count += 1;
-                sql_query.push_str(&format!(" AND p.price <= ${}", param_count));
-            }
-
-            if let Some(in_stock) = filters.in_stock {
-                if in_stock {
-                    sql_query.push_str(" AND (i.quantity_on_hand - i.quantity_reserved) > 0");
-                }
-            }
-        }
+        Self::push_filter_conditions(&mut sql_query, &query.filters, &mut param_count);
 
         // Close the CTE and add the main query
         sql_query.push_str(
@@ -162,7 +144,10 @@ impl ProductSearchService {
                 pi.product_id as image_product_id,
                 pi.url as image_url,
                 pi.alt_text,
-                pi.is_primary
+                pi.is_primary,
+                pi.id AS image_id,
+                pi.sort_order AS image_sort_order,
+                pi.renditions AS image_renditions
             FROM search_results sr
             LEFT JOIN product_images pi ON sr.id = pi.product_id
             WHERE sr.search_score > 0
@@ -214,6 +199,16 @@ impl ProductSearchService {
                     direction
                 ));
             }
+            SearchSortField::Rating => {
+                let direction = match sort.direction {
+                    SearchSortDirection::Asc => "ASC",
+                    SearchSortDirection::Desc => "DESC",
+                };
+                sql_query.push_str(&format!(
+                    " ORDER BY sr.average_rating {} NULLS LAST, sr.review_count DESC, sr.search_score DESC",
+                    direction
+                ));
+            }
         }
 
         // Add pagination
@@ -224,39 +219,17 @@ impl ProductSearchService {
         db_query = db_query.bind(search_term);
 
         // Bind filter parameters in the same order they were added
-        if let Some(filters) = &query.filters {
-            if let Some(product_type) = &filters.product_type {
-                db_query = db_query.bind(product_type);
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    for color in colors {
-                        db_query = db_query.bind(color);
-                    }
-                }
-            }
-
-            if let Some(size) = &filters.size {
-                db_query = db_query.bind(size);
-            }
-
-            if let Some(price_min) = &filters.price_min {
-                db_query = db_query.bind(price_min);
-            }
-
-            if let Some(price_max) = &filters.price_max {
-                db_query = db_query.bind(price_max);
-            }
-        }
+        db_query = Self::bind_filters(db_query, &query.filters);
 
         let rows = db_query.fetch_all(pool).await?;
 
-        // Get total count for pagination
-        let total_count = Self::get_search_count(search_term, &query.filters).await?;
+        // Get total count for pagination and the facets of all matches
+        let (total_count, facets) =
+            Self::get_count_and_facets(Some(search_term), &query.filters, query.locale).await?;
 
-        // Process results
-        let products = Self::process_search_results(rows)?;
+        // Process results; variants are shown with their parent rather than on their own
+        let mut products = Self::process_search_results(rows)?;
+        attach_variants(&mut products, false).await?;
 
         let total_pages = (total_count as f64 / pagination.per_page as f64).ceil() as u32;
 
@@ -268,6 +241,7 @@ impl ProductSearchService {
             total_pages,
             search_time_ms: 0, // Will be set by the caller
             suggestions: None,
+            facets: Some(facets),
         })
     }
 
@@ -276,17 +250,18 @@ impl ProductSearchService {
         filters: Option<SearchFilters>,
         sort: Option<SearchSort>,
         pagination: Option<SearchPagination>,
+        locale: Locale,
     ) -> Result<SearchResult, SqlxError> {
         let pool = pool();
         let pagination = pagination.unwrap_or_default();
         let sort = sort.unwrap_or_default();
         let offset = (pagination.page - 1) * pagination.per_page;
 
-        let mut sql_query = String::from(
+        let mut sql_query = format!(
             r#"
             SELECT
                 p.id,
-                p.name,
+                COALESCE(pt.name, p.name) AS name,
                 p.sku,
                 p.price,
                 COALESCE((
@@ -312,82 +287,39 @@ impl ProductSearchService {
                 p.tax,
                 p.subtotal,
                 p.is_active,
-                p.description,
+                COALESCE(pt.description, p.description) AS description,
                 p.created_at,
                 p.updated_at,
                 p.size,
                 p.colors,
                 p.product_type,
+                p.parent_id,
+                p.slug,
+                rs.average_rating,
+                COALESCE(rs.review_count, 0) AS review_count,
                 i.quantity_on_hand,
                 i.quantity_reserved,
                 (i.quantity_on_hand - i.quantity_reserved) as available_stock,
                 pi.product_id as image_product_id,
                 pi.url as image_url,
                 pi.alt_text,
-                pi.is_primary
+                pi.is_primary,
+                pi.id AS image_id,
+                pi.sort_order AS image_sort_order,
+                pi.renditions AS image_renditions
             FROM products p
-            JOIN inventory i ON p.id = i.product_id
+            JOIN product_stock i ON p.id = i.product_id
+            LEFT JOIN product_review_stats rs ON rs.product_id = COALESCE(p.parent_id, p.id)
             LEFT JOIN product_images pi ON p.id = pi.product_id
-            WHERE p.is_active = true
+            {}
+            WHERE p.is_active = true AND p.parent_id IS NULL
             "#,
+            Self::translation_join(locale)
         );
 
-        let mut param_count = 0;
-
-        #[allow(unused_assignments)]
-        let mut db_query = sqlx::query(&sql_query);
-
         // Add filters (same logic as above but without search term)
-        if let Some(filters) = &filters {
-            if let Some(_) = &filters.product_type {
-                param_count += 1;
-                sql_query.push_str(&format!(
-                    " AND LOWER(p.product_type::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    sql_query.push_str(" AND (");
-                    for (i, _) in colors.iter().enumerate() {
-                        if i > 0 {
-                            sql_query.push_str(" OR ");
-                        }
-                        param_count += 1;
-                        sql_query.push_str(&format!(
-                            "p.colors::text ILIKE '%' || ${} || '%'",
-                            param_count
-                        ));
-                    }
-                    sql_query.push_str(")");
-                }
-            }
-
-            if let Some(_) = &filters.size {
-                param_count += 1;
-                sql_query.push_str(&format!(
-                    " AND LOWER(p.size::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(_) = &filters.price_min {
-                param_count += 1;
-                sql_query.push_str(&format!(" AND p.price >= ${}", param_count));
-            }
-
-            if let Some(_) = &filters.price_max {
-                param_count += 1;
-                sql_query.push_str(&format!(" AND p.price <= ${}", param_count));
-            }
-
-            if let Some(in_stock) = filters.in_stock {
-                if in_stock {
-                    sql_query.push_str(" AND (i.quantity_on_hand - i.quantity_reserved) > 0");
-                }
-            }
-        }
+        let mut param_count = 0;
+        Self::push_filter_conditions(&mut sql_query, &filters, &mut param_count);
 
         // Add sorting
         match sort.field {
@@ -396,7 +328,10 @@ impl ProductSearchService {
                     SearchSortDirection::Asc => "ASC",
                     SearchSortDirection::Desc => "DESC",
                 };
-                sql_query.push_str(&format!(" ORDER BY p.name {}", direction));
+                sql_query.push_str(&format!(
+                    " ORDER BY COALESCE(pt.name, p.name) {}",
+                    direction
+                ));
             }
             SearchSortField::Price => {
                 let direction = match sort.direction {
@@ -419,43 +354,27 @@ impl ProductSearchService {
                 };
                 sql_query.push_str(&format!(" ORDER BY available_stock {}", direction));
             }
+            SearchSortField::Rating => {
+                let direction = match sort.direction {
+                    SearchSortDirection::Asc => "ASC",
+                    SearchSortDirection::Desc => "DESC",
+                };
+                sql_query.push_str(&format!(
+                    " ORDER BY rs.average_rating {} NULLS LAST, review_count DESC",
+                    direction
+                ));
+            }
         }
 
         sql_query.push_str(&format!(" LIMIT {} OFFSET {}", pagination.per_page, offset));
 
-        // Rebuild query with filters
-        db_query = sqlx::query(&sql_query);
-        if let Some(filters) = &filters {
-            if let Some(product_type) = &filters.product_type {
-                db_query = db_query.bind(product_type);
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    for color in colors {
-                        db_query = db_query.bind(color);
-                    }
-                }
-            }
-
-            if let Some(size) = &filters.size {
-                db_query = db_query.bind(size);
-            }
-
-            if let Some(price_min) = &filters.price_min {
-                db_query = db_query.bind(price_min);
-            }
-
-            if let Some(price_max) = &filters.price_max {
-                db_query = db_query.bind(price_max);
-            }
-        }
-
+        let db_query = Self::bind_filters(sqlx::query(&sql_query), &filters);
         let rows = db_query.fetch_all(pool).await?;
 
-        // Get total count
-        let total_count = Self::get_filter_count(&filters).await?;
-        let products = Self::process_search_results(rows)?;
+        // Get total count and the facets of all matches
+        let (total_count, facets) = Self::get_count_and_facets(None, &filters, locale).await?;
+        let mut products = Self::process_search_results(rows)?;
+        attach_variants(&mut products, false).await?;
         let total_pages = (total_count as f64 / pagination.per_page as f64).ceil() as u32;
 
         Ok(SearchResult {
@@ -466,212 +385,268 @@ impl ProductSearchService {
             total_pages,
             search_time_ms: 0,
             suggestions: None,
+            facets: Some(facets),
         })
     }
 
-    /// Get total count for search results
-    async fn get_search_count(
-        search_term: &str,
+    /// Count the products matching a search, and how many of them fall in each category and
+    /// occasion. Only parent products count; their variants are listed with them.
+    async fn get_count_and_facets(
+        search_term: Option<&str>,
         filters: &Option<SearchFilters>,
-    ) -> Result<u64, SqlxError> {
+        locale: Locale,
+    ) -> Result<(u64, SearchFacets), SqlxError> {
         let pool = pool();
 
-        let mut count_query = String::from(
+        let mut matches = format!(
             r#"
-            SELECT COUNT(DISTINCT p.id) as count
             FROM products p
-            JOIN inventory i ON p.id = i.product_id
-            WHERE p.is_active = true
-            AND (
-                LOWER(p.name) LIKE LOWER('%' || $1 || '%') OR
-                LOWER(p.sku) LIKE LOWER('%' || $1 || '%') OR
-                LOWER(p.description) LIKE LOWER('%' || $1 || '%') OR
-                p.colors::text ILIKE '%' || $1 || '%' OR
-                LOWER(p.product_type::text) LIKE LOWER('%' || $1 || '%') OR
-                p.size::text ILIKE '%' || $1 || '%'
-            )
+            JOIN product_stock i ON p.id = i.product_id
+            {}
+            WHERE p.is_active = true AND p.parent_id IS NULL
             "#,
+            Self::translation_join(locale)
         );
-
-        let mut param_count = 1;
-        if let Some(filters) = filters {
-            if let Some(_) = &filters.product_type {
-                param_count += 1;
-                count_query.push_str(&format!(
-                    " AND LOWER(p.product_type::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    count_query.push_str(" AND (");
-                    for (i, _) in colors.iter().enumerate() {
-                        if i > 0 {
-                            count_query.push_str(" OR ");
-                        }
-                        param_count += 1;
-                        count_query.push_str(&format!(
-                            "p.colors::text ILIKE '%' || ${} || '%'",
-                            param_count
-                        ));
-                    }
-                    count_query.push_str(")");
-                }
-            }
-
-            if let Some(_) = &filters.size {
-                param_count += 1;
-                count_query.push_str(&format!(
-                    " AND LOWER(p.size::text) = LOWER(${})",
-                    param_count
-                ));
-            }
-
-            if let Some(_) = &filters.price_min {
-                param_count += 1;
-                count_query.push_str(&format!(" AND p.price >= ${}", param_count));
-            }
-
-            if let Some(_) = &filters.price_max {
-                param_count += 1;
-                count_query.push_str(&format!(" AND p.price <= ${}", param_count));
-            }
-
-            if let Some(in_stock) = filters.in_stock {
-                if in_stock {
-                    count_query.push_str(" AND (i.quantity_on_hand - i.quantity_reserved) > 0");
-                }
-            }
+        let mut param_count = 0;
+        if search_term.is_some() {
+            matches.push_str(&format!(
+                " AND {}",
+                Self::family_search_condition(locale, FAMILY_MEMBERS)
+            ));
+            param_count = 1;
+        }
+        Self::push_filter_conditions(&mut matches, filters, &mut param_count);
+
+        fn bind<'q>(
+            sql: &'q str,
+            search_term: Option<&str>,
+            filters: &Option<SearchFilters>,
+        ) -> Query<'q, Postgres, PgArguments> {
+            let mut query = sqlx::query(sql);
+            if let Some(search_term) = search_term {
+                query = query.bind(search_term.to_string());
+            }
+            ProductSearchService::bind_filters(query, filters)
         }
 
-        let mut db_query = sqlx::query(&count_query);
-        db_query = db_query.bind(search_term);
-
-        if let Some(filters) = filters {
-            if let Some(product_type) = &filters.product_type {
-                db_query = db_query.bind(product_type);
-            }
-
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    for color in colors {
-                        db_query = db_query.bind(color);
-                    }
-                }
-            }
+        let count_query = format!("SELECT COUNT(DISTINCT p.id) as count {}", matches);
+        let row = bind(&count_query, search_term, filters)
+            .fetch_one(pool)
+            .await?;
+        let total_count = row.get::<i64, _>("count") as u64;
 
-            if let Some(size) = &filters.size {
-                db_query = db_query.bind(size);
-            }
+        let category_query = format!(
+            r#"
+            SELECT c.id, c.name, COUNT(DISTINCT p.id) AS count
+            FROM categories c
+            JOIN product_categories pc ON pc.category_id = c.id
+            JOIN (SELECT p.id {}) p ON pc.product_id = p.id
+            WHERE c.is_active = true
+            GROUP BY c.id, c.name
+            ORDER BY count DESC, c.name ASC
+            "#,
+            matches
+        );
+        let categories = bind(&category_query, search_term, filters)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(Self::facet_count)
+            .collect();
 
-            if let Some(price_min) = &filters.price_min {
-                db_query = db_query.bind(price_min);
-            }
+        let occasion_query = format!(
+            r#"
+            SELECT o.id, o.name, COUNT(DISTINCT p.id) AS count
+            FROM occasions o
+            JOIN product_occasions po ON po.occasion_id = o.id
+            JOIN (SELECT p.id {}) p ON po.product_id = p.id
+            GROUP BY o.id, o.name
+            ORDER BY count DESC, o.name ASC
+            "#,
+            matches
+        );
+        let occasions = bind(&occasion_query, search_term, filters)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(Self::facet_count)
+            .collect();
 
-            if let Some(price_max) = &filters.price_max {
-                db_query = db_query.bind(price_max);
-            }
-        }
+        Ok((
+            total_count,
+            SearchFacets {
+                categories,
+                occasions,
+            },
+        ))
+    }
 
-        let row = db_query.fetch_one(pool).await?;
-        Ok(row.get::<i64, _>("count") as u64)
+    /// Join the translations of the locale as `pt`. The locale is one of a fixed set of codes,
+    /// so it is safe to put in the query; the default locale has no rows, leaving `pt` empty.
+    fn translation_join(locale: Locale) -> String {
+        format!(
+            "LEFT JOIN product_translations pt ON pt.product_id = p.id AND pt.locale = '{}'",
+            locale.as_str()
+        )
     }
 
-    /// Get total count for filtered results (no search term)
-    async fn get_filter_count(filters: &Option<SearchFilters>) -> Result<u64, SqlxError> {
-        let pool = pool();
+    /// Whether a row of the family matches the search term, `members` selecting the rows
+    fn family_search_condition(locale: Locale, members: &str) -> String {
+        format!(
+            r#"EXISTS (
+                SELECT 1 FROM products v
+                LEFT JOIN product_translations vt ON vt.product_id = v.id AND vt.locale = '{}'
+                WHERE {} AND {}
+            )"#,
+            locale.as_str(),
+            members,
+            SEARCH_MATCH
+        )
+    }
 
-        let mut count_query = String::from(
-            r#"
-            SELECT COUNT(DISTINCT p.id) as count
-            FROM products p
-            JOIN inventory i ON p.id = i.product_id
-            WHERE p.is_active = true
-            "#,
-        );
+    fn facet_count(row: sqlx::postgres::PgRow) -> FacetCount {
+        FacetCount {
+            id: row.get("id"),
+            name: row.get("name"),
+            count: row.get::<i64, _>("count") as u64,
+        }
+    }
 
-        let mut param_count = 0;
-        if let Some(filters) = filters {
-            if let Some(_) = &filters.product_type {
-                param_count += 1;
-                count_query.push_str(&format!(
-                    " AND LOWER(p.product_type::text) = LOWER(${})",
-                    param_count
-                ));
-            }
+    /// Append the filter conditions. Placeholders are numbered after `param_count`, in the
+    /// order `bind_filters` binds them.
+    fn push_filter_conditions(
+        sql: &mut String,
+        filters: &Option<SearchFilters>,
+        param_count: &mut usize,
+    ) {
+        let Some(filters) = filters else {
+            return;
+        };
+
+        // Product level filters hold when a single row of the family meets all of them
+        let mut family_conditions = Vec::new();
+
+        if filters.product_type.is_some() {
+            *param_count += 1;
+            family_conditions.push(format!(
+                "LOWER(v.product_type::text) = LOWER(${})",
+                param_count
+            ));
+        }
 
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    count_query.push_str(" AND (");
-                    for (i, _) in colors.iter().enumerate() {
-                        if i > 0 {
-                            count_query.push_str(" OR ");
-                        }
-                        param_count += 1;
-                        count_query.push_str(&format!(
-                            "p.colors::text ILIKE '%' || ${} || '%'",
-                            param_count
-                        ));
-                    }
-                    count_query.push_str(")");
-                }
-            }
+        if let Some(colors) = &filters.colors
+            && !colors.is_empty()
+        {
+            let color_conditions: Vec<String> = colors
+                .iter()
+                .map(|_| {
+                    *param_count += 1;
+                    format!("v.colors::text ILIKE '%' || ${} || '%'", param_count)
+                })
+                .collect();
+            family_conditions.push(format!("({})", color_conditions.join(" OR ")));
+        }
 
-            if let Some(_) = &filters.size {
-                param_count += 1;
-                count_query.push_str(&format!(
-                    " AND LOWER(p.size::text) = LOWER(${})",
-                    param_count
-                ));
-            }
+        if filters.size.is_some() {
+            *param_count += 1;
+            family_conditions.push(format!("LOWER(v.size::text) = LOWER(${})", param_count));
+        }
 
-            if let Some(_) = &filters.price_min {
-                param_count += 1;
-                count_query.push_str(&format!(" AND p.price >= ${}", param_count));
-            }
+        if filters.price_min.is_some() {
+            *param_count += 1;
+            family_conditions.push(format!("v.price >= ${}", param_count));
+        }
 
-            if let Some(_) = &filters.price_max {
-                param_count += 1;
-                count_query.push_str(&format!(" AND p.price <= ${}", param_count));
-            }
+        if filters.price_max.is_some() {
+            *param_count += 1;
+            family_conditions.push(format!("v.price <= ${}", param_count));
+        }
 
-            if let Some(in_stock) = filters.in_stock {
-                if in_stock {
-                    count_query.push_str(" AND (i.quantity_on_hand - i.quantity_reserved) > 0");
-                }
-            }
+        if filters.in_stock == Some(true) {
+            family_conditions.push(
+                r#"EXISTS (
+                    SELECT 1 FROM product_stock vs
+                    WHERE vs.product_id = v.id AND (vs.quantity_on_hand - vs.quantity_reserved) > 0
+                )"#
+                .to_string(),
+            );
         }
 
-        let mut db_query = sqlx::query(&count_query);
-        if let Some(filters) = filters {
-            if let Some(product_type) = &filters.product_type {
-                db_query = db_query.bind(product_type);
-            }
+        if !family_conditions.is_empty() {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM products v WHERE {} AND {})",
+                FAMILY_MEMBERS,
+                family_conditions.join(" AND ")
+            ));
+        }
 
-            if let Some(colors) = &filters.colors {
-                if !colors.is_empty() {
-                    for color in colors {
-                        db_query = db_query.bind(color);
-                    }
-                }
-            }
+        // A category includes its subcategories
+        if filters.category_id.is_some() {
+            *param_count += 1;
+            sql.push_str(&format!(
+                r#" AND EXISTS (
+                    SELECT 1 FROM product_categories pc
+                    WHERE pc.product_id = p.id
+                    AND pc.category_id IN (
+                        WITH RECURSIVE tree AS (
+                            SELECT id FROM categories WHERE id = ${} AND is_active = true
+                            UNION ALL
+                            SELECT c.id FROM categories c
+                            JOIN tree t ON c.parent_id = t.id
+                            WHERE c.is_active = true
+                        )
+                        SELECT id FROM tree
+                    )
+                )"#,
+                param_count
+            ));
+        }
 
-            if let Some(size) = &filters.size {
-                db_query = db_query.bind(size);
-            }
+        if filters.occasion_id.is_some() {
+            *param_count += 1;
+            sql.push_str(&format!(
+                r#" AND EXISTS (
+                    SELECT 1 FROM product_occasions po
+                    WHERE po.product_id = p.id AND po.occasion_id = ${}
+                )"#,
+                param_count
+            ));
+        }
+    }
 
-            if let Some(price_min) = &filters.price_min {
-                db_query = db_query.bind(price_min);
-            }
+    /// Bind the filter parameters added by `push_filter_conditions`
+    fn bind_filters<'q>(
+        mut query: Query<'q, Postgres, PgArguments>,
+        filters: &Option<SearchFilters>,
+    ) -> Query<'q, Postgres, PgArguments> {
+        let Some(filters) = filters else {
+            return query;
+        };
 
-            if let Some(price_max) = &filters.price_max {
-                db_query = db_query.bind(price_max);
+        if let Some(product_type) = &filters.product_type {
+            query = query.bind(product_type.clone());
+        }
+        if let Some(colors) = &filters.colors {
+            for color in colors {
+                query = query.bind(color.clone());
             }
         }
-
-        let row = db_query.fetch_one(pool).await?;
-        Ok(row.get::<i64, _>("count") as u64)
+        if let Some(size) = &filters.size {
+            query = query.bind(size.clone());
+        }
+        if let Some(price_min) = filters.price_min {
+            query = query.bind(price_min);
+        }
+        if let Some(price_max) = filters.price_max {
+            query = query.bind(price_max);
+        }
+        if let Some(category_id) = filters.category_id {
+            query = query.bind(category_id);
+        }
+        if let Some(occasion_id) = filters.occasion_id {
+            query = query.bind(occasion_id);
+        }
+        query
     }
 
     /// Process search results into Product structs
@@ -705,16 +680,24 @@ impl ProductSearchService {
                 size: row.get("size"),
                 product_type: row.get("product_type"),
                 images: Some(Vec::new()),
+                parent_id: row.get("parent_id"),
+                slug: row.get("slug"),
+                average_rating: row.get("average_rating"),
+                review_count: row.get("review_count"),
+                variants: None,
             });
 
             // Add image if it exists
             if let Ok(image_product_id) = row.try_get::<Uuid, _>("image_product_id") {
                 if let Some(ref mut images) = product.images {
                     images.push(ProductImage {
+                        id: row.get("image_id"),
                         product_id: image_product_id,
                         url: row.get("image_url"),
                         alt_text: row.get("alt_text"),
                         is_primary: row.get("is_primary"),
+                        sort_order: row.get("image_sort_order"),
+                        renditions: row.get("image_renditions"),
                     });
                 }
             }
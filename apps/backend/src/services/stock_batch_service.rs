@@ -0,0 +1,488 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::services::{StockLocationService, StockMovementService};
+use crate::structs::stock_batch::{
+    BatchAllocation, BatchQuantity, ExpiredWriteOffReport, ExpiringBatch, StockBatch,
+    allocate_batches,
+};
+use crate::structs::stock_movement::{MovementContext, StockMovementReason};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use tracing::{error, info};
+use uuid::Uuid;
+
+const BATCH_SELECT: &str = r#"
+    SELECT id, product_id, location_id, quantity_received, quantity_remaining,
+           quantity_reserved, received_at, expires_on, written_off_at, note
+    FROM stock_batches
+"#;
+
+/// Service for perishable stock batches. The batches of a product at a location add up to
+/// its `location_inventory` row; every function here runs on the connection of the
+/// inventory update so both change together.
+pub struct StockBatchService;
+
+impl StockBatchService {
+    /// Register a delivery as a new batch. Reservations taken on backorder are assigned to
+    /// the fresh stock right away.
+    pub async fn receive(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+        expires_on: Option<NaiveDate>,
+        note: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO stock_batches (
+                product_id, location_id, quantity_received, quantity_remaining, expires_on, note
+            )
+            VALUES ($1, $2, $3, $3, $4, $5)
+            "#,
+        )
+        .bind(product_id)
+        .bind(location_id)
+        .bind(quantity)
+        .bind(expires_on)
+        .bind(note)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to create stock batch for product {}: {}",
+                product_id, e
+            ))
+        })?;
+
+        Self::allocate_backorders(conn, product_id, location_id).await
+    }
+
+    /// Reserve the first-expiring units that are still fresh today
+    pub async fn reserve(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, location_id).await?;
+        let today = Utc::now().date_naive();
+
+        let allocations =
+            allocate_batches(&batches, quantity, BatchQuantity::Available, Some(today)).map_err(
+                |missing| {
+                    AppError::ValidationError(format!(
+                        "Insufficient fresh stock for product {}. Missing: {}",
+                        product_id, missing
+                    ))
+                },
+            )?;
+
+        Self::apply(conn, &allocations, Decimal::ZERO, Decimal::ONE).await
+    }
+
+    /// Release reservations, starting with the last-expiring batch so the oldest stock
+    /// stays reserved for the remaining orders. Runs after the inventory update: backordered
+    /// units that never got a batch are released first, so the batches only give up what
+    /// the inventory no longer reserves.
+    pub async fn release(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, location_id).await?;
+        let unallocated =
+            Self::unallocated_reservations(conn, product_id, location_id, &batches).await?;
+
+        let quantity = quantity.min(-unallocated);
+        if quantity <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let allocations = allocate_batches(
+            batches.iter().rev(),
+            quantity,
+            BatchQuantity::Reserved,
+            None,
+        )
+        .map_err(|missing| Self::out_of_sync(product_id, missing))?;
+
+        Self::apply(conn, &allocations, Decimal::ZERO, Decimal::NEGATIVE_ONE).await
+    }
+
+    /// Take reserved units out of stock, first-expiring first
+    pub async fn fulfil(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, location_id).await?;
+
+        let allocations = allocate_batches(&batches, quantity, BatchQuantity::Reserved, None)
+            .map_err(|missing| Self::out_of_sync(product_id, missing))?;
+
+        Self::apply(
+            conn,
+            &allocations,
+            Decimal::NEGATIVE_ONE,
+            Decimal::NEGATIVE_ONE,
+        )
+        .await
+    }
+
+    /// Take unreserved units out of stock, first-expiring first
+    pub async fn remove_available(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, location_id).await?;
+
+        let allocations = allocate_batches(&batches, quantity, BatchQuantity::Available, None)
+            .map_err(|missing| Self::out_of_sync(product_id, missing))?;
+
+        Self::apply(conn, &allocations, Decimal::NEGATIVE_ONE, Decimal::ZERO).await
+    }
+
+    /// Move available units to another location, first-expiring first. The batches keep
+    /// their receipt and expiry dates at the new location.
+    pub async fn transfer(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        from_location_id: Uuid,
+        to_location_id: Uuid,
+        quantity: Decimal,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, from_location_id).await?;
+
+        let allocations = allocate_batches(&batches, quantity, BatchQuantity::Available, None)
+            .map_err(|missing| Self::out_of_sync(product_id, missing))?;
+
+        Self::apply(conn, &allocations, Decimal::NEGATIVE_ONE, Decimal::ZERO).await?;
+
+        for allocation in &allocations {
+            sqlx::query(
+                r#"
+                INSERT INTO stock_batches (
+                    product_id, location_id, quantity_received, quantity_remaining, received_at,
+                    expires_on, note
+                )
+                SELECT product_id, $2, $3, $3, received_at, expires_on, note
+                FROM stock_batches
+                WHERE id = $1
+                "#,
+            )
+            .bind(allocation.batch_id)
+            .bind(to_location_id)
+            .bind(allocation.quantity)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to transfer stock batch {}: {}",
+                    allocation.batch_id, e
+                ))
+            })?;
+        }
+
+        Self::allocate_backorders(conn, product_id, to_location_id).await
+    }
+
+    /// Reserve fresh stock for reservations that were taken without stock, as far as the
+    /// batches allow
+    async fn allocate_backorders(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<(), AppError> {
+        let batches = Self::lock_batches(conn, product_id, location_id).await?;
+        let unallocated =
+            Self::unallocated_reservations(conn, product_id, location_id, &batches).await?;
+        if unallocated <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let today = Utc::now().date_naive();
+        let fresh: Decimal = batches
+            .iter()
+            .filter(|batch| !batch.is_expired_on(today))
+            .map(|batch| batch.available_quantity())
+            .sum();
+        let quantity = unallocated.min(fresh);
+        if quantity <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let allocations =
+            allocate_batches(&batches, quantity, BatchQuantity::Available, Some(today))
+                .map_err(|missing| Self::out_of_sync(product_id, missing))?;
+
+        Self::apply(conn, &allocations, Decimal::ZERO, Decimal::ONE).await
+    }
+
+    /// Units the location reserves beyond what its batches reserve. Negative when the
+    /// location was already lowered and the batches still have to follow.
+    async fn unallocated_reservations(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        batches: &[StockBatch],
+    ) -> Result<Decimal, AppError> {
+        let inventory_reserved = sqlx::query_scalar::<_, Decimal>(
+            "SELECT quantity_reserved FROM location_inventory WHERE product_id = $1 AND location_id = $2",
+        )
+        .bind(product_id)
+        .bind(location_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to fetch reservations for product {}: {}",
+                product_id, e
+            ))
+        })?
+        .unwrap_or(Decimal::ZERO);
+
+        let batch_reserved: Decimal = batches.iter().map(|batch| batch.quantity_reserved).sum();
+        Ok(inventory_reserved - batch_reserved)
+    }
+
+    /// Batches with stock left that expire within `days`, soonest first
+    pub async fn list_expiring(days: i32) -> Result<Vec<ExpiringBatch>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, ExpiringBatch>(
+            r#"
+            SELECT
+                b.id AS batch_id,
+                b.product_id,
+                b.location_id,
+                l.name AS location_name,
+                p.name,
+                p.sku,
+                (b.quantity_remaining - b.quantity_reserved) AS quantity_available,
+                b.quantity_reserved,
+                b.received_at,
+                b.expires_on,
+                (b.expires_on - CURRENT_DATE) AS days_left
+            FROM stock_batches b
+            JOIN products p ON p.id = b.product_id
+            LEFT JOIN stock_locations l ON l.id = b.location_id
+            WHERE b.quantity_remaining > 0
+            AND b.expires_on IS NOT NULL
+            AND b.expires_on <= CURRENT_DATE + $1
+            ORDER BY b.expires_on ASC, p.name ASC
+            "#,
+        )
+        .bind(days)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch expiring batches: {}", e)))
+    }
+
+    /// Write off the unreserved units of every expired batch. Each batch is written off in
+    /// its own transaction so one failure does not hold back the rest.
+    pub async fn write_off_expired() -> Result<ExpiredWriteOffReport, AppError> {
+        let pool = pool();
+
+        let expired = sqlx::query_as::<_, StockBatch>(&format!(
+            "{} WHERE expires_on < CURRENT_DATE AND quantity_remaining > quantity_reserved ORDER BY expires_on ASC",
+            BATCH_SELECT
+        ))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch expired batches: {}", e)))?;
+
+        let mut report = ExpiredWriteOffReport::default();
+        for batch in expired {
+            match Self::write_off_batch(batch.id).await {
+                Ok(quantity) if quantity > Decimal::ZERO => {
+                    report.batches_written_off += 1;
+                    report.quantity_written_off += quantity;
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to write off expired batch {}: {}", batch.id, e),
+            }
+        }
+
+        if report.batches_written_off > 0 {
+            info!(
+                "Wrote off {} units from {} expired batches",
+                report.quantity_written_off, report.batches_written_off
+            );
+        }
+        Ok(report)
+    }
+
+    async fn write_off_batch(batch_id: Uuid) -> Result<Decimal, AppError> {
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let batch =
+            sqlx::query_as::<_, StockBatch>(&format!("{} WHERE id = $1 FOR UPDATE", BATCH_SELECT))
+                .bind(batch_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch batch: {}", e)))?;
+
+        let quantity = Self::write_off_batch_with(&mut tx, &batch).await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit batch write-off: {}", e))
+        })?;
+
+        Ok(quantity)
+    }
+
+    /// Write off the unreserved units of the expired batches of a product at a location.
+    /// Runs before stock is reserved, so expired units that the daily write-off has not
+    /// reached yet are never counted as available.
+    pub async fn write_off_expired_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<Decimal, AppError> {
+        let expired = sqlx::query_as::<_, StockBatch>(&format!(
+            r#"{}
+            WHERE product_id = $1 AND location_id = $2
+            AND expires_on < CURRENT_DATE AND quantity_remaining > quantity_reserved
+            FOR UPDATE
+            "#,
+            BATCH_SELECT
+        ))
+        .bind(product_id)
+        .bind(location_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch expired batches: {}", e)))?;
+
+        let mut written_off = Decimal::ZERO;
+        for batch in &expired {
+            written_off += Self::write_off_batch_with(conn, batch).await?;
+        }
+        Ok(written_off)
+    }
+
+    /// Write off the unreserved units of a locked batch
+    async fn write_off_batch_with(
+        conn: &mut PgConnection,
+        batch: &StockBatch,
+    ) -> Result<Decimal, AppError> {
+        // Reserved units stay with their orders
+        let quantity = batch.available_quantity();
+        if quantity <= Decimal::ZERO {
+            return Ok(Decimal::ZERO);
+        }
+
+        sqlx::query(
+            "UPDATE stock_batches SET quantity_remaining = quantity_reserved, written_off_at = NOW() WHERE id = $1",
+        )
+        .bind(batch.id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to write off batch: {}", e)))?;
+
+        let location_id = batch.location_id.ok_or_else(|| {
+            AppError::ValidationError(format!("Batch {} has no stock location", batch.id))
+        })?;
+        StockLocationService::apply_with(
+            &mut *conn,
+            batch.product_id,
+            location_id,
+            -quantity,
+            Decimal::ZERO,
+        )
+        .await?;
+
+        let context = MovementContext::default().with_note(format!(
+            "expired: batch received {}",
+            batch.received_at.format("%Y-%m-%d")
+        ));
+        StockMovementService::record(
+            &mut *conn,
+            batch.product_id,
+            location_id,
+            -quantity,
+            Decimal::ZERO,
+            StockMovementReason::WriteOff,
+            &context,
+        )
+        .await?;
+
+        Ok(quantity)
+    }
+
+    /// Batches of a product at a location with stock left, in first-expiring-first order
+    async fn lock_batches(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<Vec<StockBatch>, AppError> {
+        sqlx::query_as::<_, StockBatch>(&format!(
+            r#"{}
+            WHERE product_id = $1 AND location_id = $2 AND quantity_remaining > 0
+            ORDER BY expires_on ASC NULLS LAST, received_at ASC
+            FOR UPDATE
+            "#,
+            BATCH_SELECT
+        ))
+        .bind(product_id)
+        .bind(location_id)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to fetch stock batches for product {}: {}",
+                product_id, e
+            ))
+        })
+    }
+
+    /// Apply allocations, multiplying each quantity by the given sign for the remaining
+    /// and the reserved quantity
+    async fn apply(
+        conn: &mut PgConnection,
+        allocations: &[BatchAllocation],
+        remaining_sign: Decimal,
+        reserved_sign: Decimal,
+    ) -> Result<(), AppError> {
+        for allocation in allocations {
+            sqlx::query(
+                r#"
+                UPDATE stock_batches
+                SET
+                    quantity_remaining = quantity_remaining + $2,
+                    quantity_reserved = quantity_reserved + $3
+                WHERE id = $1
+                "#,
+            )
+            .bind(allocation.batch_id)
+            .bind(allocation.quantity * remaining_sign)
+            .bind(allocation.quantity * reserved_sign)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!(
+                    "Failed to update stock batch {}: {}",
+                    allocation.batch_id, e
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn out_of_sync(product_id: Uuid, missing: Decimal) -> AppError {
+        AppError::ValidationError(format!(
+            "Stock batches of product {} do not cover the inventory. Missing: {}",
+            product_id, missing
+        ))
+    }
+}
@@ -0,0 +1,521 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::services::{BillOfMaterialsService, StockBatchService, StockMovementService};
+use crate::structs::inventory::InventoryReservation;
+use crate::structs::stock_location::{
+    CreateStockLocationRequest, LocationStock, PickupPoint, StockLocation, StockTransferRequest,
+    UpdateStockLocationRequest, can_supply, normalize_postal_prefixes, preferred_location,
+};
+use crate::structs::stock_movement::{MovementContext, StockMovementReason};
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+const LOCATION_COLUMNS: &str = "id, name, is_pickup_point, delivery_postal_prefixes, priority, is_active, created_at, updated_at";
+
+/// Service for the locations stock is kept at. The `inventory` row of a product holds the
+/// sum of its `location_inventory` rows; `apply_with` changes both together.
+pub struct StockLocationService;
+
+impl StockLocationService {
+    /// Locations by priority, active ones only unless `include_inactive` is set
+    pub async fn list_locations(include_inactive: bool) -> Result<Vec<StockLocation>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, StockLocation>(&format!(
+            "SELECT {} FROM stock_locations WHERE $1 OR is_active = true ORDER BY priority ASC, created_at ASC",
+            LOCATION_COLUMNS
+        ))
+        .bind(include_inactive)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch stock locations: {}", e)))
+    }
+
+    /// Active locations where customers can pick up their order
+    pub async fn list_pickup_points() -> Result<Vec<PickupPoint>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, PickupPoint>(
+            "SELECT id, name FROM stock_locations WHERE is_active = true AND is_pickup_point = true ORDER BY priority ASC, name ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch pickup points: {}", e)))
+    }
+
+    pub async fn create_location(
+        request: &CreateStockLocationRequest,
+    ) -> Result<StockLocation, AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Location name is required".to_string(),
+            ));
+        }
+        let prefixes = normalize_postal_prefixes(&request.delivery_postal_prefixes)
+            .map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, StockLocation>(&format!(
+            r#"
+            INSERT INTO stock_locations (name, is_pickup_point, delivery_postal_prefixes, priority)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            LOCATION_COLUMNS
+        ))
+        .bind(request.name.trim())
+        .bind(request.is_pickup_point)
+        .bind(&prefixes)
+        .bind(request.priority)
+        .fetch_one(pool)
+        .await
+        .map_err(Self::map_write_error)
+    }
+
+    pub async fn update_location(
+        location_id: Uuid,
+        request: &UpdateStockLocationRequest,
+    ) -> Result<StockLocation, AppError> {
+        if request
+            .name
+            .as_deref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(AppError::ValidationError(
+                "Location name cannot be empty".to_string(),
+            ));
+        }
+        let prefixes = request
+            .delivery_postal_prefixes
+            .as_deref()
+            .map(normalize_postal_prefixes)
+            .transpose()
+            .map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        // Stock and open orders at a location have to be moved before it can close
+        if request.is_active == Some(false) {
+            let in_use = sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT
+                    EXISTS (
+                        SELECT 1 FROM location_inventory
+                        WHERE location_id = $1
+                        AND (quantity_on_hand <> 0 OR quantity_reserved <> 0)
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM orders
+                        WHERE stock_location_id = $1 AND status IN ('pending', 'processing')
+                    )
+                "#,
+            )
+            .bind(location_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to check stock location: {}", e))
+            })?;
+
+            if in_use {
+                return Err(AppError::Conflict(
+                    "This location still has stock or open orders".to_string(),
+                ));
+            }
+        }
+
+        let location = sqlx::query_as::<_, StockLocation>(&format!(
+            r#"
+            UPDATE stock_locations
+            SET
+                name = COALESCE($2, name),
+                is_pickup_point = COALESCE($3, is_pickup_point),
+                delivery_postal_prefixes = COALESCE($4, delivery_postal_prefixes),
+                priority = COALESCE($5, priority),
+                is_active = COALESCE($6, is_active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            LOCATION_COLUMNS
+        ))
+        .bind(location_id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(request.is_pickup_point)
+        .bind(prefixes)
+        .bind(request.priority)
+        .bind(request.is_active)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_write_error)?
+        .ok_or_else(|| AppError::not_found("Stock location"))?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit stock location: {}", e))
+        })?;
+
+        Ok(location)
+    }
+
+    /// Stock per location, for one product or all of them
+    pub async fn location_stock(product_id: Option<Uuid>) -> Result<Vec<LocationStock>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, LocationStock>(
+            r#"
+            SELECT
+                li.product_id,
+                li.location_id,
+                l.name AS location_name,
+                li.quantity_on_hand,
+                li.quantity_reserved,
+                (li.quantity_on_hand - li.quantity_reserved) AS available_quantity
+            FROM location_inventory li
+            JOIN stock_locations l ON l.id = li.location_id
+            WHERE $1::uuid IS NULL OR li.product_id = $1
+            ORDER BY l.priority ASC, l.name ASC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch location stock: {}", e)))
+    }
+
+    /// Move available stock between locations. The product total does not change; both
+    /// sides are recorded as a transfer movement.
+    pub async fn transfer(
+        product_id: Uuid,
+        request: &StockTransferRequest,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
+        if request.from_location_id == request.to_location_id {
+            return Err(AppError::ValidationError(
+                "Cannot transfer stock to the same location".to_string(),
+            ));
+        }
+        if request.quantity <= Decimal::ZERO {
+            return Err(AppError::ValidationError(
+                "Quantity must be greater than zero".to_string(),
+            ));
+        }
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        BillOfMaterialsService::ensure_not_composed_with(&mut tx, product_id).await?;
+        Self::ensure_active_with(&mut tx, request.to_location_id).await?;
+
+        let (on_hand, reserved) =
+            Self::lock_with(&mut tx, product_id, request.from_location_id).await?;
+        if on_hand - reserved < request.quantity {
+            return Err(AppError::ValidationError(format!(
+                "Cannot transfer {} units of product {}. Available at this location: {}",
+                request.quantity,
+                product_id,
+                on_hand - reserved
+            )));
+        }
+
+        Self::apply_with(
+            &mut tx,
+            product_id,
+            request.from_location_id,
+            -request.quantity,
+            Decimal::ZERO,
+        )
+        .await?;
+        Self::apply_with(
+            &mut tx,
+            product_id,
+            request.to_location_id,
+            request.quantity,
+            Decimal::ZERO,
+        )
+        .await?;
+
+        StockBatchService::transfer(
+            &mut tx,
+            product_id,
+            request.from_location_id,
+            request.to_location_id,
+            request.quantity,
+        )
+        .await?;
+
+        StockMovementService::record(
+            &mut tx,
+            product_id,
+            request.from_location_id,
+            -request.quantity,
+            Decimal::ZERO,
+            StockMovementReason::Transfer,
+            context,
+        )
+        .await?;
+        StockMovementService::record(
+            &mut tx,
+            product_id,
+            request.to_location_id,
+            request.quantity,
+            Decimal::ZERO,
+            StockMovementReason::Transfer,
+            context,
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| {
+            AppError::DatabaseError(format!("Failed to commit stock transfer: {}", e))
+        })?;
+
+        info!(
+            "Transferred {} units of product {} from location {} to {}",
+            request.quantity, product_id, request.from_location_id, request.to_location_id
+        );
+        Ok(())
+    }
+
+    /// Pick the location to reserve an order at. Pickup orders always use their pickup
+    /// point. Deliveries use the location for their zone, unless another location can
+    /// supply every item while that one cannot. `items` must already be expanded into
+    /// stocked products.
+    pub async fn select_for_order_with(
+        conn: &mut PgConnection,
+        pickup_location_id: Option<Uuid>,
+        postal_code: Option<&str>,
+        items: &[InventoryReservation],
+    ) -> Result<Uuid, AppError> {
+        let locations = Self::active_locations_with(&mut *conn).await?;
+        let preferred = preferred_location(&locations, pickup_location_id, postal_code)
+            .map_err(AppError::ValidationError)?;
+
+        if pickup_location_id.is_some()
+            || Self::can_supply_with(&mut *conn, preferred, items).await?
+        {
+            return Ok(preferred);
+        }
+
+        for location in locations.iter().filter(|location| location.id != preferred) {
+            if Self::can_supply_with(&mut *conn, location.id, items).await? {
+                return Ok(location.id);
+            }
+        }
+
+        Ok(preferred)
+    }
+
+    /// Pick the location to reserve an order at, for the products as ordered
+    pub async fn select_for_order(
+        pickup_location_id: Option<Uuid>,
+        postal_code: Option<&str>,
+        reservations: &[InventoryReservation],
+    ) -> Result<Uuid, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        let items =
+            BillOfMaterialsService::expand_reservations_with(&mut conn, reservations).await?;
+        Self::select_for_order_with(&mut conn, pickup_location_id, postal_code, &items).await
+    }
+
+    /// The location stock goes to when none is given: the first active one by priority
+    pub async fn default_location_with(conn: &mut PgConnection) -> Result<Uuid, AppError> {
+        Self::active_locations_with(conn)
+            .await?
+            .first()
+            .map(|location| location.id)
+            .ok_or_else(|| AppError::ValidationError("No stock location is available".to_string()))
+    }
+
+    pub async fn default_location() -> Result<Uuid, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::default_location_with(&mut conn).await
+    }
+
+    /// The given location, or the default one when none is given
+    pub async fn or_default(location_id: Option<Uuid>) -> Result<Uuid, AppError> {
+        match location_id {
+            Some(location_id) => Ok(location_id),
+            None => Self::default_location().await,
+        }
+    }
+
+    pub async fn ensure_active_with(
+        conn: &mut PgConnection,
+        location_id: Uuid,
+    ) -> Result<(), AppError> {
+        let is_active =
+            sqlx::query_scalar::<_, bool>("SELECT is_active FROM stock_locations WHERE id = $1")
+                .bind(location_id)
+                .fetch_optional(conn)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to fetch stock location: {}", e))
+                })?
+                .ok_or_else(|| AppError::not_found("Stock location"))?;
+
+        if !is_active {
+            return Err(AppError::ValidationError(
+                "This stock location is no longer active".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Lock the stock of a product at a location. A product that was never stocked there
+    /// has nothing on hand.
+    pub async fn lock_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+    ) -> Result<(Decimal, Decimal), AppError> {
+        let row = sqlx::query_as::<_, (Decimal, Decimal)>(
+            r#"
+            SELECT quantity_on_hand, quantity_reserved
+            FROM location_inventory
+            WHERE product_id = $1 AND location_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(product_id)
+        .bind(location_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to check stock of product {} at location {}: {}",
+                product_id, location_id, e
+            ))
+        })?;
+
+        Ok(row.unwrap_or((Decimal::ZERO, Decimal::ZERO)))
+    }
+
+    /// Change the stock of a product at a location, and the product total with it. The
+    /// inventory row of the product must exist.
+    pub async fn apply_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        on_hand_change: Decimal,
+        reserved_change: Decimal,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO location_inventory (location_id, product_id, quantity_on_hand, quantity_reserved)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (location_id, product_id) DO UPDATE
+            SET
+                quantity_on_hand = location_inventory.quantity_on_hand + EXCLUDED.quantity_on_hand,
+                quantity_reserved = location_inventory.quantity_reserved + EXCLUDED.quantity_reserved,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(location_id)
+        .bind(product_id)
+        .bind(on_hand_change)
+        .bind(reserved_change)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to update stock of product {} at location {}: {}",
+                product_id, location_id, e
+            ))
+        })?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE inventory
+            SET
+                quantity_on_hand = quantity_on_hand + $1,
+                quantity_reserved = quantity_reserved + $2,
+                updated_at = NOW()
+            WHERE product_id = $3
+            "#,
+        )
+        .bind(on_hand_change)
+        .bind(reserved_change)
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to update inventory for product {}: {}",
+                product_id, e
+            ))
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Product {} not found in inventory",
+                product_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn active_locations_with(
+        conn: &mut PgConnection,
+    ) -> Result<Vec<StockLocation>, AppError> {
+        sqlx::query_as::<_, StockLocation>(&format!(
+            "SELECT {} FROM stock_locations WHERE is_active = true ORDER BY priority ASC, created_at ASC",
+            LOCATION_COLUMNS
+        ))
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch stock locations: {}", e)))
+    }
+
+    async fn can_supply_with(
+        conn: &mut PgConnection,
+        location_id: Uuid,
+        items: &[InventoryReservation],
+    ) -> Result<bool, AppError> {
+        let product_ids: Vec<Uuid> = items.iter().map(|item| item.product_id).collect();
+        let available: HashMap<Uuid, Decimal> = sqlx::query_as::<_, (Uuid, Decimal)>(
+            r#"
+            SELECT product_id, quantity_on_hand - quantity_reserved
+            FROM location_inventory
+            WHERE product_id = ANY($1) AND location_id = $2
+            "#,
+        )
+        .bind(&product_ids)
+        .bind(location_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check location stock: {}", e)))?
+        .into_iter()
+        .collect();
+
+        Ok(can_supply(&available, items))
+    }
+
+    fn map_write_error(e: sqlx::Error) -> AppError {
+        match &e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict("A location with this name already exists".to_string())
+            }
+            _ => AppError::DatabaseError(format!("Failed to save stock location: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::stock_movement::{
+    MovementContext, StockLedgerCheck, StockLedgerReport, StockMovement, StockMovementReason,
+};
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+const DEFAULT_MOVEMENT_LIMIT: i64 = 50;
+const MAX_MOVEMENT_LIMIT: i64 = 500;
+
+/// Service for the append-only stock ledger
+pub struct StockMovementService;
+
+impl StockMovementService {
+    /// Record a stock change at a location. Runs on the connection of the inventory update,
+    /// so the movement is only stored when the update itself is committed.
+    pub async fn record(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+        location_id: Uuid,
+        on_hand_change: Decimal,
+        reserved_change: Decimal,
+        reason: StockMovementReason,
+        context: &MovementContext,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO stock_movements (
+                product_id, location_id, on_hand_change, reserved_change, reason, order_id,
+                user_id, note
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(product_id)
+        .bind(location_id)
+        .bind(on_hand_change)
+        .bind(reserved_change)
+        .bind(reason)
+        .bind(context.order_id)
+        .bind(context.user_id)
+        .bind(&context.note)
+        .execute(conn)
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to record stock movement for product {}: {}",
+                product_id, e
+            ))
+        })?;
+
+        Ok(())
+    }
+
+    /// Movements of a product, newest first
+    pub async fn list_movements(
+        product_id: Uuid,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<StockMovement>, AppError> {
+        let pool = pool();
+        let limit = limit
+            .unwrap_or(DEFAULT_MOVEMENT_LIMIT)
+            .clamp(1, MAX_MOVEMENT_LIMIT);
+        let offset = offset.unwrap_or(0).max(0);
+
+        sqlx::query_as::<_, StockMovement>(
+            r#"
+            SELECT id, product_id, location_id, on_hand_change, reserved_change, reason,
+                   order_id, user_id, note, created_at
+            FROM stock_movements
+            WHERE product_id = $1
+            ORDER BY created_at DESC, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(product_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch stock movements: {}", e)))
+    }
+
+    /// Verify the stock of every product in the catalogue against the ledger: sum each
+    /// product's movements and report the products whose inventory does not match. A
+    /// product with movements but no inventory row counts as having nothing in stock.
+    /// Nothing is changed; the stock ledger job runs this regularly.
+    pub async fn check_ledger() -> Result<StockLedgerReport, AppError> {
+        let pool = pool();
+
+        let checks = sqlx::query_as::<_, StockLedgerCheck>(
+            r#"
+            SELECT
+                p.id AS product_id,
+                p.name,
+                p.sku,
+                COALESCE(i.quantity_on_hand, 0) AS quantity_on_hand,
+                COALESCE(i.quantity_reserved, 0) AS quantity_reserved,
+                COALESCE(m.on_hand, 0) AS ledger_on_hand,
+                COALESCE(m.reserved, 0) AS ledger_reserved
+            FROM products p
+            LEFT JOIN inventory i ON i.product_id = p.id
+            LEFT JOIN (
+                SELECT product_id, SUM(on_hand_change) AS on_hand, SUM(reserved_change) AS reserved
+                FROM stock_movements
+                GROUP BY product_id
+            ) m ON m.product_id = p.id
+            ORDER BY p.name ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to verify stock ledger: {}", e)))?;
+
+        let products_checked = checks.len();
+        let inconsistent: Vec<StockLedgerCheck> = checks
+            .into_iter()
+            .filter(|check| !check.is_consistent())
+            .collect();
+
+        if !inconsistent.is_empty() {
+            warn!(
+                "Stock ledger check: {} of {} products do not match their movements",
+                inconsistent.len(),
+                products_checked
+            );
+        }
+
+        Ok(StockLedgerReport {
+            products_checked,
+            inconsistent,
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::purchase_order::{CreateSupplierRequest, Supplier, UpdateSupplierRequest};
+use uuid::Uuid;
+
+const SUPPLIER_COLUMNS: &str =
+    "id, name, contact_name, email, phone, notes, is_active, created_at, updated_at";
+
+/// Service for the suppliers we buy stock from
+pub struct SupplierService;
+
+impl SupplierService {
+    /// Suppliers by name, active ones only unless `include_inactive` is set
+    pub async fn list_suppliers(include_inactive: bool) -> Result<Vec<Supplier>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Supplier>(&format!(
+            "SELECT {} FROM suppliers WHERE $1 OR is_active = true ORDER BY name ASC",
+            SUPPLIER_COLUMNS
+        ))
+        .bind(include_inactive)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch suppliers: {}", e)))
+    }
+
+    pub async fn get_supplier(supplier_id: Uuid) -> Result<Supplier, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Supplier>(&format!(
+            "SELECT {} FROM suppliers WHERE id = $1",
+            SUPPLIER_COLUMNS
+        ))
+        .bind(supplier_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch supplier: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Supplier"))
+    }
+
+    pub async fn create_supplier(request: &CreateSupplierRequest) -> Result<Supplier, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Supplier>(&format!(
+            r#"
+            INSERT INTO suppliers (name, contact_name, email, phone, notes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            SUPPLIER_COLUMNS
+        ))
+        .bind(request.name.trim())
+        .bind(&request.contact_name)
+        .bind(&request.email)
+        .bind(&request.phone)
+        .bind(&request.notes)
+        .fetch_one(pool)
+        .await
+        .map_err(Self::map_write_error)
+    }
+
+    pub async fn update_supplier(
+        supplier_id: Uuid,
+        request: &UpdateSupplierRequest,
+    ) -> Result<Supplier, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Supplier>(&format!(
+            r#"
+            UPDATE suppliers
+            SET
+                name = COALESCE($2, name),
+                contact_name = COALESCE($3, contact_name),
+                email = COALESCE($4, email),
+                phone = COALESCE($5, phone),
+                notes = COALESCE($6, notes),
+                is_active = COALESCE($7, is_active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SUPPLIER_COLUMNS
+        ))
+        .bind(supplier_id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(&request.contact_name)
+        .bind(&request.email)
+        .bind(&request.phone)
+        .bind(&request.notes)
+        .bind(request.is_active)
+        .fetch_optional(pool)
+        .await
+        .map_err(Self::map_write_error)?
+        .ok_or_else(|| AppError::not_found("Supplier"))
+    }
+
+    fn map_write_error(e: sqlx::Error) -> AppError {
+        match &e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict("A supplier with this name already exists".to_string())
+            }
+            _ => AppError::DatabaseError(format!("Failed to save supplier: {}", e)),
+        }
+    }
+}
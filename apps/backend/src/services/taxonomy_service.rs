@@ -0,0 +1,418 @@
+use crate::pool::connect::pool;
+use crate::response::error::AppError;
+use crate::structs::taxonomy::{
+    Category, CategoryNode, CategoryRequest, Occasion, OccasionRequest, ProductTaxonomy,
+    build_category_tree, is_within,
+};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+const CATEGORY_COLUMNS: &str =
+    "id, parent_id, name, description, sort_order, is_active, created_at, updated_at";
+const OCCASION_COLUMNS: &str = "id, name, created_at";
+
+/// Service for browsing the catalogue by category and occasion
+pub struct TaxonomyService;
+
+impl TaxonomyService {
+    /// Categories in display order, active ones only unless `include_inactive` is set
+    pub async fn list_categories(include_inactive: bool) -> Result<Vec<Category>, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::list_categories_with(&mut conn, include_inactive).await
+    }
+
+    /// The category hierarchy. Subcategories of an inactive category are hidden with it.
+    pub async fn category_tree(include_inactive: bool) -> Result<Vec<CategoryNode>, AppError> {
+        Ok(build_category_tree(
+            Self::list_categories(include_inactive).await?,
+        ))
+    }
+
+    pub async fn create_category(request: &CategoryRequest) -> Result<Category, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        if let Some(parent_id) = request.parent_id {
+            let categories = Self::list_categories_with(&mut tx, true).await?;
+            if !categories.iter().any(|category| category.id == parent_id) {
+                return Err(AppError::not_found("Parent category"));
+            }
+        }
+
+        let category = sqlx::query_as::<_, Category>(&format!(
+            r#"
+            INSERT INTO categories (parent_id, name, description, sort_order, is_active)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            CATEGORY_COLUMNS
+        ))
+        .bind(request.parent_id)
+        .bind(request.name.trim())
+        .bind(clean_description(&request.description))
+        .bind(request.sort_order)
+        .bind(request.is_active)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Self::map_write_error(e, "A category with this name already exists here"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit category: {}", e)))?;
+
+        Ok(category)
+    }
+
+    /// Replace a category. It can move under another parent, but not under itself or one
+    /// of its own subcategories.
+    pub async fn update_category(
+        category_id: Uuid,
+        request: &CategoryRequest,
+    ) -> Result<Category, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        // Lock the hierarchy so two moves cannot create a cycle together
+        sqlx::query("LOCK TABLE categories IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to lock categories: {}", e)))?;
+
+        let categories = Self::list_categories_with(&mut tx, true).await?;
+        if !categories.iter().any(|category| category.id == category_id) {
+            return Err(AppError::not_found("Category"));
+        }
+        if let Some(parent_id) = request.parent_id {
+            if !categories.iter().any(|category| category.id == parent_id) {
+                return Err(AppError::not_found("Parent category"));
+            }
+            if is_within(&categories, parent_id, category_id) {
+                return Err(AppError::ValidationError(
+                    "A category cannot be moved under itself or one of its subcategories"
+                        .to_string(),
+                ));
+            }
+        }
+
+        let category = sqlx::query_as::<_, Category>(&format!(
+            r#"
+            UPDATE categories
+            SET
+                parent_id = $2,
+                name = $3,
+                description = $4,
+                sort_order = $5,
+                is_active = $6,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            CATEGORY_COLUMNS
+        ))
+        .bind(category_id)
+        .bind(request.parent_id)
+        .bind(request.name.trim())
+        .bind(clean_description(&request.description))
+        .bind(request.sort_order)
+        .bind(request.is_active)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Self::map_write_error(e, "A category with this name already exists here"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit category: {}", e)))?;
+
+        Ok(category)
+    }
+
+    /// Delete a category without subcategories. Its products stay in the catalogue.
+    pub async fn delete_category(category_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let has_children = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM categories WHERE parent_id = $1)",
+        )
+        .bind(category_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check category: {}", e)))?;
+
+        if has_children {
+            return Err(AppError::Conflict(
+                "Move or delete the subcategories of this category first".to_string(),
+            ));
+        }
+
+        let result = sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(category_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                Self::map_write_error(e, "Move or delete the subcategories of this category first")
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Category"));
+        }
+        Ok(())
+    }
+
+    /// Occasions by name
+    pub async fn list_occasions() -> Result<Vec<Occasion>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Occasion>(&format!(
+            "SELECT {} FROM occasions ORDER BY name ASC",
+            OCCASION_COLUMNS
+        ))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch occasions: {}", e)))
+    }
+
+    pub async fn create_occasion(request: &OccasionRequest) -> Result<Occasion, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, Occasion>(&format!(
+            "INSERT INTO occasions (name) VALUES ($1) RETURNING {}",
+            OCCASION_COLUMNS
+        ))
+        .bind(request.name.trim())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, "An occasion with this name already exists"))
+    }
+
+    pub async fn rename_occasion(
+        occasion_id: Uuid,
+        request: &OccasionRequest,
+    ) -> Result<Occasion, AppError> {
+        request.validate().map_err(AppError::ValidationError)?;
+
+        let pool = pool();
+
+        sqlx::query_as::<_, Occasion>(&format!(
+            "UPDATE occasions SET name = $2 WHERE id = $1 RETURNING {}",
+            OCCASION_COLUMNS
+        ))
+        .bind(occasion_id)
+        .bind(request.name.trim())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, "An occasion with this name already exists"))?
+        .ok_or_else(|| AppError::not_found("Occasion"))
+    }
+
+    /// Delete an occasion. Products tagged with it lose the tag.
+    pub async fn delete_occasion(occasion_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("DELETE FROM occasions WHERE id = $1")
+            .bind(occasion_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to delete occasion: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Occasion"));
+        }
+        Ok(())
+    }
+
+    /// The categories and occasions of a product
+    pub async fn product_taxonomy(product_id: Uuid) -> Result<ProductTaxonomy, AppError> {
+        let pool = pool();
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to acquire connection: {}", e)))?;
+
+        Self::product_taxonomy_with(&mut conn, product_id).await
+    }
+
+    /// Replace the categories a product is listed under
+    pub async fn set_product_categories(
+        product_id: Uuid,
+        category_ids: &[Uuid],
+    ) -> Result<ProductTaxonomy, AppError> {
+        Self::replace_links(
+            product_id,
+            category_ids,
+            "categories",
+            "product_categories",
+            "category_id",
+            "Category",
+        )
+        .await
+    }
+
+    /// Replace the occasions a product is tagged with
+    pub async fn set_product_occasions(
+        product_id: Uuid,
+        occasion_ids: &[Uuid],
+    ) -> Result<ProductTaxonomy, AppError> {
+        Self::replace_links(
+            product_id,
+            occasion_ids,
+            "occasions",
+            "product_occasions",
+            "occasion_id",
+            "Occasion",
+        )
+        .await
+    }
+
+    async fn replace_links(
+        product_id: Uuid,
+        ids: &[Uuid],
+        table: &str,
+        link_table: &str,
+        link_column: &str,
+        kind: &str,
+    ) -> Result<ProductTaxonomy, AppError> {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        ids.dedup();
+
+        let pool = pool();
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to start transaction: {}", e)))?;
+
+        let product_exists =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM products WHERE id = $1)")
+                .bind(product_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch product: {}", e)))?;
+        if !product_exists {
+            return Err(AppError::not_found("Product"));
+        }
+
+        let found = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(*) FROM {} WHERE id = ANY($1)",
+            table
+        ))
+        .bind(&ids)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check {}: {}", table, e)))?;
+        if found as usize != ids.len() {
+            return Err(AppError::not_found(kind));
+        }
+
+        sqlx::query(&format!("DELETE FROM {} WHERE product_id = $1", link_table))
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to update {}: {}", table, e)))?;
+
+        sqlx::query(&format!(
+            "INSERT INTO {} (product_id, {}) SELECT $1, UNNEST($2::uuid[])",
+            link_table, link_column
+        ))
+        .bind(product_id)
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to update {}: {}", table, e)))?;
+
+        let taxonomy = Self::product_taxonomy_with(&mut tx, product_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit {}: {}", table, e)))?;
+
+        Ok(taxonomy)
+    }
+
+    async fn product_taxonomy_with(
+        conn: &mut PgConnection,
+        product_id: Uuid,
+    ) -> Result<ProductTaxonomy, AppError> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT c.id, c.parent_id, c.name, c.description, c.sort_order, c.is_active,
+                   c.created_at, c.updated_at
+            FROM categories c
+            JOIN product_categories pc ON pc.category_id = c.id
+            WHERE pc.product_id = $1
+            ORDER BY c.sort_order ASC, c.name ASC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch categories: {}", e)))?;
+
+        let occasions = sqlx::query_as::<_, Occasion>(
+            r#"
+            SELECT o.id, o.name, o.created_at
+            FROM occasions o
+            JOIN product_occasions po ON po.occasion_id = o.id
+            WHERE po.product_id = $1
+            ORDER BY o.name ASC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch occasions: {}", e)))?;
+
+        Ok(ProductTaxonomy {
+            categories,
+            occasions,
+        })
+    }
+
+    async fn list_categories_with(
+        conn: &mut PgConnection,
+        include_inactive: bool,
+    ) -> Result<Vec<Category>, AppError> {
+        sqlx::query_as::<_, Category>(&format!(
+            "SELECT {} FROM categories WHERE $1 OR is_active = true ORDER BY sort_order ASC, name ASC",
+            CATEGORY_COLUMNS
+        ))
+        .bind(include_inactive)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch categories: {}", e)))
+    }
+
+    fn map_write_error(error: sqlx::Error, conflict: &str) -> AppError {
+        match error {
+            sqlx::Error::Database(db_error)
+                if db_error.is_unique_violation() || db_error.is_foreign_key_violation() =>
+            {
+                AppError::Conflict(conflict.to_string())
+            }
+            e => AppError::DatabaseError(format!("Failed to save: {}", e)),
+        }
+    }
+}
+
+fn clean_description(description: &Option<String>) -> Option<&str> {
+    description
+        .as_deref()
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+}
@@ -0,0 +1,477 @@
+use crate::pool::connect::pool;
+use crate::response::{AppResponse, error::AppError};
+use crate::secrets;
+use crate::services::{CartService, PromotionService};
+use crate::structs::cart::{AddCartItemRequest, CartItemWithProduct};
+use crate::structs::wishlist::{
+    AddWishlistItemRequest, CreateWishlistRequest, SAVED_FOR_LATER_LIST, SharedWishlistResponse,
+    UpdateWishlistRequest, Wishlist, WishlistItemDetails, WishlistItemRow, WishlistResponse,
+    WishlistShareLink, WishlistSummary,
+};
+use sqlx::Row;
+use uuid::Uuid;
+
+const WISHLIST_COLUMNS: &str =
+    "id, user_id, name, occasion_date, share_token, created_at, updated_at";
+
+/// Service for named wishlists and the "saved for later" list
+pub struct WishlistService;
+
+impl WishlistService {
+    /// All lists of a user with their item counts
+    pub async fn list_wishlists(user_id: Uuid) -> Result<Vec<WishlistSummary>, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, WishlistSummary>(
+            r#"
+            SELECT
+                w.id, w.name, w.occasion_date,
+                w.share_token IS NOT NULL AS is_shared,
+                COUNT(wi.id) AS item_count,
+                w.created_at, w.updated_at
+            FROM wishlists w
+            LEFT JOIN wishlist_items wi ON wi.wishlist_id = w.id
+            WHERE w.user_id = $1
+            GROUP BY w.id
+            ORDER BY w.created_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch wishlists: {}", e)))
+    }
+
+    /// Create a new named list
+    pub async fn create_wishlist(
+        user_id: Uuid,
+        request: CreateWishlistRequest,
+    ) -> Result<Wishlist, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Wishlist>(&format!(
+            "INSERT INTO wishlists (user_id, name, occasion_date) VALUES ($1, $2, $3) RETURNING {}",
+            WISHLIST_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(request.name.trim())
+        .bind(request.occasion_date)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, "create wishlist"))
+    }
+
+    /// Get a list with its items, current prices and price-drop flags
+    pub async fn get_wishlist(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+    ) -> Result<WishlistResponse, AppError> {
+        let wishlist = Self::get_owned_wishlist(user_id, wishlist_id).await?;
+        let items = Self::get_item_details(wishlist.id).await?;
+
+        Ok(WishlistResponse { wishlist, items })
+    }
+
+    /// Rename a list or change its occasion date
+    pub async fn update_wishlist(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+        request: UpdateWishlistRequest,
+    ) -> Result<Wishlist, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Wishlist>(&format!(
+            r#"
+            UPDATE wishlists
+            SET name = COALESCE($3, name), occasion_date = COALESCE($4, occasion_date)
+            WHERE id = $1 AND user_id = $2
+            RETURNING {}
+            "#,
+            WISHLIST_COLUMNS
+        ))
+        .bind(wishlist_id)
+        .bind(user_id)
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(request.occasion_date)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, "update wishlist"))?
+        .ok_or_else(|| AppError::not_found("Wishlist"))
+    }
+
+    /// Delete a list and all its items
+    pub async fn delete_wishlist(user_id: Uuid, wishlist_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query("DELETE FROM wishlists WHERE id = $1 AND user_id = $2")
+            .bind(wishlist_id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to delete wishlist: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Wishlist"));
+        }
+        Ok(())
+    }
+
+    /// Add a product to a list. Adding a product that is already on the list updates its
+    /// quantity but keeps the originally saved price, so price drops stay visible.
+    pub async fn add_item(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+        request: AddWishlistItemRequest,
+    ) -> Result<WishlistResponse, AppError> {
+        let wishlist = Self::get_owned_wishlist(user_id, wishlist_id).await?;
+        Self::save_product(
+            wishlist.id,
+            request.product_id,
+            request.quantity.unwrap_or(1),
+        )
+        .await?;
+
+        let items = Self::get_item_details(wishlist.id).await?;
+        Ok(WishlistResponse { wishlist, items })
+    }
+
+    /// Remove an item from a list
+    pub async fn remove_item(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM wishlist_items wi
+            USING wishlists w
+            WHERE wi.id = $1 AND wi.wishlist_id = $2 AND w.id = wi.wishlist_id AND w.user_id = $3
+            "#,
+        )
+        .bind(item_id)
+        .bind(wishlist_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to remove wishlist item: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Wishlist item"));
+        }
+        Ok(())
+    }
+
+    /// Move an item into the cart. The item only leaves the list once the cart accepted it.
+    pub async fn move_item_to_cart(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+        item_id: Uuid,
+    ) -> AppResponse<CartItemWithProduct> {
+        let pool = pool();
+
+        let item = match sqlx::query(
+            r#"
+            SELECT wi.product_id, wi.quantity
+            FROM wishlist_items wi
+            JOIN wishlists w ON w.id = wi.wishlist_id
+            WHERE wi.id = $1 AND wi.wishlist_id = $2 AND w.user_id = $3
+            "#,
+        )
+        .bind(item_id)
+        .bind(wishlist_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        {
+            Ok(Some(row)) => row,
+            Ok(None) => return AppResponse::Error(AppError::not_found("Wishlist item")),
+            Err(e) => {
+                return AppResponse::Error(AppError::DatabaseError(format!(
+                    "Failed to fetch wishlist item: {}",
+                    e
+                )));
+            }
+        };
+
+        let request = AddCartItemRequest {
+            product_id: item.get("product_id"),
+            quantity: item.get("quantity"),
+        };
+
+        let cart_item = match CartService::add_item_to_cart(user_id, request).await {
+            AppResponse::Success(cart_item) => cart_item,
+            AppResponse::Error(e) => return AppResponse::Error(e),
+        };
+
+        if let Err(e) = sqlx::query("DELETE FROM wishlist_items WHERE id = $1")
+            .bind(item_id)
+            .execute(pool)
+            .await
+        {
+            tracing::warn!("Failed to remove moved wishlist item {}: {}", item_id, e);
+        }
+
+        AppResponse::Success(cart_item)
+    }
+
+    /// Move a cart item to the user's "saved for later" list
+    pub async fn save_cart_item_for_later(
+        user_id: Uuid,
+        cart_item_id: Uuid,
+    ) -> Result<WishlistResponse, AppError> {
+        let pool = pool();
+
+        let cart_item = sqlx::query(
+            r#"
+            SELECT ci.product_id, ci.quantity
+            FROM cart_items ci
+            JOIN carts c ON c.id = ci.cart_id
+            WHERE ci.id = $1 AND c.user_id = $2
+            "#,
+        )
+        .bind(cart_item_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch cart item: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Cart item"))?;
+
+        let wishlist = Self::get_or_create_saved_for_later(user_id).await?;
+        Self::save_product(
+            wishlist.id,
+            cart_item.get("product_id"),
+            cart_item.get("quantity"),
+        )
+        .await?;
+
+        if let AppResponse::Error(e) = CartService::remove_cart_item(cart_item_id).await {
+            return Err(e);
+        }
+
+        let items = Self::get_item_details(wishlist.id).await?;
+        Ok(WishlistResponse { wishlist, items })
+    }
+
+    /// Create (or return the existing) read-only share link for a list
+    pub async fn share_wishlist(
+        user_id: Uuid,
+        wishlist_id: Uuid,
+    ) -> Result<WishlistShareLink, AppError> {
+        let pool = pool();
+
+        let share_token: String = sqlx::query_scalar(
+            r#"
+            UPDATE wishlists
+            SET share_token = COALESCE(share_token, $3)
+            WHERE id = $1 AND user_id = $2
+            RETURNING share_token
+            "#,
+        )
+        .bind(wishlist_id)
+        .bind(user_id)
+        .bind(Uuid::new_v4().simple().to_string())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to share wishlist: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Wishlist"))?;
+
+        Ok(WishlistShareLink {
+            share_url: Self::build_share_url(&secrets::get_frontend_url(), &share_token),
+            share_token,
+        })
+    }
+
+    /// Revoke the share link so the old URL stops working
+    pub async fn unshare_wishlist(user_id: Uuid, wishlist_id: Uuid) -> Result<(), AppError> {
+        let pool = pool();
+
+        let result =
+            sqlx::query("UPDATE wishlists SET share_token = NULL WHERE id = $1 AND user_id = $2")
+                .bind(wishlist_id)
+                .bind(user_id)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to unshare wishlist: {}", e))
+                })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Wishlist"));
+        }
+        Ok(())
+    }
+
+    /// Public read-only view of a shared list
+    pub async fn get_shared_wishlist(
+        share_token: &str,
+    ) -> Result<SharedWishlistResponse, AppError> {
+        let pool = pool();
+
+        let row = sqlx::query(
+            r#"
+            SELECT w.id, w.name, w.occasion_date, u.first_name
+            FROM wishlists w
+            JOIN users u ON u.id = w.user_id
+            WHERE w.share_token = $1
+            "#,
+        )
+        .bind(share_token)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch shared wishlist: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Wishlist"))?;
+
+        let items = Self::get_item_details(row.get("id")).await?;
+
+        Ok(SharedWishlistResponse {
+            name: row.get("name"),
+            occasion_date: row.get("occasion_date"),
+            owner_first_name: row.get("first_name"),
+            items,
+        })
+    }
+
+    /// Link the frontend uses to show a shared list
+    pub fn build_share_url(base_url: &str, share_token: &str) -> String {
+        format!(
+            "{}/wishlists/shared/{}",
+            base_url.trim_end_matches('/'),
+            share_token
+        )
+    }
+
+    async fn get_owned_wishlist(user_id: Uuid, wishlist_id: Uuid) -> Result<Wishlist, AppError> {
+        let pool = pool();
+
+        sqlx::query_as::<_, Wishlist>(&format!(
+            "SELECT {} FROM wishlists WHERE id = $1 AND user_id = $2",
+            WISHLIST_COLUMNS
+        ))
+        .bind(wishlist_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch wishlist: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Wishlist"))
+    }
+
+    async fn get_or_create_saved_for_later(user_id: Uuid) -> Result<Wishlist, AppError> {
+        let pool = pool();
+
+        // The no-op update makes RETURNING yield the existing row on conflict
+        sqlx::query_as::<_, Wishlist>(&format!(
+            r#"
+            INSERT INTO wishlists (user_id, name) VALUES ($1, $2)
+            ON CONFLICT (user_id, name) DO UPDATE SET name = EXCLUDED.name
+            RETURNING {}
+            "#,
+            WISHLIST_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(SAVED_FOR_LATER_LIST)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to get saved for later list: {}", e)))
+    }
+
+    /// Insert or update a product on a list, recording the price it currently sells for
+    async fn save_product(
+        wishlist_id: Uuid,
+        product_id: Uuid,
+        quantity: i32,
+    ) -> Result<(), AppError> {
+        let product = match crate::actions::get::get_product_by_id(product_id).await {
+            Ok(Some(product)) => product,
+            Ok(None) => return Err(AppError::not_found("Product")),
+            Err(e) => {
+                return Err(AppError::DatabaseError(format!(
+                    "Failed to fetch product: {}",
+                    e
+                )));
+            }
+        };
+
+        let pool = pool();
+
+        sqlx::query(
+            r#"
+            INSERT INTO wishlist_items (wishlist_id, product_id, quantity, saved_price)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (wishlist_id, product_id) DO UPDATE SET quantity = EXCLUDED.quantity
+            "#,
+        )
+        .bind(wishlist_id)
+        .bind(product.id)
+        .bind(quantity)
+        .bind(product.discounted_price.round_dp(2))
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to save wishlist item: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_item_details(wishlist_id: Uuid) -> Result<Vec<WishlistItemDetails>, AppError> {
+        let pool = pool();
+
+        let rows = sqlx::query_as::<_, WishlistItemRow>(
+            r#"
+            SELECT
+                wi.id, wi.product_id, wi.quantity, wi.saved_price, wi.created_at,
+                p.name AS product_name, p.sku AS product_sku, p.price AS product_price,
+                (
+                    SELECT pi.url FROM product_images pi
+                    WHERE pi.product_id = p.id
+                    ORDER BY pi.is_primary DESC
+                    LIMIT 1
+                ) AS primary_image_url,
+                COALESCE(i.quantity_on_hand - i.quantity_reserved, 0) AS available_stock
+            FROM wishlist_items wi
+            JOIN products p ON p.id = wi.product_id
+            LEFT JOIN product_stock i ON i.product_id = p.id
+            WHERE wi.wishlist_id = $1 AND p.is_active = true
+            ORDER BY wi.created_at ASC
+            "#,
+        )
+        .bind(wishlist_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch wishlist items: {}", e)))?;
+
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let base_prices: Vec<(Uuid, rust_decimal::Decimal)> = rows
+            .iter()
+            .map(|row| (row.product_id, row.product_price))
+            .collect();
+
+        let current_prices = match PromotionService::get_current_prices(&base_prices).await {
+            AppResponse::Success(prices) => prices,
+            AppResponse::Error(e) => return Err(e),
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let current_price = current_prices
+                    .get(&row.product_id)
+                    .copied()
+                    .unwrap_or(row.product_price);
+                WishlistItemDetails::from_row(row, current_price)
+            })
+            .collect())
+    }
+
+    fn map_write_error(error: sqlx::Error, action: &str) -> AppError {
+        match error {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                AppError::conflict("A wishlist with this name")
+            }
+            e => AppError::DatabaseError(format!("Failed to {}: {}", action, e)),
+        }
+    }
+}
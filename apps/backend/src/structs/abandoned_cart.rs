@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A reminder queued for a cart that was left without checking out
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct AbandonedCartReminder {
+    pub id: Uuid,
+    pub cart_id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub restore_token: String,
+    pub cart_last_activity: DateTime<Utc>,
+    pub items: serde_json::Value, // Snapshot of the cart items at reminder time
+    pub email_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub restored_at: Option<DateTime<Utc>>,
+    pub recovered_at: Option<DateTime<Utc>>,
+    pub recovered_order_id: Option<Uuid>,
+}
+
+/// A cart that qualifies for a reminder
+#[derive(FromRow, Debug, Clone)]
+pub struct AbandonedCart {
+    pub cart_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub first_name: String,
+    pub last_activity: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RestoreCartRequest {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CartReminderPreference {
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AbandonedCartStats {
+    pub reminders_queued: i64,
+    pub carts_restored: i64,
+    pub carts_recovered: i64,
+    pub recovery_rate: Decimal, // Percentage of reminders that ended in an order
+    pub recovered_revenue: Decimal,
+}
@@ -0,0 +1,120 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// A component of a bouquet recipe, with the stock it currently has
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct BouquetComponent {
+    pub component_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub quantity: Decimal, // Units used per bouquet
+    pub available_quantity: Decimal,
+}
+
+/// The recipe of a bouquet. A bouquet without components keeps its own stock.
+#[derive(Serialize, Debug, Clone)]
+pub struct BouquetRecipe {
+    pub bouquet_id: Uuid,
+    pub name: String,
+    pub components: Vec<BouquetComponent>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RecipeComponent {
+    pub component_id: Uuid,
+    pub quantity: Decimal,
+}
+
+/// Replaces the recipe of a bouquet; an empty list removes it
+#[derive(Deserialize, Debug)]
+pub struct SetRecipeRequest {
+    pub components: Vec<RecipeComponent>,
+}
+
+impl SetRecipeRequest {
+    /// Checks that do not need the database
+    pub fn validate(&self, bouquet_id: Uuid) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for component in &self.components {
+            if component.component_id == bouquet_id {
+                return Err("A bouquet cannot be a component of itself".to_string());
+            }
+            if component.quantity <= Decimal::ZERO {
+                return Err(format!(
+                    "Quantity of component {} must be greater than zero",
+                    component.component_id
+                ));
+            }
+            if !seen.insert(component.component_id) {
+                return Err(format!(
+                    "Component {} is listed more than once",
+                    component.component_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace every product that has a recipe by its components, multiplied by the ordered
+/// quantity. Quantities of the same product are added up, in order of first appearance.
+pub fn expand_components(
+    items: &[(Uuid, Decimal)],
+    recipes: &HashMap<Uuid, Vec<(Uuid, Decimal)>>,
+) -> Vec<(Uuid, Decimal)> {
+    fn add(expanded: &mut Vec<(Uuid, Decimal)>, product_id: Uuid, quantity: Decimal) {
+        match expanded.iter_mut().find(|(id, _)| *id == product_id) {
+            Some((_, total)) => *total += quantity,
+            None => expanded.push((product_id, quantity)),
+        }
+    }
+
+    let mut expanded = Vec::new();
+    for (product_id, quantity) in items {
+        match recipes.get(product_id) {
+            Some(components) if !components.is_empty() => {
+                for (component_id, per_bouquet) in components {
+                    add(&mut expanded, *component_id, per_bouquet * quantity);
+                }
+            }
+            _ => add(&mut expanded, *product_id, *quantity),
+        }
+    }
+
+    expanded
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProductionListQuery {
+    pub date: Option<NaiveDate>,
+}
+
+/// Bouquets to assemble for a delivery date
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ProductionBouquet {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub quantity: Decimal,
+    pub orders: i64,
+}
+
+/// Stems and materials to pick for a delivery date
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ProductionComponent {
+    pub component_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub quantity: Decimal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProductionList {
+    pub date: NaiveDate,
+    pub bouquets: Vec<ProductionBouquet>,
+    pub components: Vec<ProductionComponent>,
+}
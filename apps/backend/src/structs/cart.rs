@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -106,6 +106,10 @@ pub struct CartItemWithProduct {
     pub metadata: serde_json::Value,
     pub product_name: String,
     pub product_sku: String,
+    #[sqlx(default)]
+    pub awaiting_stock: bool, // The quantity is ordered on backorder or pre-order
+    #[sqlx(default)]
+    pub expected_ship_date: Option<NaiveDate>,
 }
 
 impl CartResponse {
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+/// Where on the site a featured product is placed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type, Default)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FeaturedType {
+    #[default]
+    Homepage,
+    Seasonal,
+    Banner,
+}
+
+/// A product placed in a featured spot, optionally only within a date window
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct FeaturedPlacement {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub featured_type: FeaturedType,
+    pub position: i32, // Lowest first
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub is_live: bool, // Inside its window right now
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Used to create a placement and to replace one. Without dates a placement is live
+/// until it is removed.
+#[derive(Deserialize, Debug)]
+pub struct FeaturedRequest {
+    pub product_id: Uuid,
+    #[serde(default)]
+    pub featured_type: FeaturedType,
+    #[serde(default)]
+    pub position: i32,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+impl FeaturedRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.position < 0 {
+            return Err("Position cannot be negative".to_string());
+        }
+        if let (Some(start_date), Some(end_date)) = (self.start_date, self.end_date)
+            && end_date <= start_date
+        {
+            return Err("End date must be after the start date".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FeaturedQuery {
+    #[serde(rename = "type")]
+    pub featured_type: Option<FeaturedType>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FeaturedAdminQuery {
+    #[serde(rename = "type")]
+    pub featured_type: Option<FeaturedType>,
+    pub include_ended: Option<bool>, // Placements whose window has passed are hidden by default
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A period in which demand is a multiple of a normal day, like the days before Mother's Day
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct SeasonalEvent {
+    pub id: Uuid,
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate, // Inclusive
+    pub multiplier: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SeasonalEvent {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        self.starts_on <= date && date <= self.ends_on
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SeasonalEventRequest {
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub multiplier: Decimal,
+}
+
+impl SeasonalEventRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Event name is required".to_string());
+        }
+        if self.ends_on < self.starts_on {
+            return Err("An event cannot end before it starts".to_string());
+        }
+        if self.multiplier <= Decimal::ZERO {
+            return Err("Multiplier must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ForecastQuery {
+    pub days: Option<i32>,
+    pub product_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    pub multiplier: Decimal,
+    pub quantity: Decimal,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DemandForecast {
+    pub baseline: Decimal, // Expected units on a day without events
+    pub total: Decimal,
+    pub days: Vec<DailyForecast>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductForecast {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    #[serde(flatten)]
+    pub forecast: DemandForecast,
+}
+
+/// Multiplier for a date. When events overlap the strongest one wins.
+pub fn seasonal_multiplier(events: &[SeasonalEvent], date: NaiveDate) -> Decimal {
+    events
+        .iter()
+        .filter(|event| event.covers(date))
+        .map(|event| event.multiplier)
+        .max()
+        .unwrap_or(Decimal::ONE)
+}
+
+/// Simple exponential smoothing: every value moves the level `alpha` of the way towards it.
+/// The first value is the starting level.
+pub fn exponential_smoothing(values: &[Decimal], alpha: Decimal) -> Decimal {
+    let mut values = values.iter();
+    let Some(first) = values.next() else {
+        return Decimal::ZERO;
+    };
+
+    values.fold(*first, |level, value| {
+        alpha * value + (Decimal::ONE - alpha) * level
+    })
+}
+
+/// Forecast `days` days from `today` out of the quantities sold per day since
+/// `history_start`. Days without sales count as zero. Event days are divided by their
+/// multiplier before smoothing, so last year's Valentine's Day does not inflate a normal week,
+/// and multiplied again for the days ahead.
+pub fn forecast_demand(
+    history: &[(NaiveDate, Decimal)],
+    history_start: NaiveDate,
+    today: NaiveDate,
+    days: u64,
+    events: &[SeasonalEvent],
+    alpha: Decimal,
+) -> DemandForecast {
+    let mut sold: HashMap<NaiveDate, Decimal> = HashMap::new();
+    for (date, quantity) in history {
+        *sold.entry(*date).or_default() += quantity;
+    }
+
+    let normalized: Vec<Decimal> = history_start
+        .iter_days()
+        .take_while(|date| *date < today)
+        .map(|date| {
+            sold.get(&date).copied().unwrap_or_default() / seasonal_multiplier(events, date)
+        })
+        .collect();
+    let baseline = exponential_smoothing(&normalized, alpha);
+
+    let days: Vec<DailyForecast> = (0..days)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .map(|date| {
+            let multiplier = seasonal_multiplier(events, date);
+            DailyForecast {
+                date,
+                multiplier,
+                quantity: (baseline * multiplier).round_dp(2),
+            }
+        })
+        .collect();
+
+    DemandForecast {
+        baseline: baseline.round_dp(2),
+        total: days.iter().map(|day| day.quantity).sum(),
+        days,
+    }
+}
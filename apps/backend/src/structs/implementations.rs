@@ -35,14 +35,17 @@ impl From<String> for OrderStatus {
 }
 
 impl Order {
-    /// Calculate total from components
+    /// Calculate total from components (subtotal + tax + shipping).
+    /// The subtotal is already after discounts; `discount_amount` only records how much
+    /// was taken off, so it is not subtracted again.
     pub fn calculate_total(&self) -> Decimal {
-        self.subtotal + self.tax_amount + self.shipping_cost - self.discount_amount
+        self.subtotal + self.tax_amount + self.shipping_cost
     }
 
     /// Build order with pricing information from the pricing service.
-    /// This method uses the calculated pricing including discounts
-    /// The payload price is tax-inclusive (price = subtotal + tax)
+    /// This method uses the calculated pricing including discounts.
+    /// The total is derived from the calculated figures (subtotal + tax + shipping)
+    /// rather than taken from the client-supplied price.
     pub fn build_order_with_pricing(
         payload: &IncomingOrder,
         pricing_result: &PricingResult,
@@ -61,8 +64,8 @@ impl Order {
             .sum::<Decimal>();
 
         let discount_amount = pricing_result.total_discount_amount;
-        let total_amount = payload.price; // This is tax-inclusive
         let shipping_cost = dec!(0.00);
+        let total_amount = subtotal_after_discount + tax_amount + shipping_cost;
 
         Order {
             id: None,
@@ -78,6 +81,11 @@ impl Order {
             total_amount,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            delivery_date: payload.delivery_date,
+            awaiting_stock: false,
+            expected_ship_date: None,
+            pickup_location_id: payload.pickup_location_id,
+            stock_location_id: None,
             status: OrderStatus::Pending,
         }
     }
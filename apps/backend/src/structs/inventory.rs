@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -24,6 +24,177 @@ pub struct InventoryReservation {
     pub quantity_to_reserve: Decimal,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ReceiveStockRequest {
+    pub quantity: Decimal,
+    pub expires_on: Option<NaiveDate>, // Last day the flowers can be sold
+    pub note: Option<String>,          // E.g. the supplier delivery reference
+    pub location_id: Option<Uuid>,     // Defaults to the first location by priority
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StockCountRequest {
+    pub counted_quantity: Decimal, // Quantity physically on hand
+    pub note: Option<String>,
+    pub location_id: Option<Uuid>, // Location that was counted, defaults to the first one
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteOffReason {
+    Damaged,
+    Wilted,
+    Lost,
+    Other,
+}
+
+impl std::fmt::Display for WriteOffReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            WriteOffReason::Damaged => "damaged",
+            WriteOffReason::Wilted => "wilted",
+            WriteOffReason::Lost => "lost",
+            WriteOffReason::Other => "other",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WriteOffRequest {
+    pub quantity: Decimal,
+    pub reason: WriteOffReason,
+    pub note: Option<String>,
+    pub location_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BackorderMode {
+    Backorder, // Sold out, more is on its way
+    Preorder,  // Not released yet, e.g. a peak-day collection
+}
+
+/// Lets a product be ordered beyond its available stock
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackorderPolicy {
+    pub mode: BackorderMode,
+    pub limit: Option<Decimal>, // Most units that may be on order without stock, None for no limit
+    pub expected_available_on: Option<NaiveDate>,
+}
+
+/// Sets the backorder policy of a product; a null policy stops taking orders without stock
+#[derive(Deserialize, Debug)]
+pub struct BackorderPolicyRequest {
+    pub policy: Option<BackorderPolicy>,
+}
+
+/// Whether a quantity can be ordered
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StockCheck {
+    InStock,
+    Backordered {
+        expected_available_on: Option<NaiveDate>, // None when the date is not known yet
+    },
+    Insufficient,
+}
+
+impl StockCheck {
+    /// Check `requested` units against the `available` quantity, which is negative when
+    /// units are already on backorder
+    pub fn check(
+        available: Decimal,
+        requested: Decimal,
+        policy: Option<&BackorderPolicy>,
+    ) -> StockCheck {
+        if requested <= available {
+            return StockCheck::InStock;
+        }
+
+        match policy {
+            Some(policy)
+                if policy
+                    .limit
+                    .is_none_or(|limit| requested - available <= limit) =>
+            {
+                StockCheck::Backordered {
+                    expected_available_on: policy.expected_available_on,
+                }
+            }
+            _ => StockCheck::Insufficient,
+        }
+    }
+
+    /// Check `requested` units to be reserved at one location. Only the stock there can be
+    /// reserved, but the backorder policy applies to the product as a whole: units are only
+    /// backordered when the product is short over all locations, and count against its limit
+    /// from what is `product_available` there.
+    pub fn check_at_location(
+        location_available: Decimal,
+        product_available: Decimal,
+        requested: Decimal,
+        policy: Option<&BackorderPolicy>,
+    ) -> StockCheck {
+        if requested <= location_available {
+            return StockCheck::InStock;
+        }
+        if requested <= product_available {
+            // In stock, but at other locations
+            return StockCheck::Insufficient;
+        }
+        Self::check(product_available, requested, policy)
+    }
+
+    /// Combine the checks of the parts of one order; it waits for the part that arrives last
+    pub fn combine(self, other: StockCheck) -> StockCheck {
+        match (self, other) {
+            (StockCheck::Insufficient, _) | (_, StockCheck::Insufficient) => {
+                StockCheck::Insufficient
+            }
+            (StockCheck::InStock, check) | (check, StockCheck::InStock) => check,
+            (
+                StockCheck::Backordered {
+                    expected_available_on: first,
+                },
+                StockCheck::Backordered {
+                    expected_available_on: second,
+                },
+            ) => StockCheck::Backordered {
+                expected_available_on: first.zip(second).map(|(first, second)| first.max(second)),
+            },
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        !matches!(self, StockCheck::Insufficient)
+    }
+
+    pub fn is_backordered(&self) -> bool {
+        matches!(self, StockCheck::Backordered { .. })
+    }
+
+    pub fn expected_available_on(&self) -> Option<NaiveDate> {
+        match self {
+            StockCheck::Backordered {
+                expected_available_on,
+            } => *expected_available_on,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReorderThresholdRequest {
+    pub reorder_threshold: Option<Decimal>, // null to use the default threshold
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LowStockQuery {
+    pub threshold: Option<Decimal>,
+}
+
 /// Never used; this is for demonstration purposes only. SQL table.
 pub struct DiscountPromotion {
     pub product_ids: Vec<Uuid>, // List of product IDs eligible for the promotion
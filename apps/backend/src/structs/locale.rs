@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+/// A language the shop's content is available in. Product names and descriptions on the
+/// product itself are in the default locale; other locales are stored as translations.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash, Type, Default)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Nl,
+    En,
+}
+
+impl Locale {
+    pub const DEFAULT: Locale = Locale::Nl;
+    pub const ALL: [Locale; 2] = [Locale::Nl, Locale::En];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::Nl => "nl",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::DEFAULT
+    }
+
+    /// Parse a language tag such as "en", "en-GB" or "nl_BE"; only the language counts
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.as_str() == language)
+    }
+
+    /// The supported locale the client prefers most in an `Accept-Language` header, e.g.
+    /// "fr-FR, en;q=0.8, nl;q=0.5" gives English. Ties go to the first listed.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut best: Option<(Locale, f32)> = None;
+
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|part| part.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((locale, quality));
+            }
+        }
+
+        best.map(|(locale, _)| locale)
+    }
+
+    /// The locale of a request: the `lang` query parameter wins over `Accept-Language`, and
+    /// anything unsupported falls back to the default locale
+    pub fn negotiate(lang: Option<&str>, accept_language: Option<&str>) -> Locale {
+        lang.and_then(Self::from_tag)
+            .or_else(|| accept_language.and_then(Self::from_accept_language))
+            .unwrap_or(Self::DEFAULT)
+    }
+}
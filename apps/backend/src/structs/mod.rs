@@ -1,14 +1,33 @@
+pub mod abandoned_cart;
+pub mod bill_of_materials;
 pub mod cart;
 pub mod contact;
 pub mod customer;
 pub mod enums;
+pub mod featured;
+pub mod forecast;
 pub mod implementations;
 pub mod inventory;
 pub mod jwt;
+pub mod locale;
+pub mod notification;
 pub mod order;
+pub mod price_history;
 pub mod product;
+pub mod product_image;
 pub mod promotion;
+pub mod purchase_order;
+pub mod quote;
+pub mod recommendation;
+pub mod reconciliation;
+pub mod review;
+pub mod stock_batch;
+pub mod stock_location;
+pub mod stock_movement;
+pub mod stock_notification;
+pub mod taxonomy;
 pub mod user;
+pub mod wishlist;
 
 pub use cart::{
     AddCartItemRequest, Cart, CartItem, CartItemWithProduct, CartResponse, CartWithItems,
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    LowStock,
+}
+
+/// An entry in the admin notification feed
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct AdminNotification {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub message: String,
+    pub product_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub read_by: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NotificationQuery {
+    pub unread_only: Option<bool>,
+    pub limit: Option<i64>,
+}
@@ -1,6 +1,10 @@
+use crate::structs::enums::{Colors, Size};
+use crate::structs::product::Product;
 use crate::structs::{Address, OrderStatus};
-use chrono::{DateTime, Utc};
+use crate::utils::tax::Tax;
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -14,6 +18,8 @@ pub struct IncomingOrder {
     pub shipping_address: Address,
     pub billing_address: Address,
     pub notes: Option<String>,
+    pub delivery_date: Option<NaiveDate>,
+    pub pickup_location_id: Option<Uuid>, // Collect at this pickup point instead of delivery
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -45,6 +51,11 @@ pub struct Order {
     pub billing_address: Address,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub delivery_date: Option<NaiveDate>, // Requested delivery day, None for as soon as possible
+    pub awaiting_stock: bool,             // Contains backordered or pre-ordered units
+    pub expected_ship_date: Option<NaiveDate>,
+    pub pickup_location_id: Option<Uuid>,
+    pub stock_location_id: Option<Uuid>, // Location the order is reserved at
 }
 
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -56,12 +67,29 @@ pub struct OrderLine {
     pub quantity: Decimal,
     pub unit_price: Decimal, // Price per unit at time of order
     pub discount_amount: Decimal,
+    // Snapshot of the product at order time, so later product changes don't alter the order.
+    // Lines placed before snapshots were recorded fall back to the current product.
+    pub product_name: Option<String>,
+    pub product_sku: Option<String>,
+    pub product_size: Option<Size>,
+    pub product_colors: Option<Vec<Colors>>,
+    pub product_image_url: Option<String>,
+    pub vat_rate: Option<Decimal>,
+    pub applied_promotion_id: Option<Uuid>,
 }
 
 impl OrderLine {
-    /// Calculate line total (quantity * unit_price - discount_amount)
+    /// Calculate line total after discount (quantity * unit_price).
+    /// `unit_price` is already the discounted price; `discount_amount` records how much
+    /// was taken off the whole line, so it must not be subtracted again.
     pub fn calculate_line_total(&self) -> Decimal {
-        (self.quantity * self.unit_price) - self.discount_amount
+        self.quantity * self.unit_price
+    }
+
+    /// Calculate the VAT for this line, rounded per unit like at order time
+    pub fn calculate_line_tax(&self) -> Decimal {
+        let vat_rate = self.vat_rate.unwrap_or(Tax::RATE);
+        (self.unit_price * vat_rate).round_dp(2) * self.quantity
     }
 
     /// Create a new OrderLine
@@ -80,8 +108,39 @@ impl OrderLine {
             quantity,
             unit_price,
             discount_amount,
+            product_name: None,
+            product_sku: None,
+            product_size: None,
+            product_colors: None,
+            product_image_url: None,
+            vat_rate: None,
+            applied_promotion_id: None,
         }
     }
+
+    /// Record the product details and pricing context as they are at order time
+    pub fn with_product_snapshot(
+        mut self,
+        product: &Product,
+        vat_rate: Decimal,
+        applied_promotion_id: Option<Uuid>,
+    ) -> Self {
+        let primary_image = product.images.as_ref().and_then(|images| {
+            images
+                .iter()
+                .find(|image| image.is_primary)
+                .or_else(|| images.first())
+        });
+
+        self.product_name = Some(product.name.clone());
+        self.product_sku = Some(product.sku.clone());
+        self.product_size = Some(product.size.clone());
+        self.product_colors = Some(product.colors.clone());
+        self.product_image_url = primary_image.map(|image| image.url.clone());
+        self.vat_rate = Some(vat_rate);
+        self.applied_promotion_id = applied_promotion_id;
+        self
+    }
 }
 
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
@@ -95,18 +154,66 @@ impl OrderWithLines {
         Self { order, order_lines }
     }
 
-    /// Calculate total from order lines and verify it matches the order total
+    /// Largest difference between a recorded and a recomputed figure that is accepted
+    pub const TOTALS_TOLERANCE: Decimal = dec!(0.01);
+
+    /// Recompute the order figures from the order lines and verify they match the order
     pub fn validate_totals(&self) -> bool {
-        let calculated_total: Decimal = self
-            .order_lines
+        self.totals_mismatches().is_empty()
+    }
+
+    /// Compare the recorded subtotal, tax, discount and total with the figures recomputed
+    /// from the order lines. Returns every figure that is off by more than one cent.
+    pub fn totals_mismatches(&self) -> Vec<TotalsMismatch> {
+        let checks = [
+            ("subtotal", self.order.subtotal, self.calculated_subtotal()),
+            ("tax_amount", self.order.tax_amount, self.calculated_tax()),
+            (
+                "discount_amount",
+                self.order.discount_amount,
+                self.calculated_discount(),
+            ),
+            (
+                "total_amount",
+                self.order.total_amount,
+                self.calculated_total(),
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(_, recorded, calculated)| {
+                (*recorded - *calculated).abs() > Self::TOTALS_TOLERANCE
+            })
+            .map(|(field, recorded, calculated)| TotalsMismatch {
+                field: field.to_string(),
+                recorded,
+                calculated,
+                difference: recorded - calculated,
+            })
+            .collect()
+    }
+
+    /// Get the VAT of all order lines
+    pub fn calculated_tax(&self) -> Decimal {
+        self.order_lines
             .iter()
-            .map(|line| line.calculate_line_total())
-            .sum();
+            .map(|line| line.calculate_line_tax())
+            .sum()
+    }
 
-        // Add tax and shipping, subtract overall discounts
-        let expected_total = calculated_total + self.order.tax_amount + self.order.shipping_cost;
+    /// Get the discount given on all order lines
+    pub fn calculated_discount(&self) -> Decimal {
+        self.order_lines
+            .iter()
+            .map(|line| line.discount_amount)
+            .sum()
+    }
 
-        expected_total == self.order.total_amount
+    /// Get the order total from the order lines (subtotal + tax + shipping). Like
+    /// `Order::calculate_total`, the line discounts are already in the subtotal.
+    pub fn calculated_total(&self) -> Decimal {
+        self.calculated_subtotal() + self.calculated_tax() + self.order.shipping_cost
     }
 
     /// Get the total quantity of items in this order
@@ -114,7 +221,7 @@ impl OrderWithLines {
         self.order_lines.iter().map(|line| line.quantity).sum()
     }
 
-    /// Get the subtotal from order lines (before tax and shipping)
+    /// Get the subtotal from order lines (after discounts, before tax and shipping)
     pub fn calculated_subtotal(&self) -> Decimal {
         self.order_lines
             .iter()
@@ -122,3 +229,12 @@ impl OrderWithLines {
             .sum()
     }
 }
+
+/// A recorded order figure that differs from the figure recomputed from its lines
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TotalsMismatch {
+    pub field: String,
+    pub recorded: Decimal,
+    pub calculated: Decimal,
+    pub difference: Decimal, // recorded - calculated
+}
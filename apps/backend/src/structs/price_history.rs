@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+use crate::structs::product::validate_price;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PriceChangeSource {
+    Created, // The price the product was added with
+    Manual,
+    Scheduled,
+}
+
+/// A change of a product's list price
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct PriceChange {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub old_price: Option<Decimal>, // None when the product was created
+    pub new_price: Decimal,
+    pub source: PriceChangeSource,
+    pub changed_by: Option<Uuid>, // The admin who made or scheduled the change
+    pub changed_by_email: Option<String>,
+    pub scheduled_change_id: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledPriceStatus {
+    Pending,
+    Applied,
+    Cancelled,
+}
+
+/// A list price change that is applied at `effective_at`
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ScheduledPriceChange {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub new_price: Decimal,
+    pub effective_at: DateTime<Utc>,
+    pub status: ScheduledPriceStatus,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub applied_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SchedulePriceChangeRequest {
+    pub new_price: Decimal,
+    pub effective_at: DateTime<Utc>,
+}
+
+impl SchedulePriceChangeRequest {
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), String> {
+        validate_price(self.new_price)?;
+        if self.effective_at <= now {
+            return Err("A price change can only be scheduled in the future".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The price history of a product with its upcoming changes
+#[derive(Serialize, Debug, Clone)]
+pub struct PriceHistory {
+    pub product_id: Uuid,
+    pub current_price: Decimal,
+    pub reference_price: Decimal, // See `ReferencePrice`
+    pub reference_days: i64,
+    pub changes: Vec<PriceChange>, // Newest first
+    pub scheduled: Vec<ScheduledPriceChange>,
+}
+
+/// The price to show as the "was" price next to a promotion: the lowest list price of the
+/// product over the last `reference_days` days
+#[derive(Serialize, Debug, Clone)]
+pub struct ReferencePrice {
+    pub product_id: Uuid,
+    pub current_price: Decimal,
+    pub reference_price: Decimal,
+    pub reference_days: i64,
+}
+
+/// The lowest list price in effect at any moment since `since`. The price before a change
+/// within the period was in effect up to that change, so it counts too.
+pub fn lowest_price_since(
+    current_price: Decimal,
+    changes: &[PriceChange],
+    since: DateTime<Utc>,
+) -> Decimal {
+    changes
+        .iter()
+        .filter(|change| change.changed_at >= since)
+        .flat_map(|change| change.old_price.into_iter().chain([change.new_price]))
+        .fold(current_price, Decimal::min)
+}
+
+/// The "was" price to show with a promotional price: the reference price, but never below
+/// the promotional price, so a promotion never shows as a negative discount
+pub fn was_price(reference_price: Decimal, discounted_price: Decimal) -> Decimal {
+    reference_price.max(discounted_price)
+}
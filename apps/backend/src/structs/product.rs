@@ -2,15 +2,20 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use sqlx::types::Json;
 use uuid::Uuid;
 
 use crate::structs::enums::{Colors, ProductType, Size};
+use crate::structs::locale::Locale;
+use crate::structs::product_image::ImageRendition;
+use crate::utils::tax::Tax;
 
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
 pub struct Product {
     pub id: Uuid,
     pub name: String,
-    pub sku: String, // Stock Keeping Unit for better inventory tracking
+    pub slug: String, // Follows the name; earlier slugs redirect here
+    pub sku: String,  // Stock Keeping Unit for better inventory tracking
     pub price: Decimal,
     pub discounted_price: Decimal,
     pub tax: Decimal,
@@ -24,12 +29,213 @@ pub struct Product {
     pub product_type: ProductType,
     pub stock: Decimal,
     pub images: Option<Vec<ProductImage>>,
+    pub parent_id: Option<Uuid>, // Set on variants, e.g. the small version of a bouquet
+    pub average_rating: Option<Decimal>, // Over approved reviews of the product family
+    pub review_count: i64,
+    pub variants: Option<Vec<ProductVariant>>, // Filled in on parents in listings
+}
+
+/// A variant as listed under its parent product
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct ProductVariant {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub parent_id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub sku: String,
+    pub price: Decimal,
+    pub discounted_price: Decimal,
+    pub size: Size,
+    pub colors: Vec<Colors>,
+    pub stock: Decimal,
 }
 
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
 pub struct ProductImage {
+    pub id: Uuid,
     pub product_id: Uuid,
-    pub url: String,
+    pub url: String, // The image as uploaded, or an external URL
     pub alt_text: Option<String>,
     pub is_primary: bool,
+    pub sort_order: i32,                       // Lowest first
+    pub renditions: Json<Vec<ImageRendition>>, // Empty for external images
+}
+
+/// Where a slug leads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlugLookup {
+    Current(Uuid),
+    Moved(String), // An earlier slug; this is the product's slug now
+}
+
+const MAX_NAME_LENGTH: usize = 200;
+const MAX_SKU_LENGTH: usize = 64;
+const MAX_DESCRIPTION_LENGTH: usize = 5000;
+const MAX_PRICE: Decimal = Decimal::from_parts(1000000, 0, 0, false, 2); // €10,000.00
+
+#[derive(Deserialize, Debug)]
+pub struct CreateProductRequest {
+    pub name: String,
+    pub sku: String,
+    pub price: Decimal, // Including VAT
+    pub description: String,
+    pub size: Size,
+    pub colors: Vec<Colors>,
+    pub product_type: ProductType,
+    pub initial_stock: Option<Decimal>, // Booked at the default stock location
+    pub parent_id: Option<Uuid>,        // Create the product as a variant of this one
+}
+
+impl CreateProductRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name(&self.name)?;
+        normalize_sku(&self.sku)?;
+        validate_price(self.price)?;
+        validate_description(&self.description)?;
+        validate_colors(&self.colors)?;
+        if self
+            .initial_stock
+            .is_some_and(|stock| stock < Decimal::ZERO)
+        {
+            return Err("Initial stock cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Fields left out keep their current value
+#[derive(Deserialize, Debug)]
+pub struct UpdateProductRequest {
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    pub price: Option<Decimal>,
+    pub description: Option<String>,
+    pub size: Option<Size>,
+    pub colors: Option<Vec<Colors>>,
+    pub product_type: Option<ProductType>,
+}
+
+impl UpdateProductRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.name {
+            validate_name(name)?;
+        }
+        if let Some(sku) = &self.sku {
+            normalize_sku(sku)?;
+        }
+        if let Some(price) = self.price {
+            validate_price(price)?;
+        }
+        if let Some(description) = &self.description {
+            validate_description(description)?;
+        }
+        if let Some(colors) = &self.colors {
+            validate_colors(colors)?;
+        }
+        Ok(())
+    }
+}
+
+/// The name and description of a product in a locale other than the default
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ProductTranslation {
+    pub product_id: Uuid,
+    pub locale: Locale,
+    pub name: String,
+    pub description: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Used to add or replace the translation of a product
+#[derive(Deserialize, Debug)]
+pub struct ProductTranslationRequest {
+    pub name: String,
+    pub description: String,
+}
+
+impl ProductTranslationRequest {
+    pub fn validate(&self, locale: Locale) -> Result<(), String> {
+        if locale.is_default() {
+            return Err(format!(
+                "Content in '{}' is the product's own name and description; update the product instead",
+                locale.as_str()
+            ));
+        }
+        validate_name(&self.name)?;
+        validate_description(&self.description)
+    }
+}
+
+/// SKUs are stored trimmed and in upper case, e.g. "BQ-SUMMER-M"
+pub fn normalize_sku(sku: &str) -> Result<String, String> {
+    let sku = sku.trim().to_uppercase();
+    if sku.is_empty() || sku.len() > MAX_SKU_LENGTH {
+        return Err(format!(
+            "SKU must be between 1 and {} characters",
+            MAX_SKU_LENGTH
+        ));
+    }
+    if !sku
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("SKU can only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(sku)
+}
+
+/// Split a price including VAT into the subtotal and tax stored on the product, so that
+/// subtotal + tax is always the price
+pub fn price_components(price: Decimal) -> (Decimal, Decimal) {
+    let subtotal = Tax::total_without_tax(price);
+    (subtotal, price - subtotal)
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let length = name.trim().chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Product name must be between 1 and {} characters",
+            MAX_NAME_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_price(price: Decimal) -> Result<(), String> {
+    if price <= Decimal::ZERO {
+        return Err("Price must be greater than 0".to_string());
+    }
+    if price > MAX_PRICE {
+        return Err(format!("Price cannot be more than {}", MAX_PRICE));
+    }
+    if price.normalize().scale() > 2 {
+        return Err("Price can have maximum 2 decimal places".to_string());
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), String> {
+    if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return Err(format!(
+            "Description cannot be longer than {} characters",
+            MAX_DESCRIPTION_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn validate_colors(colors: &[Colors]) -> Result<(), String> {
+    if colors.is_empty() {
+        return Err("At least one colour is required".to_string());
+    }
+    if colors
+        .iter()
+        .enumerate()
+        .any(|(i, color)| colors[..i].contains(color))
+    {
+        return Err("Colours cannot be listed twice".to_string());
+    }
+    Ok(())
 }
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MAX_ALT_TEXT_LENGTH: usize = 250;
+
+/// The sizes every uploaded image is stored in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSize {
+    Original,
+    Medium,
+    Thumbnail,
+}
+
+impl ImageSize {
+    pub const ALL: [ImageSize; 3] = [ImageSize::Original, ImageSize::Medium, ImageSize::Thumbnail];
+
+    /// Widest the stored image may be; smaller uploads are never enlarged
+    pub fn max_dimension(self) -> Option<u32> {
+        match self {
+            ImageSize::Original => None,
+            ImageSize::Medium => Some(800),
+            ImageSize::Thumbnail => Some(200),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageSize::Original => "original",
+            ImageSize::Medium => "medium",
+            ImageSize::Thumbnail => "thumbnail",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// One stored version of an image
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageRendition {
+    pub size: ImageSize,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub url: String,
+}
+
+/// Change the alt text of an image, or make it the primary one
+#[derive(Deserialize, Debug)]
+pub struct UpdateImageRequest {
+    pub alt_text: Option<String>,
+    pub is_primary: Option<bool>,
+}
+
+impl UpdateImageRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(alt_text) = &self.alt_text {
+            validate_alt_text(alt_text)?;
+        }
+        if self.is_primary == Some(false) {
+            return Err(
+                "A product always has a primary image; make another image primary instead"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The images of a product in their new order; must list every image exactly once
+#[derive(Deserialize, Debug)]
+pub struct ReorderImagesRequest {
+    pub image_ids: Vec<Uuid>,
+}
+
+pub fn validate_alt_text(alt_text: &str) -> Result<(), String> {
+    if alt_text.chars().count() > MAX_ALT_TEXT_LENGTH {
+        return Err(format!(
+            "Alt text cannot be longer than {} characters",
+            MAX_ALT_TEXT_LENGTH
+        ));
+    }
+    Ok(())
+}
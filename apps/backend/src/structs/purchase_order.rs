@@ -0,0 +1,263 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// An auction house or wholesaler we buy flowers and materials from
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct Supplier {
+    pub id: Uuid,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateSupplierRequest {
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Fields left out keep their current value
+#[derive(Deserialize, Debug)]
+pub struct UpdateSupplierRequest {
+    pub name: Option<String>,
+    pub contact_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SupplierQuery {
+    pub include_inactive: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PurchaseOrderStatus {
+    Draft,
+    Sent,
+    PartiallyReceived,
+    Received,
+}
+
+impl std::fmt::Display for PurchaseOrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            PurchaseOrderStatus::Draft => "draft",
+            PurchaseOrderStatus::Sent => "sent",
+            PurchaseOrderStatus::PartiallyReceived => "partially received",
+            PurchaseOrderStatus::Received => "received",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct PurchaseOrder {
+    pub id: Uuid,
+    pub reference: String, // E.g. PO-20270508-1A2B3C4D
+    pub supplier_id: Uuid,
+    pub supplier_name: String,
+    pub status: PurchaseOrderStatus,
+    pub expected_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub received_at: Option<DateTime<Utc>>,
+}
+
+impl PurchaseOrder {
+    /// A reference with a random suffix that is long enough to not collide within a day
+    pub fn generate_reference() -> String {
+        let suffix = Uuid::new_v4().simple().to_string();
+        format!(
+            "PO-{}-{}",
+            Utc::now().format("%Y%m%d"),
+            suffix[..8].to_uppercase()
+        )
+    }
+}
+
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct PurchaseOrderLine {
+    pub id: Uuid,
+    pub purchase_order_id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub product_sku: String,
+    pub quantity: Decimal,
+    pub quantity_received: Decimal,
+    pub cost_price: Decimal, // Per unit, excluding VAT
+}
+
+impl PurchaseOrderLine {
+    pub fn quantity_outstanding(&self) -> Decimal {
+        self.quantity - self.quantity_received
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PurchaseOrderWithLines {
+    #[serde(flatten)]
+    pub purchase_order: PurchaseOrder,
+    pub lines: Vec<PurchaseOrderLine>,
+    pub total_cost: Decimal,
+}
+
+impl PurchaseOrderWithLines {
+    pub fn new(purchase_order: PurchaseOrder, lines: Vec<PurchaseOrderLine>) -> Self {
+        let total_cost = lines
+            .iter()
+            .map(|line| line.quantity * line.cost_price)
+            .sum();
+        Self {
+            purchase_order,
+            lines,
+            total_cost,
+        }
+    }
+
+    /// Status once the received quantities are booked on the lines
+    pub fn status_after_receipt(lines: &[PurchaseOrderLine]) -> PurchaseOrderStatus {
+        if lines
+            .iter()
+            .all(|line| line.quantity_outstanding() <= Decimal::ZERO)
+        {
+            PurchaseOrderStatus::Received
+        } else {
+            PurchaseOrderStatus::PartiallyReceived
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PurchaseOrderLineRequest {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub cost_price: Decimal,
+}
+
+/// Creates a draft purchase order, or replaces the contents of a draft
+#[derive(Deserialize, Debug)]
+pub struct PurchaseOrderRequest {
+    pub supplier_id: Uuid,
+    pub expected_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<PurchaseOrderLineRequest>,
+}
+
+impl PurchaseOrderRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lines.is_empty() {
+            return Err("A purchase order needs at least one line".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        for line in &self.lines {
+            if line.quantity <= Decimal::ZERO {
+                return Err(format!(
+                    "Quantity of product {} must be greater than zero",
+                    line.product_id
+                ));
+            }
+            if line.cost_price < Decimal::ZERO {
+                return Err(format!(
+                    "Cost price of product {} cannot be negative",
+                    line.product_id
+                ));
+            }
+            if !seen.insert(line.product_id) {
+                return Err(format!(
+                    "Product {} is listed more than once",
+                    line.product_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReceiveLineRequest {
+    pub product_id: Uuid,
+    pub quantity: Decimal,
+    pub expires_on: Option<NaiveDate>, // Last day the flowers can be sold
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReceivePurchaseOrderRequest {
+    pub lines: Vec<ReceiveLineRequest>,
+    pub note: Option<String>,
+    pub location_id: Option<Uuid>, // Where the delivery is stored, defaults to the first location
+}
+
+impl ReceivePurchaseOrderRequest {
+    /// Total received per product. A product can be on several lines, e.g. when part of
+    /// the delivery has a different expiry date.
+    pub fn quantity_per_product(&self) -> HashMap<Uuid, Decimal> {
+        let mut quantities: HashMap<Uuid, Decimal> = HashMap::new();
+        for line in &self.lines {
+            *quantities.entry(line.product_id).or_default() += line.quantity;
+        }
+        quantities
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PurchaseOrderQuery {
+    pub status: Option<PurchaseOrderStatus>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SuggestedPurchaseQuery {
+    pub days: Option<i32>,
+}
+
+/// A product worth buying, with the numbers behind the suggestion
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct SuggestedPurchase {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub available_quantity: Decimal, // Negative when units are on backorder
+    pub on_order: Decimal,           // Outstanding on sent purchase orders
+    pub upcoming_demand: Decimal,    // Ordered for delivery within the window
+    #[sqlx(default)]
+    pub forecast_demand: Decimal, // Expected to be ordered for delivery within the window
+    pub reorder_threshold: Decimal,
+    #[sqlx(default)]
+    pub suggested_quantity: Decimal,
+    pub last_supplier_id: Option<Uuid>,
+    pub last_cost_price: Option<Decimal>,
+}
+
+/// Units to buy to stay above the reorder threshold through the window. Reserved units,
+/// including those for upcoming deliveries, already lower the available quantity, so only the
+/// part of the forecast that has not been ordered yet is added.
+pub fn suggested_quantity(
+    available_quantity: Decimal,
+    on_order: Decimal,
+    reorder_threshold: Decimal,
+    upcoming_demand: Decimal,
+    forecast_demand: Decimal,
+) -> Decimal {
+    let expected_orders = (forecast_demand - upcoming_demand).max(Decimal::ZERO);
+    (reorder_threshold + expected_orders - available_quantity - on_order).max(Decimal::ZERO)
+}
@@ -0,0 +1,63 @@
+use crate::services::{PricingResult, ProductPriceInfo};
+use crate::structs::order::OrderContent;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A stored price quote. The product prices are kept exactly as they were calculated,
+/// including the promotion that was applied to each product.
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct PriceQuote {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub products: sqlx::types::Json<Vec<ProductPriceInfo>>,
+    pub subtotal_before_discount: Decimal,
+    pub total_discount_amount: Decimal,
+    pub final_total: Decimal,
+    pub total_with_tax: Decimal, // The `price` an order must send to use this quote
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub order_id: Option<Uuid>,
+}
+
+impl PriceQuote {
+    /// Check that an order contains exactly the products and quantities that were quoted
+    pub fn covers_items(&self, items: &[OrderContent]) -> bool {
+        let mut quoted: HashMap<Uuid, i64> = HashMap::new();
+        for product in self.products.iter() {
+            *quoted.entry(product.id).or_insert(0) += i64::from(product.quantity);
+        }
+
+        let mut ordered: HashMap<Uuid, i64> = HashMap::new();
+        for entry in items.iter().flat_map(|content| content.product.iter()) {
+            *ordered.entry(entry.product_id).or_insert(0) += i64::from(entry.quantity);
+        }
+
+        quoted == ordered
+    }
+}
+
+/// Claims of the signed quote ID handed to the client
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuoteClaims {
+    pub quote_id: Uuid,
+    pub user_id: Uuid,
+    pub exp: usize, // Expiration time
+    pub iat: usize, // Issued at
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QuoteRequest {
+    pub items: Vec<OrderContent>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct QuoteResponse {
+    pub quote_id: String, // Signed; send back as `quote_id` when placing the order
+    pub expires_at: DateTime<Utc>,
+    pub total_with_tax: Decimal,
+    pub pricing: PricingResult,
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+const DEFAULT_RECOMMENDATIONS: i64 = 4;
+const MAX_RECOMMENDATIONS: i64 = 20;
+const MAX_CART_PRODUCTS: usize = 100;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct RecommendationQuery {
+    pub limit: Option<i64>,
+}
+
+impl RecommendationQuery {
+    /// How many products to recommend, between 1 and 20
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_RECOMMENDATIONS)
+            .clamp(1, MAX_RECOMMENDATIONS)
+    }
+}
+
+/// The products in a guest's cart, which lives in the browser
+#[derive(Deserialize, Debug)]
+pub struct CartRecommendationsRequest {
+    pub product_ids: Vec<Uuid>,
+}
+
+impl CartRecommendationsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.product_ids.len() > MAX_CART_PRODUCTS {
+            return Err(format!(
+                "A cart can have at most {} products",
+                MAX_CART_PRODUCTS
+            ));
+        }
+        Ok(())
+    }
+}
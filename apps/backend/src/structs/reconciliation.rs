@@ -0,0 +1,36 @@
+use crate::structs::order::TotalsMismatch;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An order whose recorded totals do not match its order lines
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct OrderTotalsFlag {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub order_number: String,
+    pub mismatches: sqlx::types::Json<Vec<TotalsMismatch>>,
+    pub detected_at: DateTime<Utc>,
+    pub last_checked_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub reviewed_by: Option<Uuid>,
+    pub review_note: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReconciliationReport {
+    pub orders_checked: usize,
+    pub orders_flagged: usize,
+    pub flags: Vec<OrderTotalsFlag>, // Flags that still need a review
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReconciliationQuery {
+    pub include_reviewed: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewFlagRequest {
+    pub note: Option<String>,
+}
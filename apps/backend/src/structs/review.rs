@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+const MAX_TITLE_LENGTH: usize = 120;
+const MAX_BODY_LENGTH: usize = 4000;
+const MAX_NOTE_LENGTH: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending, // Waiting for moderation, not shown in the shop
+    Approved,
+    Rejected,
+}
+
+/// A review as the admin sees it
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct Review {
+    pub id: Uuid,
+    pub product_id: Uuid, // The parent when a variant was bought
+    pub user_id: Uuid,
+    pub order_id: Option<Uuid>, // The delivered order the purchase was verified with
+    pub rating: i16,
+    pub title: Option<String>,
+    pub body: String,
+    pub status: ReviewStatus,
+    pub moderation_note: Option<String>,
+    pub moderated_by: Option<Uuid>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A review in the moderation queue, with what the moderator needs to judge it
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ModerationQueueItem {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub review: Review,
+    pub product_name: String,
+    pub author_email: String,
+}
+
+#[derive(FromRow, Debug)]
+pub struct PublicReviewRow {
+    pub id: Uuid,
+    pub rating: i16,
+    pub title: Option<String>,
+    pub body: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub order_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A review as shown in the shop
+#[derive(Serialize, Debug, Clone)]
+pub struct PublicReview {
+    pub id: Uuid,
+    pub rating: i16,
+    pub title: Option<String>,
+    pub body: String,
+    pub author: String,
+    pub is_verified_purchase: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PublicReviewRow> for PublicReview {
+    fn from(row: PublicReviewRow) -> Self {
+        PublicReview {
+            id: row.id,
+            rating: row.rating,
+            title: row.title,
+            body: row.body,
+            author: author_display_name(&row.first_name, &row.last_name),
+            is_verified_purchase: row.order_id.is_some(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductReviews {
+    pub average_rating: Option<Decimal>,
+    pub review_count: i64,
+    pub reviews: Vec<PublicReview>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitReviewRequest {
+    pub rating: i16,
+    pub title: Option<String>,
+    pub body: String,
+}
+
+impl SubmitReviewRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=5).contains(&self.rating) {
+            return Err("Rating must be between 1 and 5".to_string());
+        }
+        if self
+            .title
+            .as_ref()
+            .is_some_and(|title| title.trim().chars().count() > MAX_TITLE_LENGTH)
+        {
+            return Err(format!(
+                "Title cannot be longer than {} characters",
+                MAX_TITLE_LENGTH
+            ));
+        }
+        let body_length = self.body.trim().chars().count();
+        if body_length == 0 || body_length > MAX_BODY_LENGTH {
+            return Err(format!(
+                "Review text must be between 1 and {} characters",
+                MAX_BODY_LENGTH
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Approve or reject a review, optionally noting why
+#[derive(Deserialize, Debug)]
+pub struct ModerateReviewRequest {
+    pub status: ReviewStatus,
+    pub note: Option<String>,
+}
+
+impl ModerateReviewRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.status == ReviewStatus::Pending {
+            return Err("A review can only be approved or rejected".to_string());
+        }
+        if self
+            .note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+        {
+            return Err(format!(
+                "Note cannot be longer than {} characters",
+                MAX_NOTE_LENGTH
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewQueueQuery {
+    pub status: Option<ReviewStatus>, // Pending when not given
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProductReviewsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// First name and the initial of the last name, e.g. "Anna J."
+pub fn author_display_name(first_name: &str, last_name: &str) -> String {
+    let first_name = first_name.trim();
+    if first_name.is_empty() {
+        return "Customer".to_string();
+    }
+    match last_name.trim().chars().next() {
+        Some(initial) => format!("{} {}.", first_name, initial.to_uppercase()),
+        None => first_name.to_string(),
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A delivery of a product, tracked separately because fresh flowers expire
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct StockBatch {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub quantity_received: Decimal,
+    pub quantity_remaining: Decimal, // Still on hand, including reserved units
+    pub quantity_reserved: Decimal,
+    pub received_at: DateTime<Utc>,
+    pub expires_on: Option<NaiveDate>, // None for stock that does not perish
+    pub written_off_at: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+}
+
+impl StockBatch {
+    pub fn available_quantity(&self) -> Decimal {
+        self.quantity_remaining - self.quantity_reserved
+    }
+
+    /// A batch can still be sold on its expiry date
+    pub fn is_expired_on(&self, date: NaiveDate) -> bool {
+        self.expires_on.is_some_and(|expires_on| expires_on < date)
+    }
+}
+
+/// Which part of a batch a stock change takes from
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BatchQuantity {
+    Available,
+    Reserved,
+}
+
+/// Quantity taken from a single batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchAllocation {
+    pub batch_id: Uuid,
+    pub quantity: Decimal,
+}
+
+/// Spread `quantity` over `batches` in the order given, taking from the earliest batch first.
+/// Batches expired on `fresh_on` are skipped. Returns the missing quantity when the batches
+/// do not hold enough.
+pub fn allocate_batches<'a>(
+    batches: impl IntoIterator<Item = &'a StockBatch>,
+    quantity: Decimal,
+    take_from: BatchQuantity,
+    fresh_on: Option<NaiveDate>,
+) -> Result<Vec<BatchAllocation>, Decimal> {
+    let mut allocations = Vec::new();
+    let mut remaining = quantity;
+
+    for batch in batches {
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        if fresh_on.is_some_and(|date| batch.is_expired_on(date)) {
+            continue;
+        }
+
+        let in_batch = match take_from {
+            BatchQuantity::Available => batch.available_quantity(),
+            BatchQuantity::Reserved => batch.quantity_reserved,
+        };
+        let taken = in_batch.min(remaining);
+        if taken > Decimal::ZERO {
+            allocations.push(BatchAllocation {
+                batch_id: batch.id,
+                quantity: taken,
+            });
+            remaining -= taken;
+        }
+    }
+
+    if remaining > Decimal::ZERO {
+        return Err(remaining);
+    }
+    Ok(allocations)
+}
+
+/// A batch that expires soon, for discounting or featuring the product
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ExpiringBatch {
+    pub batch_id: Uuid,
+    pub product_id: Uuid,
+    pub location_id: Option<Uuid>,
+    pub location_name: Option<String>,
+    pub name: String,
+    pub sku: String,
+    pub quantity_available: Decimal,
+    pub quantity_reserved: Decimal,
+    pub received_at: DateTime<Utc>,
+    pub expires_on: NaiveDate,
+    pub days_left: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExpiringBatchQuery {
+    pub days: Option<i32>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ExpiredWriteOffReport {
+    pub batches_written_off: usize,
+    pub quantity_written_off: Decimal,
+}
@@ -0,0 +1,163 @@
+use crate::structs::inventory::InventoryReservation;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A place where stock is kept, like the shop or the cold room
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct StockLocation {
+    pub id: Uuid,
+    pub name: String,
+    pub is_pickup_point: bool,
+    pub delivery_postal_prefixes: Vec<String>, // Delivery zone, e.g. "10" or "1011"
+    pub priority: i32,                         // Lowest first when no zone matches
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StockLocation {
+    /// Length of the longest delivery prefix that matches the postal code, if any
+    pub fn zone_match(&self, postal_code: &str) -> Option<usize> {
+        let postal_code = normalize_postal_code(postal_code);
+        self.delivery_postal_prefixes
+            .iter()
+            .filter(|prefix| postal_code.starts_with(prefix.as_str()))
+            .map(|prefix| prefix.len())
+            .max()
+    }
+}
+
+/// Pickup points as shown to customers
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct PickupPoint {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateStockLocationRequest {
+    pub name: String,
+    #[serde(default)]
+    pub is_pickup_point: bool,
+    #[serde(default)]
+    pub delivery_postal_prefixes: Vec<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Fields left out keep their current value
+#[derive(Deserialize, Debug)]
+pub struct UpdateStockLocationRequest {
+    pub name: Option<String>,
+    pub is_pickup_point: Option<bool>,
+    pub delivery_postal_prefixes: Option<Vec<String>>,
+    pub priority: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StockLocationQuery {
+    pub include_inactive: Option<bool>,
+}
+
+/// Check and normalize delivery prefixes: the start of a Dutch postal code, like "10",
+/// "1011" or "1011AB"
+pub fn normalize_postal_prefixes(prefixes: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for prefix in prefixes {
+        let prefix = normalize_postal_code(prefix);
+        let is_valid = !prefix.is_empty()
+            && prefix.len() <= 6
+            && prefix.chars().enumerate().all(|(i, c)| {
+                if i < 4 {
+                    c.is_ascii_digit()
+                } else {
+                    c.is_ascii_uppercase()
+                }
+            });
+        if !is_valid {
+            return Err(format!("Invalid postal code prefix: {}", prefix));
+        }
+        if !normalized.contains(&prefix) {
+            normalized.push(prefix);
+        }
+    }
+    Ok(normalized)
+}
+
+fn normalize_postal_code(postal_code: &str) -> String {
+    postal_code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// The location an order is taken from. A pickup order uses its pickup point; a delivery
+/// uses the location whose zone matches the postal code best, or the first location by
+/// priority. `locations` must be active and sorted by priority.
+pub fn preferred_location(
+    locations: &[StockLocation],
+    pickup_location_id: Option<Uuid>,
+    postal_code: Option<&str>,
+) -> Result<Uuid, String> {
+    if let Some(pickup_location_id) = pickup_location_id {
+        return locations
+            .iter()
+            .find(|location| location.id == pickup_location_id && location.is_pickup_point)
+            .map(|location| location.id)
+            .ok_or_else(|| "The chosen pickup point is not available".to_string());
+    }
+
+    // Longest prefix wins, then priority
+    let mut in_zone: Option<(usize, Uuid)> = None;
+    if let Some(postal_code) = postal_code {
+        for location in locations {
+            if let Some(len) = location.zone_match(postal_code)
+                && in_zone.is_none_or(|(best, _)| len > best)
+            {
+                in_zone = Some((len, location.id));
+            }
+        }
+    }
+
+    in_zone
+        .map(|(_, location_id)| location_id)
+        .or_else(|| locations.first().map(|location| location.id))
+        .ok_or_else(|| "No stock location is available".to_string())
+}
+
+/// Whether a location has every item in stock by itself, given what is `available` there
+/// per product. An order is reserved at one location, so stock elsewhere doesn't help.
+pub fn can_supply(available: &HashMap<Uuid, Decimal>, items: &[InventoryReservation]) -> bool {
+    items.iter().all(|item| {
+        available
+            .get(&item.product_id)
+            .is_some_and(|available| *available >= item.quantity_to_reserve)
+    })
+}
+
+/// Stock of a product at one location
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct LocationStock {
+    #[serde(skip_serializing)]
+    pub product_id: Uuid,
+    pub location_id: Uuid,
+    pub location_name: String,
+    pub quantity_on_hand: Decimal,
+    pub quantity_reserved: Decimal,
+    pub available_quantity: Decimal,
+}
+
+/// Move available stock from one location to another
+#[derive(Deserialize, Debug)]
+pub struct StockTransferRequest {
+    pub from_location_id: Uuid,
+    pub to_location_id: Uuid,
+    pub quantity: Decimal,
+    pub note: Option<String>,
+}
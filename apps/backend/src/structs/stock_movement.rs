@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum StockMovementReason {
+    OpeningBalance, // Stock that existed before the ledger
+    InitialStock,
+    Restock,
+    Reservation,
+    ReservationRelease,
+    Fulfillment,
+    Adjustment,
+    StockCount, // Difference between the counted and the recorded quantity
+    WriteOff,
+    Transfer, // Between locations; recorded once for each side
+}
+
+/// One entry of the append-only stock ledger
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct StockMovement {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub location_id: Option<Uuid>, // None for movements from before stock locations
+    pub on_hand_change: Decimal,
+    pub reserved_change: Decimal,
+    pub reason: StockMovementReason,
+    pub order_id: Option<Uuid>,
+    pub user_id: Option<Uuid>, // Customer or admin that caused the movement
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Who or what caused a stock change, recorded along with the movement
+#[derive(Debug, Clone, Default)]
+pub struct MovementContext {
+    pub order_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub note: Option<String>,
+}
+
+impl MovementContext {
+    pub fn for_order(order_id: Uuid, user_id: Uuid) -> Self {
+        Self {
+            order_id: Some(order_id),
+            user_id: Some(user_id),
+            note: None,
+        }
+    }
+
+    pub fn by_user(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StockMovementQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Current stock of a product compared with the sum of its movements
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct StockLedgerCheck {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub quantity_on_hand: Decimal,
+    pub quantity_reserved: Decimal,
+    pub ledger_on_hand: Decimal,
+    pub ledger_reserved: Decimal,
+}
+
+impl StockLedgerCheck {
+    /// Positive when the inventory row holds more than the ledger explains
+    pub fn on_hand_difference(&self) -> Decimal {
+        self.quantity_on_hand - self.ledger_on_hand
+    }
+
+    pub fn reserved_difference(&self) -> Decimal {
+        self.quantity_reserved - self.ledger_reserved
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.on_hand_difference().is_zero() && self.reserved_difference().is_zero()
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct StockLedgerReport {
+    pub products_checked: usize,
+    pub inconsistent: Vec<StockLedgerCheck>,
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A request to be emailed once a product is back in stock
+#[derive(FromRow, Debug, Clone)]
+pub struct StockNotification {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub email: String,
+    pub verification_token: String,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub verification_sent_at: Option<DateTime<Utc>>, // Last verification email
+    pub created_at: DateTime<Utc>,
+    pub notified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NotifyMeRequest {
+    pub email: Option<String>, // Required when not logged in
+}
+
+#[derive(Serialize, Debug)]
+pub struct NotifyMeResponse {
+    pub product_id: Uuid,
+    pub email: String,
+    pub verification_required: bool, // Guests confirm their address via email first
+}
+
+/// Open back-in-stock subscriptions of a product
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct ProductSubscriberCount {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub available_quantity: Decimal,
+    pub subscribers: i64,
+    pub pending_verification: i64,
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+/// A category in the catalogue, e.g. "Boeketten" with "Rozen" under it
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct Category {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub sort_order: i32, // Lowest first among siblings
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A category with its subcategories
+#[derive(Serialize, Debug, Clone)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    pub children: Vec<CategoryNode>,
+}
+
+/// Used to create a category and to replace one
+#[derive(Deserialize, Debug)]
+pub struct CategoryRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub sort_order: i32,
+    #[serde(default = "default_active")]
+    pub is_active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl CategoryRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name(&self.name, "Category")?;
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|description| description.chars().count() > MAX_DESCRIPTION_LENGTH)
+        {
+            return Err(format!(
+                "Description cannot be longer than {} characters",
+                MAX_DESCRIPTION_LENGTH
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CategoryQuery {
+    pub include_inactive: Option<bool>,
+}
+
+/// An occasion products can be tagged with, e.g. "Verjaardag" or "Rouw"
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct Occasion {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OccasionRequest {
+    pub name: String,
+}
+
+impl OccasionRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_name(&self.name, "Occasion")
+    }
+}
+
+/// Replaces the categories of a product
+#[derive(Deserialize, Debug)]
+pub struct ProductCategoriesRequest {
+    pub category_ids: Vec<Uuid>,
+}
+
+/// Replaces the occasions of a product
+#[derive(Deserialize, Debug)]
+pub struct ProductOccasionsRequest {
+    pub occasion_ids: Vec<Uuid>,
+}
+
+/// The categories and occasions a product is listed under
+#[derive(Serialize, Debug, Clone)]
+pub struct ProductTaxonomy {
+    pub categories: Vec<Category>,
+    pub occasions: Vec<Occasion>,
+}
+
+fn validate_name(name: &str, kind: &str) -> Result<(), String> {
+    let length = name.trim().chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        return Err(format!(
+            "{} name must be between 1 and {} characters",
+            kind, MAX_NAME_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// Arrange categories into trees. Siblings keep the order they are given in; categories
+/// whose parent is missing from the list are left out.
+pub fn build_category_tree(categories: Vec<Category>) -> Vec<CategoryNode> {
+    let mut children: HashMap<Option<Uuid>, Vec<Category>> = HashMap::new();
+    for category in categories {
+        children
+            .entry(category.parent_id)
+            .or_default()
+            .push(category);
+    }
+
+    fn attach(
+        parent_id: Option<Uuid>,
+        children: &mut HashMap<Option<Uuid>, Vec<Category>>,
+    ) -> Vec<CategoryNode> {
+        children
+            .remove(&parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|category| {
+                let id = category.id;
+                CategoryNode {
+                    category,
+                    children: attach(Some(id), children),
+                }
+            })
+            .collect()
+    }
+
+    attach(None, &mut children)
+}
+
+/// Whether `category_id` is `ancestor_id` or lies somewhere below it
+pub fn is_within(categories: &[Category], category_id: Uuid, ancestor_id: Uuid) -> bool {
+    let parents: HashMap<Uuid, Option<Uuid>> = categories
+        .iter()
+        .map(|category| (category.id, category.parent_id))
+        .collect();
+
+    let mut current = Some(category_id);
+    let mut steps = 0;
+    while let Some(id) = current {
+        if id == ancestor_id {
+            return true;
+        }
+        // A broken chain cannot loop forever
+        steps += 1;
+        if steps > categories.len() {
+            return false;
+        }
+        current = parents.get(&id).copied().flatten();
+    }
+    false
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Name of the list that cart items are moved to with "save for later"
+pub const SAVED_FOR_LATER_LIST: &str = "Saved for later";
+
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct Wishlist {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub occasion_date: Option<NaiveDate>,
+    pub share_token: Option<String>, // Set while the list is shared via a read-only link
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow, Serialize, Debug)]
+pub struct WishlistSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub occasion_date: Option<NaiveDate>,
+    pub is_shared: bool,
+    pub item_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Wishlist item joined with the product it refers to
+#[derive(FromRow, Debug)]
+pub struct WishlistItemRow {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: i32,
+    pub saved_price: Decimal, // Price the customer saw when adding the item
+    pub created_at: DateTime<Utc>,
+    pub product_name: String,
+    pub product_sku: String,
+    pub product_price: Decimal,
+    pub primary_image_url: Option<String>,
+    pub available_stock: Decimal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WishlistItemDetails {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub product_sku: String,
+    pub primary_image_url: Option<String>,
+    pub quantity: i32,
+    pub saved_price: Decimal,
+    pub current_price: Decimal,
+    pub price_dropped: bool,
+    pub price_drop_amount: Decimal,
+    pub in_stock: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WishlistItemDetails {
+    /// Combine a stored item with the price it currently sells for
+    pub fn from_row(row: WishlistItemRow, current_price: Decimal) -> Self {
+        let price_drop_amount = price_drop(row.saved_price, current_price);
+
+        Self {
+            id: row.id,
+            product_id: row.product_id,
+            product_name: row.product_name,
+            product_sku: row.product_sku,
+            primary_image_url: row.primary_image_url,
+            quantity: row.quantity,
+            saved_price: row.saved_price,
+            current_price,
+            price_dropped: price_drop_amount > Decimal::ZERO,
+            price_drop_amount,
+            in_stock: row.available_stock >= Decimal::from(row.quantity),
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// How much cheaper a product got since it was saved; zero when the price did not drop
+pub fn price_drop(saved_price: Decimal, current_price: Decimal) -> Decimal {
+    (saved_price - current_price).max(Decimal::ZERO).round_dp(2)
+}
+
+#[derive(Serialize, Debug)]
+pub struct WishlistResponse {
+    pub wishlist: Wishlist,
+    pub items: Vec<WishlistItemDetails>,
+}
+
+/// Read-only view of a shared list, without any account details of the owner
+#[derive(Serialize, Debug)]
+pub struct SharedWishlistResponse {
+    pub name: String,
+    pub occasion_date: Option<NaiveDate>,
+    pub owner_first_name: String,
+    pub items: Vec<WishlistItemDetails>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WishlistShareLink {
+    pub share_token: String,
+    pub share_url: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateWishlistRequest {
+    pub name: String,
+    pub occasion_date: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateWishlistRequest {
+    pub name: Option<String>,
+    pub occasion_date: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AddWishlistItemRequest {
+    pub product_id: Uuid,
+    pub quantity: Option<i32>,
+}
@@ -1,3 +1,4 @@
 pub mod calculate;
 pub mod discount;
+pub mod slug;
 pub mod tax;
@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+const MAX_SLUG_LENGTH: usize = 80;
+
+/// Turn a product name into a URL slug: lowercase ASCII letters and digits separated by
+/// single hyphens, with accents folded, e.g. "Crème brûlée-rozen" becomes "creme-brulee-rozen"
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    let mut pending_hyphen = false;
+
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let mut ascii = [0; 4];
+        let folded = match c {
+            'a'..='z' | '0'..='9' => &*c.encode_utf8(&mut ascii),
+            _ => fold(c),
+        };
+        if folded.is_empty() {
+            pending_hyphen = !slug.is_empty();
+            continue;
+        }
+        if pending_hyphen {
+            slug.push('-');
+            pending_hyphen = false;
+        }
+        slug.push_str(folded);
+    }
+
+    if slug.len() > MAX_SLUG_LENGTH {
+        // Cut at a word boundary when there is one
+        slug.truncate(MAX_SLUG_LENGTH);
+        if let Some(cut) = slug.rfind('-') {
+            slug.truncate(cut);
+        }
+    }
+
+    if slug.is_empty() {
+        "product".to_string()
+    } else {
+        slug
+    }
+}
+
+/// The ASCII spelling of a lowercase accented letter, or "" for separators and symbols
+fn fold(c: char) -> &'static str {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+        'æ' => "ae",
+        'ç' => "c",
+        'è' | 'é' | 'ê' | 'ë' => "e",
+        'ì' | 'í' | 'î' | 'ï' => "i",
+        'ĳ' => "ij",
+        'ñ' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+        'œ' => "oe",
+        'ß' => "ss",
+        'ù' | 'ú' | 'û' | 'ü' => "u",
+        'ý' | 'ÿ' => "y",
+        '&' => "en", // "Rozen & tulpen" reads as "rozen en tulpen"
+        _ => "",
+    }
+}
+
+/// The first of `base`, `base-2`, `base-3`, ... that is not taken
+pub fn first_free_slug(base: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|slug| !taken.contains(slug))
+        .unwrap_or_else(|| base.to_string())
+}
+
+/// Whether `slug` was generated from `base`, with or without a number to make it unique
+pub fn is_slug_of(slug: &str, base: &str) -> bool {
+    slug == base
+        || slug
+            .strip_prefix(base)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
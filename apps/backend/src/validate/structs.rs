@@ -8,6 +8,7 @@ use crate::validate::{
     validate_dutch_city_name, validate_dutch_house_number, validate_dutch_postal_code,
     validate_dutch_province,
 };
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -100,6 +101,10 @@ pub struct ValidatedIncomingOrder {
 
     #[validate(length(max = 1000, message = "Notes cannot exceed 1000 characters"))]
     pub notes: Option<String>,
+
+    pub delivery_date: Option<NaiveDate>,
+
+    pub pickup_location_id: Option<Uuid>,
 }
 
 /// Validated customer information
@@ -315,6 +320,8 @@ impl From<ValidatedIncomingOrder> for crate::structs::IncomingOrder {
             shipping_address: validated.shipping_address.into(),
             billing_address: validated.billing_address.into(),
             notes: validated.notes,
+            delivery_date: validated.delivery_date,
+            pickup_location_id: validated.pickup_location_id,
         }
     }
 }
@@ -328,6 +335,8 @@ impl From<crate::structs::IncomingOrder> for ValidatedIncomingOrder {
             shipping_address: order.shipping_address.into(),
             billing_address: order.billing_address.into(),
             notes: order.notes,
+            delivery_date: order.delivery_date,
+            pickup_location_id: order.pickup_location_id,
         }
     }
 }
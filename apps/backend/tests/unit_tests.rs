@@ -16,6 +16,8 @@ mod search_actions_tests {
             price_min: Some(10.0),
             price_max: Some(50.0),
             in_stock: Some(true),
+            category_id: None,
+            occasion_id: None,
             sort_by: Some("price".to_string()),
             sort_direction: Some("desc".to_string()),
             page: Some(2),
@@ -48,6 +50,8 @@ mod search_actions_tests {
             price_min: Some(20.0),
             price_max: Some(100.0),
             in_stock: Some(false),
+            category_id: None,
+            occasion_id: None,
             sort_by: None,
             sort_direction: None,
             page: None,
@@ -73,6 +77,8 @@ mod search_actions_tests {
             price_min: None,
             price_max: None,
             in_stock: None,
+            category_id: None,
+            occasion_id: None,
             sort_by: None,
             sort_direction: None,
             page: None,
@@ -87,6 +93,8 @@ mod search_actions_tests {
             price_min: None,
             price_max: None,
             in_stock: None,
+            category_id: None,
+            occasion_id: None,
             sort_by: None,
             sort_direction: None,
             page: None,
@@ -95,6 +103,17 @@ mod search_actions_tests {
 
         assert!(has_filters(&params_with_filters));
         assert!(!has_filters(&params_without_filters));
+
+        let params_with_category = SearchParams {
+            category_id: Some(uuid::Uuid::new_v4()),
+            product_type: None,
+            ..params_with_filters
+        };
+        assert!(has_filters(&params_with_category));
+        assert_eq!(
+            parse_search_filters(&params_with_category).category_id,
+            params_with_category.category_id
+        );
     }
 }
 
@@ -179,3 +198,2006 @@ mod search_suggestions_tests {
         );
     }
 }
+
+// Tests for services::abandoned_cart_service module
+mod abandoned_cart_tests {
+    use mamabloemetjes_backend::services::AbandonedCartService;
+    use mamabloemetjes_backend::structs::abandoned_cart::AbandonedCart;
+    use uuid::Uuid;
+
+    fn create_test_cart(first_name: &str) -> AbandonedCart {
+        AbandonedCart {
+            cart_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            email: "klant@example.nl".to_string(),
+            first_name: first_name.to_string(),
+            last_activity: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_build_reminder_email_links() {
+        let cart = create_test_cart("Anna");
+        let email =
+            AbandonedCartService::build_reminder_email(&cart, "https://shop.example/", "abc123");
+
+        assert_eq!(email.recipient, "klant@example.nl");
+        assert_eq!(email.template, "abandoned_cart_reminder");
+        assert!(email.body.starts_with("Hallo Anna,"));
+        assert!(
+            email
+                .body
+                .contains("https://shop.example/cart/restore?token=abc123")
+        );
+        assert!(
+            email
+                .body
+                .contains("https://shop.example/cart/reminders/unsubscribe?token=abc123")
+        );
+    }
+
+    #[test]
+    fn test_build_reminder_email_without_name() {
+        let cart = create_test_cart("  ");
+        let email = AbandonedCartService::build_reminder_email(&cart, "http://localhost:3000", "t");
+
+        assert!(email.body.starts_with("Hallo,"));
+    }
+}
+
+// Tests for structs::wishlist module
+mod wishlist_tests {
+    use mamabloemetjes_backend::services::WishlistService;
+    use mamabloemetjes_backend::structs::wishlist::price_drop;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_price_drop() {
+        assert_eq!(price_drop(dec!(29.95), dec!(24.95)), dec!(5.00));
+        assert_eq!(price_drop(dec!(24.95), dec!(24.95)), dec!(0));
+        // A price increase is not reported as a negative drop
+        assert_eq!(price_drop(dec!(24.95), dec!(29.95)), dec!(0));
+    }
+
+    #[test]
+    fn test_build_share_url() {
+        assert_eq!(
+            WishlistService::build_share_url("https://shop.example/", "abc123"),
+            "https://shop.example/wishlists/shared/abc123"
+        );
+    }
+}
+
+// Tests for structs::quote module
+mod quote_tests {
+    use chrono::{Duration, Utc};
+    use mamabloemetjes_backend::services::ProductPriceInfo;
+    use mamabloemetjes_backend::structs::order::{OrderContent, ProductEntry};
+    use mamabloemetjes_backend::structs::quote::PriceQuote;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn create_test_quote(products: &[(Uuid, i32)]) -> PriceQuote {
+        let products = products
+            .iter()
+            .map(|(id, quantity)| ProductPriceInfo {
+                id: *id,
+                name: "Boeket".to_string(),
+                original_price: dec!(25.00),
+                quantity: *quantity,
+                line_total: dec!(25.00) * rust_decimal::Decimal::from(*quantity),
+                best_discount_percentage: dec!(10),
+                discounted_price: dec!(22.50),
+                final_line_total: dec!(22.50) * rust_decimal::Decimal::from(*quantity),
+                applied_promotion_id: Some(Uuid::new_v4()),
+                reference_price: Some(dec!(25.00)),
+            })
+            .collect();
+
+        PriceQuote {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            products: sqlx::types::Json(products),
+            subtotal_before_discount: dec!(0),
+            total_discount_amount: dec!(0),
+            final_total: dec!(0),
+            total_with_tax: dec!(0),
+            expires_at: Utc::now() + Duration::minutes(15),
+            created_at: Utc::now(),
+            used_at: None,
+            order_id: None,
+        }
+    }
+
+    fn entry(product_id: Uuid, quantity: i32) -> ProductEntry {
+        ProductEntry {
+            product_id,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_quote_covers_same_items_in_any_grouping() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let quote = create_test_quote(&[(a, 2), (b, 1)]);
+
+        let items = vec![
+            OrderContent {
+                product: vec![entry(b, 1), entry(a, 1)],
+            },
+            OrderContent {
+                product: vec![entry(a, 1)],
+            },
+        ];
+        assert!(quote.covers_items(&items));
+    }
+
+    #[test]
+    fn test_quote_rejects_changed_basket() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let quote = create_test_quote(&[(a, 2)]);
+
+        let more = vec![OrderContent {
+            product: vec![entry(a, 3)],
+        }];
+        let extra = vec![OrderContent {
+            product: vec![entry(a, 2), entry(b, 1)],
+        }];
+        assert!(!quote.covers_items(&more));
+        assert!(!quote.covers_items(&extra));
+    }
+}
+
+// Tests for structs::order module
+mod order_line_snapshot_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::structs::enums::{Colors, ProductType, Size};
+    use mamabloemetjes_backend::structs::order::OrderLine;
+    use mamabloemetjes_backend::structs::product::{Product, ProductImage};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn create_test_product(images: Option<Vec<ProductImage>>) -> Product {
+        Product {
+            id: Uuid::new_v4(),
+            name: "Zomerboeket".to_string(),
+            slug: "zomerboeket".to_string(),
+            sku: "BQ-SUMMER-M".to_string(),
+            price: dec!(29.95),
+            discounted_price: dec!(29.95),
+            tax: dec!(5.20),
+            subtotal: dec!(24.75),
+            description: "Vrolijk boeket".to_string(),
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            size: Size::Medium,
+            colors: vec![Colors::Yellow, Colors::Orange],
+            product_type: ProductType::Bouquet,
+            stock: dec!(10),
+            images,
+            parent_id: None,
+            average_rating: Some(dec!(4.50)),
+            review_count: 2,
+            variants: None,
+        }
+    }
+
+    fn image(product_id: Uuid, url: &str, is_primary: bool) -> ProductImage {
+        ProductImage {
+            id: Uuid::new_v4(),
+            product_id,
+            url: url.to_string(),
+            alt_text: None,
+            is_primary,
+            sort_order: 0,
+            renditions: sqlx::types::Json(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_copies_product_details() {
+        let mut product = create_test_product(None);
+        product.images = Some(vec![
+            image(product.id, "side.jpg", false),
+            image(product.id, "front.jpg", true),
+        ]);
+        let promotion_id = Uuid::new_v4();
+
+        let line = OrderLine::new(Uuid::new_v4(), product.id, dec!(1), dec!(29.95), dec!(0))
+            .with_product_snapshot(&product, dec!(0.21), Some(promotion_id));
+
+        assert_eq!(line.product_name.as_deref(), Some("Zomerboeket"));
+        assert_eq!(line.product_sku.as_deref(), Some("BQ-SUMMER-M"));
+        assert_eq!(line.product_size, Some(Size::Medium));
+        assert_eq!(
+            line.product_colors,
+            Some(vec![Colors::Yellow, Colors::Orange])
+        );
+        assert_eq!(line.product_image_url.as_deref(), Some("front.jpg"));
+        assert_eq!(line.vat_rate, Some(dec!(0.21)));
+        assert_eq!(line.applied_promotion_id, Some(promotion_id));
+    }
+
+    #[test]
+    fn test_snapshot_without_images() {
+        let product = create_test_product(None);
+
+        let line = OrderLine::new(Uuid::new_v4(), product.id, dec!(2), dec!(29.95), dec!(0))
+            .with_product_snapshot(&product, dec!(0.21), None);
+
+        assert!(line.product_image_url.is_none());
+        assert!(line.applied_promotion_id.is_none());
+    }
+}
+
+// Tests for order totals reconciliation in structs::order
+mod order_totals_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::structs::order::{Order, OrderLine, OrderWithLines};
+    use mamabloemetjes_backend::structs::{Address, OrderStatus};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn address() -> Address {
+        Address {
+            street: "Bloemgracht".to_string(),
+            house_number: "12".to_string(),
+            postal_code: "1015TK".to_string(),
+            city: "Amsterdam".to_string(),
+            province: "Noord-Holland".to_string(),
+        }
+    }
+
+    fn create_order(subtotal: Decimal, tax: Decimal, discount: Decimal, total: Decimal) -> Order {
+        Order {
+            id: Some(Uuid::new_v4()),
+            user_id: Uuid::new_v4(),
+            order_number: "MB-1".to_string(),
+            status: OrderStatus::Pending,
+            subtotal,
+            tax_amount: tax,
+            shipping_cost: dec!(0),
+            discount_amount: discount,
+            total_amount: total,
+            notes: None,
+            shipping_address: address(),
+            billing_address: address(),
+            delivery_date: None,
+            awaiting_stock: false,
+            expected_ship_date: None,
+            pickup_location_id: None,
+            stock_location_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_lines(order_id: Uuid) -> Vec<OrderLine> {
+        vec![
+            // 2 x 22.50 after a 2.50 discount per unit
+            OrderLine::new(order_id, Uuid::new_v4(), dec!(2), dec!(22.50), dec!(5.00)),
+            OrderLine::new(order_id, Uuid::new_v4(), dec!(1), dec!(10.00), dec!(0)),
+        ]
+    }
+
+    #[test]
+    fn test_matching_totals() {
+        // Subtotal 55.00, tax 2 x 4.72 + 2.10 = 11.54 (VAT is rounded per unit, half to even)
+        let order = create_order(dec!(55.00), dec!(11.54), dec!(5.00), dec!(66.54));
+        let lines = create_lines(order.id.unwrap());
+        let order_with_lines = OrderWithLines::new(order, lines);
+
+        assert!(order_with_lines.validate_totals());
+    }
+
+    #[test]
+    fn test_discounted_order_totals_agree() {
+        // An order discount is already in the subtotal, so the order's own total and the
+        // total recomputed from its lines must not subtract it a second time
+        let order = create_order(dec!(55.00), dec!(11.54), dec!(5.00), dec!(66.54));
+        assert_eq!(order.calculate_total(), dec!(66.54));
+
+        let lines = create_lines(order.id.unwrap());
+        let order_with_lines = OrderWithLines::new(order, lines);
+
+        assert_eq!(order_with_lines.calculated_subtotal(), dec!(55.00));
+        assert_eq!(order_with_lines.calculated_discount(), dec!(5.00));
+        assert_eq!(
+            order_with_lines.calculated_total(),
+            order_with_lines.order.calculate_total()
+        );
+        assert!(order_with_lines.totals_mismatches().is_empty());
+    }
+
+    #[test]
+    fn test_one_cent_is_tolerated() {
+        let order = create_order(dec!(55.00), dec!(11.54), dec!(5.00), dec!(66.55));
+        let lines = create_lines(order.id.unwrap());
+
+        assert!(OrderWithLines::new(order, lines).validate_totals());
+    }
+
+    #[test]
+    fn test_mismatching_figures_are_reported() {
+        let order = create_order(dec!(55.00), dec!(11.54), dec!(0), dec!(70.00));
+        let lines = create_lines(order.id.unwrap());
+        let mismatches = OrderWithLines::new(order, lines).totals_mismatches();
+
+        let fields: Vec<&str> = mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["discount_amount", "total_amount"]);
+
+        let total = &mismatches[1];
+        assert_eq!(total.recorded, dec!(70.00));
+        assert_eq!(total.calculated, dec!(66.54));
+        assert_eq!(total.difference, dec!(3.46));
+    }
+}
+
+// Tests for structs::stock_movement module
+mod stock_movement_tests {
+    use mamabloemetjes_backend::structs::stock_movement::{
+        MovementContext, StockLedgerCheck, StockMovementReason,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn create_check(on_hand: Decimal, reserved: Decimal) -> StockLedgerCheck {
+        StockLedgerCheck {
+            product_id: Uuid::new_v4(),
+            name: "Rozenboeket".to_string(),
+            sku: "RB-001".to_string(),
+            quantity_on_hand: on_hand,
+            quantity_reserved: reserved,
+            ledger_on_hand: dec!(12),
+            ledger_reserved: dec!(3),
+        }
+    }
+
+    #[test]
+    fn test_ledger_matches_inventory() {
+        let check = create_check(dec!(12), dec!(3));
+
+        assert!(check.is_consistent());
+    }
+
+    #[test]
+    fn test_ledger_differences() {
+        let check = create_check(dec!(10), dec!(4));
+
+        assert!(!check.is_consistent());
+        assert_eq!(check.on_hand_difference(), dec!(-2));
+        assert_eq!(check.reserved_difference(), dec!(1));
+    }
+
+    #[test]
+    fn test_reason_serialization() {
+        let json = serde_json::to_string(&StockMovementReason::ReservationRelease).unwrap();
+
+        assert_eq!(json, "\"reservation_release\"");
+    }
+
+    #[test]
+    fn test_order_context() {
+        let order_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let context = MovementContext::for_order(order_id, user_id).with_note("Cancelled");
+
+        assert_eq!(context.order_id, Some(order_id));
+        assert_eq!(context.user_id, Some(user_id));
+        assert_eq!(context.note.as_deref(), Some("Cancelled"));
+    }
+}
+
+// Tests for admin stock requests in structs::inventory
+mod stock_request_tests {
+    use mamabloemetjes_backend::structs::inventory::{WriteOffReason, WriteOffRequest};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_write_off_request_deserialization() {
+        let request: WriteOffRequest =
+            serde_json::from_str(r#"{"quantity": "3", "reason": "wilted"}"#).unwrap();
+
+        assert_eq!(request.quantity, dec!(3));
+        assert_eq!(request.reason, WriteOffReason::Wilted);
+        assert!(request.note.is_none());
+    }
+
+    #[test]
+    fn test_unknown_write_off_reason_is_rejected() {
+        let result: Result<WriteOffRequest, _> =
+            serde_json::from_str(r#"{"quantity": "3", "reason": "sold"}"#);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_write_off_reason_display() {
+        assert_eq!(WriteOffReason::Damaged.to_string(), "damaged");
+        assert_eq!(WriteOffReason::Other.to_string(), "other");
+    }
+}
+
+// Tests for first-expiring-first allocation in structs::stock_batch
+mod stock_batch_tests {
+    use chrono::{NaiveDate, Utc};
+    use mamabloemetjes_backend::structs::stock_batch::{
+        BatchQuantity, StockBatch, allocate_batches,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, day).unwrap()
+    }
+
+    fn create_batch(remaining: Decimal, reserved: Decimal, expires_on: Option<u32>) -> StockBatch {
+        StockBatch {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            location_id: None,
+            quantity_received: remaining,
+            quantity_remaining: remaining,
+            quantity_reserved: reserved,
+            received_at: Utc::now(),
+            expires_on: expires_on.map(date),
+            written_off_at: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_earliest_batch_is_used_first() {
+        let batches = vec![
+            create_batch(dec!(4), dec!(1), Some(10)),
+            create_batch(dec!(10), dec!(0), Some(12)),
+        ];
+
+        let allocations =
+            allocate_batches(&batches, dec!(5), BatchQuantity::Available, None).unwrap();
+
+        assert_eq!(allocations.len(), 2);
+        assert_eq!(allocations[0].batch_id, batches[0].id);
+        assert_eq!(allocations[0].quantity, dec!(3));
+        assert_eq!(allocations[1].batch_id, batches[1].id);
+        assert_eq!(allocations[1].quantity, dec!(2));
+    }
+
+    #[test]
+    fn test_expired_batches_are_skipped_for_fresh_stock() {
+        let batches = vec![
+            create_batch(dec!(4), dec!(0), Some(9)),
+            create_batch(dec!(4), dec!(0), Some(10)),
+        ];
+
+        let allocations =
+            allocate_batches(&batches, dec!(2), BatchQuantity::Available, Some(date(10))).unwrap();
+
+        // A batch can still be sold on its expiry date
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].batch_id, batches[1].id);
+    }
+
+    #[test]
+    fn test_reserved_quantity_is_taken_in_reverse() {
+        let batches = [
+            create_batch(dec!(4), dec!(2), Some(10)),
+            create_batch(dec!(4), dec!(3), None),
+        ];
+
+        let allocations =
+            allocate_batches(batches.iter().rev(), dec!(4), BatchQuantity::Reserved, None).unwrap();
+
+        assert_eq!(allocations[0].batch_id, batches[1].id);
+        assert_eq!(allocations[0].quantity, dec!(3));
+        assert_eq!(allocations[1].quantity, dec!(1));
+    }
+
+    #[test]
+    fn test_missing_quantity_is_reported() {
+        let batches = vec![create_batch(dec!(3), dec!(1), Some(10))];
+
+        let result = allocate_batches(&batches, dec!(5), BatchQuantity::Available, None);
+
+        assert_eq!(result, Err(dec!(3)));
+    }
+}
+
+// Tests for services::low_stock_service module
+mod low_stock_alert_tests {
+    use mamabloemetjes_backend::services::{LowStockProduct, LowStockService};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn create_product() -> LowStockProduct {
+        LowStockProduct {
+            product_id: Uuid::new_v4(),
+            name: "Zonnebloemen".to_string(),
+            sku: "ZB-010".to_string(),
+            quantity_on_hand: dec!(6),
+            quantity_reserved: dec!(4),
+            available_quantity: dec!(2),
+            reorder_threshold: dec!(3),
+        }
+    }
+
+    #[test]
+    fn test_alert_message() {
+        let message = LowStockService::alert_message(&create_product());
+
+        assert_eq!(
+            message,
+            "Zonnebloemen (ZB-010) has 2 available (6 on hand, 4 reserved), at or below its reorder threshold of 3."
+        );
+    }
+
+    #[test]
+    fn test_alert_email() {
+        let email = LowStockService::build_alert_email(&create_product(), "winkel@example.com");
+
+        assert_eq!(email.recipient, "winkel@example.com");
+        assert_eq!(email.subject, "Low stock: Zonnebloemen (ZB-010)");
+        assert_eq!(email.template, "low_stock_alert");
+        assert!(email.body.contains("has 2 available"));
+    }
+}
+
+// Tests for services::back_in_stock_service module
+mod back_in_stock_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::services::BackInStockService;
+    use mamabloemetjes_backend::structs::stock_notification::StockNotification;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_verification_email() {
+        let subscription = StockNotification {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            user_id: None,
+            email: "klant@example.com".to_string(),
+            verification_token: "abc123".to_string(),
+            verified_at: None,
+            verification_sent_at: None,
+            created_at: Utc::now(),
+            notified_at: None,
+        };
+
+        let email = BackInStockService::build_verification_email(
+            &subscription,
+            "Pioenrozen",
+            "https://mamabloemetjes.nl/",
+        );
+
+        assert_eq!(email.recipient, "klant@example.com");
+        assert_eq!(email.template, "back_in_stock_verification");
+        assert!(
+            email
+                .body
+                .contains("https://mamabloemetjes.nl/products/notify-me/verify?token=abc123")
+        );
+    }
+
+    #[test]
+    fn test_back_in_stock_email() {
+        let product_id = Uuid::new_v4();
+        let email = BackInStockService::build_back_in_stock_email(
+            "klant@example.com",
+            "Pioenrozen",
+            product_id,
+            "https://mamabloemetjes.nl",
+        );
+
+        assert_eq!(email.subject, "Pioenrozen is weer op voorraad");
+        assert!(email.body.contains(&format!(
+            "https://mamabloemetjes.nl/products/{}",
+            product_id
+        )));
+    }
+}
+
+// Tests for bouquet recipes in structs::bill_of_materials
+mod bill_of_materials_tests {
+    use mamabloemetjes_backend::structs::bill_of_materials::{
+        RecipeComponent, SetRecipeRequest, expand_components,
+    };
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_expand_replaces_bouquet_with_components() {
+        let bouquet = Uuid::new_v4();
+        let roses = Uuid::new_v4();
+        let ribbon = Uuid::new_v4();
+        let recipes = HashMap::from([(bouquet, vec![(roses, dec!(10)), (ribbon, dec!(1))])]);
+
+        let expanded = expand_components(&[(bouquet, dec!(2))], &recipes);
+
+        assert_eq!(expanded, vec![(roses, dec!(20)), (ribbon, dec!(2))]);
+    }
+
+    #[test]
+    fn test_expand_merges_shared_components() {
+        let bouquet = Uuid::new_v4();
+        let roses = Uuid::new_v4();
+        let tulips = Uuid::new_v4();
+        let recipes = HashMap::from([(bouquet, vec![(roses, dec!(10))])]);
+
+        // Loose roses ordered next to a rose bouquet are reserved together
+        let expanded = expand_components(
+            &[(tulips, dec!(5)), (bouquet, dec!(1)), (roses, dec!(3))],
+            &recipes,
+        );
+
+        assert_eq!(expanded, vec![(tulips, dec!(5)), (roses, dec!(13))]);
+    }
+
+    #[test]
+    fn test_expand_keeps_products_without_recipe() {
+        let bouquet = Uuid::new_v4();
+        let expanded = expand_components(&[(bouquet, dec!(4))], &HashMap::new());
+        assert_eq!(expanded, vec![(bouquet, dec!(4))]);
+    }
+
+    #[test]
+    fn test_recipe_validation() {
+        let bouquet = Uuid::new_v4();
+        let roses = Uuid::new_v4();
+        let component = |component_id, quantity| RecipeComponent {
+            component_id,
+            quantity,
+        };
+
+        let valid = SetRecipeRequest {
+            components: vec![component(roses, dec!(10))],
+        };
+        assert!(valid.validate(bouquet).is_ok());
+
+        let empty = SetRecipeRequest { components: vec![] };
+        assert!(empty.validate(bouquet).is_ok());
+
+        let itself = SetRecipeRequest {
+            components: vec![component(bouquet, dec!(1))],
+        };
+        assert!(itself.validate(bouquet).is_err());
+
+        let zero = SetRecipeRequest {
+            components: vec![component(roses, dec!(0))],
+        };
+        assert!(zero.validate(bouquet).is_err());
+
+        let duplicate = SetRecipeRequest {
+            components: vec![component(roses, dec!(5)), component(roses, dec!(5))],
+        };
+        assert!(duplicate.validate(bouquet).is_err());
+    }
+}
+
+// Tests for backorders and pre-orders in structs::inventory
+mod backorder_tests {
+    use chrono::NaiveDate;
+    use mamabloemetjes_backend::structs::inventory::{BackorderMode, BackorderPolicy, StockCheck};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2027, 5, day).unwrap()
+    }
+
+    fn policy(limit: Option<Decimal>, day: Option<u32>) -> BackorderPolicy {
+        BackorderPolicy {
+            mode: BackorderMode::Preorder,
+            limit,
+            expected_available_on: day.map(date),
+        }
+    }
+
+    #[test]
+    fn test_in_stock_ignores_policy() {
+        assert_eq!(
+            StockCheck::check(dec!(5), dec!(5), None),
+            StockCheck::InStock
+        );
+        assert_eq!(
+            StockCheck::check(dec!(5), dec!(2), Some(&policy(None, Some(9)))),
+            StockCheck::InStock
+        );
+    }
+
+    #[test]
+    fn test_short_without_policy_is_insufficient() {
+        assert_eq!(
+            StockCheck::check(dec!(1), dec!(2), None),
+            StockCheck::Insufficient
+        );
+    }
+
+    #[test]
+    fn test_backorder_within_limit() {
+        let policy = policy(Some(dec!(10)), Some(9));
+
+        // 3 units already on backorder, 7 more fit in the limit
+        assert_eq!(
+            StockCheck::check(dec!(-3), dec!(7), Some(&policy)),
+            StockCheck::Backordered {
+                expected_available_on: Some(date(9))
+            }
+        );
+        assert_eq!(
+            StockCheck::check(dec!(-3), dec!(8), Some(&policy)),
+            StockCheck::Insufficient
+        );
+    }
+
+    #[test]
+    fn test_backorder_without_limit() {
+        let check = StockCheck::check(dec!(0), dec!(500), Some(&policy(None, None)));
+        assert!(check.is_backordered());
+        assert_eq!(check.expected_available_on(), None);
+    }
+
+    #[test]
+    fn test_backorder_limit_is_for_the_product_as_a_whole() {
+        let policy = policy(Some(dec!(2)), Some(9));
+
+        // Enough at other locations: no backorder while they still have stock
+        assert_eq!(
+            StockCheck::check_at_location(dec!(1), dec!(6), dec!(4), Some(&policy)),
+            StockCheck::Insufficient
+        );
+        // 1 here and 1 elsewhere: 2 short over all locations fits the limit
+        assert_eq!(
+            StockCheck::check_at_location(dec!(1), dec!(2), dec!(4), Some(&policy)),
+            StockCheck::Backordered {
+                expected_available_on: Some(date(9))
+            }
+        );
+        assert_eq!(
+            StockCheck::check_at_location(dec!(1), dec!(1), dec!(4), Some(&policy)),
+            StockCheck::Insufficient
+        );
+        assert_eq!(
+            StockCheck::check_at_location(dec!(4), dec!(4), dec!(4), None),
+            StockCheck::InStock
+        );
+    }
+
+    #[test]
+    fn test_combine_waits_for_last_part() {
+        let early = StockCheck::Backordered {
+            expected_available_on: Some(date(3)),
+        };
+        let late = StockCheck::Backordered {
+            expected_available_on: Some(date(8)),
+        };
+        let unknown = StockCheck::Backordered {
+            expected_available_on: None,
+        };
+
+        assert_eq!(StockCheck::InStock.combine(early.clone()), early.clone());
+        assert_eq!(early.clone().combine(late.clone()), late);
+        assert_eq!(early.clone().combine(unknown.clone()), unknown);
+        assert_eq!(
+            early.combine(StockCheck::Insufficient),
+            StockCheck::Insufficient
+        );
+    }
+}
+
+// Tests for purchase orders in structs::purchase_order
+mod purchase_order_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::structs::purchase_order::{
+        PurchaseOrder, PurchaseOrderLine, PurchaseOrderLineRequest, PurchaseOrderRequest,
+        PurchaseOrderStatus, PurchaseOrderWithLines, ReceiveLineRequest,
+        ReceivePurchaseOrderRequest, suggested_quantity,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn line(
+        quantity: Decimal,
+        quantity_received: Decimal,
+        cost_price: Decimal,
+    ) -> PurchaseOrderLine {
+        PurchaseOrderLine {
+            id: Uuid::new_v4(),
+            purchase_order_id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            product_name: "Roos rood".to_string(),
+            product_sku: "ROS-RED".to_string(),
+            quantity,
+            quantity_received,
+            cost_price,
+        }
+    }
+
+    fn request(lines: Vec<PurchaseOrderLineRequest>) -> PurchaseOrderRequest {
+        PurchaseOrderRequest {
+            supplier_id: Uuid::new_v4(),
+            expected_on: None,
+            notes: None,
+            lines,
+        }
+    }
+
+    fn line_request(
+        product_id: Uuid,
+        quantity: Decimal,
+        cost_price: Decimal,
+    ) -> PurchaseOrderLineRequest {
+        PurchaseOrderLineRequest {
+            product_id,
+            quantity,
+            cost_price,
+        }
+    }
+
+    #[test]
+    fn test_purchase_order_status_display() {
+        assert_eq!(PurchaseOrderStatus::Draft.to_string(), "draft");
+        assert_eq!(
+            PurchaseOrderStatus::PartiallyReceived.to_string(),
+            "partially received"
+        );
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let product_id = Uuid::new_v4();
+
+        assert!(request(vec![]).validate().is_err());
+        assert!(
+            request(vec![line_request(product_id, dec!(0), dec!(1))])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            request(vec![line_request(product_id, dec!(5), dec!(-1))])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            request(vec![
+                line_request(product_id, dec!(5), dec!(1)),
+                line_request(product_id, dec!(5), dec!(1)),
+            ])
+            .validate()
+            .is_err()
+        );
+        assert!(
+            request(vec![
+                line_request(product_id, dec!(50), dec!(0.45)),
+                line_request(Uuid::new_v4(), dec!(10), dec!(0)),
+            ])
+            .validate()
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_status_after_receipt() {
+        assert_eq!(
+            PurchaseOrderWithLines::status_after_receipt(&[
+                line(dec!(50), dec!(50), dec!(0.45)),
+                line(dec!(20), dec!(5), dec!(1.10)),
+            ]),
+            PurchaseOrderStatus::PartiallyReceived
+        );
+        assert_eq!(
+            PurchaseOrderWithLines::status_after_receipt(&[
+                line(dec!(50), dec!(50), dec!(0.45)),
+                line(dec!(20), dec!(20), dec!(1.10)),
+            ]),
+            PurchaseOrderStatus::Received
+        );
+    }
+
+    #[test]
+    fn test_received_quantity_is_summed_per_product() {
+        let roses = Uuid::new_v4();
+        let tulips = Uuid::new_v4();
+        let receive_line = |product_id, quantity| ReceiveLineRequest {
+            product_id,
+            quantity,
+            expires_on: None,
+        };
+        let request = ReceivePurchaseOrderRequest {
+            lines: vec![
+                receive_line(roses, dec!(30)),
+                receive_line(tulips, dec!(10)),
+                receive_line(roses, dec!(25)),
+            ],
+            note: None,
+            location_id: None,
+        };
+
+        let quantities = request.quantity_per_product();
+        assert_eq!(quantities.len(), 2);
+        assert_eq!(quantities[&roses], dec!(55));
+        assert_eq!(quantities[&tulips], dec!(10));
+    }
+
+    #[test]
+    fn test_total_cost_uses_ordered_quantity() {
+        let purchase_order = PurchaseOrder {
+            id: Uuid::new_v4(),
+            reference: PurchaseOrder::generate_reference(),
+            supplier_id: Uuid::new_v4(),
+            supplier_name: "Bloemenveiling Aalsmeer".to_string(),
+            status: PurchaseOrderStatus::Sent,
+            expected_on: None,
+            notes: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sent_at: Some(Utc::now()),
+            received_at: None,
+        };
+
+        let with_lines = PurchaseOrderWithLines::new(
+            purchase_order,
+            vec![
+                line(dec!(50), dec!(0), dec!(0.45)),
+                line(dec!(20), dec!(10), dec!(1.10)),
+            ],
+        );
+
+        assert_eq!(with_lines.total_cost, dec!(44.50));
+        assert!(with_lines.purchase_order.reference.starts_with("PO-"));
+        assert_eq!(with_lines.purchase_order.reference.len(), 20);
+    }
+
+    #[test]
+    fn test_suggested_quantity() {
+        // Below the threshold, nothing on order
+        assert_eq!(
+            suggested_quantity(dec!(3), dec!(0), dec!(10), dec!(0), dec!(0)),
+            dec!(7)
+        );
+        // Backordered units are bought as well
+        assert_eq!(
+            suggested_quantity(dec!(-4), dec!(0), dec!(10), dec!(0), dec!(0)),
+            dec!(14)
+        );
+        // Outstanding purchase orders count towards the threshold
+        assert_eq!(
+            suggested_quantity(dec!(3), dec!(5), dec!(10), dec!(0), dec!(0)),
+            dec!(2)
+        );
+        assert_eq!(
+            suggested_quantity(dec!(3), dec!(20), dec!(10), dec!(0), dec!(0)),
+            dec!(0)
+        );
+    }
+
+    #[test]
+    fn test_suggested_quantity_adds_forecast_not_yet_ordered() {
+        // 30 expected, of which 12 are already ordered and reserved
+        assert_eq!(
+            suggested_quantity(dec!(15), dec!(0), dec!(10), dec!(12), dec!(30)),
+            dec!(13)
+        );
+        // More ordered than forecast: nothing extra
+        assert_eq!(
+            suggested_quantity(dec!(15), dec!(0), dec!(10), dec!(12), dec!(8)),
+            dec!(0)
+        );
+    }
+}
+
+// Tests for demand forecasting in structs::forecast
+mod forecast_tests {
+    use chrono::{NaiveDate, Utc};
+    use mamabloemetjes_backend::structs::forecast::{
+        SeasonalEvent, SeasonalEventRequest, exponential_smoothing, forecast_demand,
+        seasonal_multiplier,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2027, month, day).unwrap()
+    }
+
+    fn event(starts_on: NaiveDate, ends_on: NaiveDate, multiplier: Decimal) -> SeasonalEvent {
+        SeasonalEvent {
+            id: Uuid::new_v4(),
+            name: "Moederdag".to_string(),
+            starts_on,
+            ends_on,
+            multiplier,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_exponential_smoothing() {
+        assert_eq!(exponential_smoothing(&[], dec!(0.5)), dec!(0));
+        assert_eq!(exponential_smoothing(&[dec!(8)], dec!(0.5)), dec!(8));
+        // 8 -> 0.5 * 4 + 0.5 * 8 = 6 -> 0.5 * 10 + 0.5 * 6 = 8
+        assert_eq!(
+            exponential_smoothing(&[dec!(8), dec!(4), dec!(10)], dec!(0.5)),
+            dec!(8)
+        );
+    }
+
+    #[test]
+    fn test_strongest_event_wins() {
+        let events = vec![
+            event(date(5, 6), date(5, 9), dec!(2.5)),
+            event(date(5, 9), date(5, 9), dec!(1.5)),
+        ];
+
+        assert_eq!(seasonal_multiplier(&events, date(5, 5)), dec!(1));
+        assert_eq!(seasonal_multiplier(&events, date(5, 6)), dec!(2.5));
+        assert_eq!(seasonal_multiplier(&events, date(5, 9)), dec!(2.5));
+    }
+
+    #[test]
+    fn test_forecast_applies_multipliers() {
+        let events = vec![event(date(5, 3), date(5, 4), dec!(3))];
+        let history = vec![
+            (date(4, 28), dec!(4)),
+            (date(4, 29), dec!(4)),
+            (date(4, 30), dec!(4)),
+            (date(5, 1), dec!(4)),
+        ];
+
+        let forecast = forecast_demand(&history, date(4, 28), date(5, 2), 3, &events, dec!(0.3));
+
+        assert_eq!(forecast.baseline, dec!(4));
+        assert_eq!(forecast.days.len(), 3);
+        assert_eq!(forecast.days[0].quantity, dec!(4));
+        assert_eq!(forecast.days[1].date, date(5, 3));
+        assert_eq!(forecast.days[1].quantity, dec!(12));
+        assert_eq!(forecast.total, dec!(28));
+    }
+
+    #[test]
+    fn test_event_days_in_history_are_normalized() {
+        // The peak during an event does not raise the baseline, quiet days count as zero
+        let events = vec![event(date(2, 12), date(2, 14), dec!(3))];
+        let history = vec![
+            (date(2, 10), dec!(2)),
+            (date(2, 11), dec!(2)),
+            (date(2, 12), dec!(6)),
+            (date(2, 13), dec!(6)),
+            (date(2, 14), dec!(6)),
+        ];
+
+        let forecast = forecast_demand(&history, date(2, 10), date(2, 15), 1, &events, dec!(0.5));
+        assert_eq!(forecast.baseline, dec!(2));
+
+        let forecast = forecast_demand(&[], date(2, 10), date(2, 15), 2, &events, dec!(0.5));
+        assert_eq!(forecast.baseline, dec!(0));
+        assert_eq!(forecast.total, dec!(0));
+    }
+
+    #[test]
+    fn test_event_request_validation() {
+        let request = |name: &str, ends_on: NaiveDate, multiplier: Decimal| SeasonalEventRequest {
+            name: name.to_string(),
+            starts_on: date(12, 19),
+            ends_on,
+            multiplier,
+        };
+
+        assert!(request("Kerst", date(12, 24), dec!(1.8)).validate().is_ok());
+        assert!(request(" ", date(12, 24), dec!(1.8)).validate().is_err());
+        assert!(
+            request("Kerst", date(12, 18), dec!(1.8))
+                .validate()
+                .is_err()
+        );
+        assert!(request("Kerst", date(12, 24), dec!(0)).validate().is_err());
+    }
+}
+
+// Tests for stock locations and picking where an order is taken from
+mod stock_location_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::structs::inventory::{InventoryReservation, StockCheck};
+    use mamabloemetjes_backend::structs::stock_location::{
+        StockLocation, can_supply, normalize_postal_prefixes, preferred_location,
+    };
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn location(name: &str, is_pickup_point: bool, prefixes: &[&str]) -> StockLocation {
+        StockLocation {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            is_pickup_point,
+            delivery_postal_prefixes: prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            priority: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_zone_match_uses_longest_prefix() {
+        let shop = location("Winkel", true, &["10", "1011"]);
+
+        assert_eq!(shop.zone_match("1011 AB"), Some(4));
+        assert_eq!(shop.zone_match("1020AB"), Some(2));
+        assert_eq!(shop.zone_match("2011AB"), None);
+    }
+
+    #[test]
+    fn test_normalize_postal_prefixes() {
+        let prefixes = vec![" 10".to_string(), "1011 ab".to_string(), "10".to_string()];
+        assert_eq!(
+            normalize_postal_prefixes(&prefixes).unwrap(),
+            vec!["10".to_string(), "1011AB".to_string()]
+        );
+
+        assert!(normalize_postal_prefixes(&["AB".to_string()]).is_err());
+        assert!(normalize_postal_prefixes(&["1011ABC".to_string()]).is_err());
+        assert!(normalize_postal_prefixes(&[" ".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_preferred_location_for_delivery() {
+        let shop = location("Winkel", true, &["10"]);
+        let cold_room = location("Koelcel", false, &["1011", "20"]);
+        let locations = vec![shop.clone(), cold_room.clone()];
+
+        assert_eq!(
+            preferred_location(&locations, None, Some("1011AB")),
+            Ok(cold_room.id)
+        );
+        assert_eq!(
+            preferred_location(&locations, None, Some("1020AB")),
+            Ok(shop.id)
+        );
+        // Outside every zone the first location by priority is used
+        assert_eq!(
+            preferred_location(&locations, None, Some("3011AB")),
+            Ok(shop.id)
+        );
+        assert_eq!(preferred_location(&locations, None, None), Ok(shop.id));
+        assert!(preferred_location(&[], None, None).is_err());
+    }
+
+    #[test]
+    fn test_preferred_location_for_pickup() {
+        let shop = location("Winkel", true, &[]);
+        let cold_room = location("Koelcel", false, &[]);
+        let locations = vec![shop.clone(), cold_room.clone()];
+
+        assert_eq!(
+            preferred_location(&locations, Some(shop.id), Some("1011AB")),
+            Ok(shop.id)
+        );
+        assert!(preferred_location(&locations, Some(cold_room.id), None).is_err());
+        assert!(preferred_location(&locations, Some(Uuid::new_v4()), None).is_err());
+    }
+
+    #[test]
+    fn test_split_stock_is_not_added_up() {
+        let product_id = Uuid::new_v4();
+        let shop = HashMap::from([(product_id, dec!(3))]);
+        let cold_room = HashMap::from([(product_id, dec!(2))]);
+        let order = [InventoryReservation {
+            product_id,
+            quantity_to_reserve: dec!(4),
+        }];
+
+        // 5 in stock in total, but no location can take the whole order
+        assert!(!can_supply(&shop, &order));
+        assert!(!can_supply(&cold_room, &order));
+        assert!(!can_supply(&HashMap::new(), &order));
+        assert_eq!(
+            StockCheck::check(shop[&product_id], dec!(4), None),
+            StockCheck::Insufficient
+        );
+
+        let smaller_order = [InventoryReservation {
+            product_id,
+            quantity_to_reserve: dec!(3),
+        }];
+        assert!(can_supply(&shop, &smaller_order));
+        assert!(!can_supply(&cold_room, &smaller_order));
+    }
+}
+
+// Tests for admin product requests
+mod product_request_tests {
+    use mamabloemetjes_backend::structs::enums::{Colors, ProductType, Size};
+    use mamabloemetjes_backend::structs::product::{
+        CreateProductRequest, ProductVariant, UpdateProductRequest, normalize_sku, price_components,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn request(price: Decimal, colors: Vec<Colors>) -> CreateProductRequest {
+        CreateProductRequest {
+            name: "Zomerboeket".to_string(),
+            sku: "bq-summer-m".to_string(),
+            price,
+            description: "Vrolijk boeket".to_string(),
+            size: Size::Medium,
+            colors,
+            product_type: ProductType::Bouquet,
+            initial_stock: None,
+            parent_id: None,
+        }
+    }
+
+    #[test]
+    fn test_price_components_add_up_to_price() {
+        assert_eq!(price_components(dec!(29.95)), (dec!(24.75), dec!(5.20)));
+        assert_eq!(price_components(dec!(121)), (dec!(100.00), dec!(21.00)));
+
+        for price in [dec!(0.01), dec!(4.99), dec!(12.50), dec!(999.99)] {
+            let (subtotal, tax) = price_components(price);
+            assert_eq!(subtotal + tax, price);
+        }
+    }
+
+    #[test]
+    fn test_normalize_sku() {
+        assert_eq!(
+            normalize_sku(" bq-summer-m "),
+            Ok("BQ-SUMMER-M".to_string())
+        );
+        assert!(normalize_sku("").is_err());
+        assert!(normalize_sku("BQ SUMMER").is_err());
+        assert!(normalize_sku(&"A".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_create_request_validation() {
+        assert!(
+            request(dec!(29.95), vec![Colors::Yellow])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            request(dec!(29.950), vec![Colors::Yellow])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            request(dec!(29.955), vec![Colors::Yellow])
+                .validate()
+                .is_err()
+        );
+        assert!(request(dec!(0), vec![Colors::Yellow]).validate().is_err());
+        assert!(request(dec!(29.95), vec![]).validate().is_err());
+        assert!(
+            request(dec!(29.95), vec![Colors::Red, Colors::Red])
+                .validate()
+                .is_err()
+        );
+
+        let mut negative_stock = request(dec!(29.95), vec![Colors::Yellow]);
+        negative_stock.initial_stock = Some(dec!(-1));
+        assert!(negative_stock.validate().is_err());
+    }
+
+    #[test]
+    fn test_variant_serialization_omits_parent() {
+        let variant = ProductVariant {
+            id: Uuid::new_v4(),
+            parent_id: Uuid::new_v4(),
+            slug: "rozenboeket-klein".to_string(),
+            name: "Rozenboeket klein".to_string(),
+            sku: "BQ-ROSE-S".to_string(),
+            price: dec!(19.95),
+            discounted_price: dec!(17.95),
+            size: Size::Small,
+            colors: vec![Colors::Red],
+            stock: dec!(4),
+        };
+
+        let json = serde_json::to_value(&variant).unwrap();
+        assert!(json.get("parent_id").is_none());
+        assert_eq!(json["sku"], "BQ-ROSE-S");
+        assert_eq!(json["size"], "Small");
+    }
+
+    #[test]
+    fn test_update_request_only_checks_given_fields() {
+        let update = UpdateProductRequest {
+            name: None,
+            sku: None,
+            price: Some(dec!(19.99)),
+            description: None,
+            size: None,
+            colors: None,
+            product_type: None,
+        };
+        assert!(update.validate().is_ok());
+
+        let update = UpdateProductRequest {
+            name: Some("  ".to_string()),
+            ..update
+        };
+        assert!(update.validate().is_err());
+    }
+}
+
+// Tests for categories and occasions in structs::taxonomy
+mod taxonomy_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::structs::taxonomy::{
+        Category, CategoryRequest, OccasionRequest, build_category_tree, is_within,
+    };
+    use uuid::Uuid;
+
+    fn category(name: &str, parent_id: Option<Uuid>) -> Category {
+        Category {
+            id: Uuid::new_v4(),
+            parent_id,
+            name: name.to_string(),
+            description: None,
+            sort_order: 0,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_build_category_tree() {
+        let bouquets = category("Boeketten", None);
+        let roses = category("Rozen", Some(bouquets.id));
+        let tulips = category("Tulpen", Some(bouquets.id));
+        let red_roses = category("Rode rozen", Some(roses.id));
+        let plants = category("Planten", None);
+        let orphan = category("Wees", Some(Uuid::new_v4()));
+
+        let tree = build_category_tree(vec![
+            bouquets.clone(),
+            roses.clone(),
+            tulips.clone(),
+            red_roses.clone(),
+            plants.clone(),
+            orphan,
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].category.id, bouquets.id);
+        assert_eq!(tree[1].category.id, plants.id);
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[0].category.id, roses.id);
+        assert_eq!(tree[0].children[1].category.id, tulips.id);
+        assert_eq!(tree[0].children[0].children[0].category.id, red_roses.id);
+        assert!(tree[1].children.is_empty());
+    }
+
+    #[test]
+    fn test_is_within() {
+        let bouquets = category("Boeketten", None);
+        let roses = category("Rozen", Some(bouquets.id));
+        let red_roses = category("Rode rozen", Some(roses.id));
+        let plants = category("Planten", None);
+        let categories = vec![
+            bouquets.clone(),
+            roses.clone(),
+            red_roses.clone(),
+            plants.clone(),
+        ];
+
+        assert!(is_within(&categories, red_roses.id, bouquets.id));
+        assert!(is_within(&categories, roses.id, roses.id));
+        assert!(!is_within(&categories, bouquets.id, red_roses.id));
+        assert!(!is_within(&categories, red_roses.id, plants.id));
+    }
+
+    #[test]
+    fn test_is_within_stops_on_a_loop() {
+        let mut a = category("A", None);
+        let b = category("B", Some(a.id));
+        a.parent_id = Some(b.id);
+        let other = category("C", None);
+
+        assert!(!is_within(&[a.clone(), b], a.id, other.id));
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let request: CategoryRequest = serde_json::from_str(r#"{"name": "Boeketten"}"#).unwrap();
+        assert!(request.is_active);
+        assert_eq!(request.sort_order, 0);
+        assert!(request.validate().is_ok());
+
+        let request = CategoryRequest {
+            name: "   ".to_string(),
+            ..request
+        };
+        assert!(request.validate().is_err());
+
+        let request = CategoryRequest {
+            name: "Boeketten".to_string(),
+            description: Some("x".repeat(1001)),
+            ..request
+        };
+        assert!(request.validate().is_err());
+
+        let occasion = OccasionRequest {
+            name: "Moederdag".to_string(),
+        };
+        assert!(occasion.validate().is_ok());
+        let occasion = OccasionRequest {
+            name: String::new(),
+        };
+        assert!(occasion.validate().is_err());
+    }
+}
+
+// Tests for product image processing
+mod product_image_tests {
+    use image::{DynamicImage, ImageFormat as EncodedFormat, Rgb, RgbImage, Rgba, RgbaImage};
+    use mamabloemetjes_backend::services::product_image_service::render_image;
+    use mamabloemetjes_backend::structs::product_image::{
+        ImageFormat, ImageSize, UpdateImageRequest,
+    };
+    use std::io::Cursor;
+
+    fn encoded(image: DynamicImage, format: EncodedFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, format).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_render_jpeg_in_all_sizes() {
+        let upload = encoded(
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(1600, 1200, Rgb([200, 30, 60]))),
+            EncodedFormat::Jpeg,
+        );
+
+        let rendered = render_image(&upload).unwrap();
+        assert_eq!(rendered.len(), 6);
+
+        let find = |size, format| {
+            rendered
+                .iter()
+                .find(|image| image.size == size && image.format == format)
+                .unwrap()
+        };
+
+        // The upload itself is kept as the original
+        let original = find(ImageSize::Original, ImageFormat::Jpeg);
+        assert_eq!(original.bytes, upload);
+        assert_eq!((original.width, original.height), (1600, 1200));
+
+        let medium = find(ImageSize::Medium, ImageFormat::Webp);
+        assert_eq!((medium.width, medium.height), (800, 600));
+        assert_eq!(
+            image::guess_format(&medium.bytes).unwrap(),
+            EncodedFormat::WebP
+        );
+
+        let thumbnail = find(ImageSize::Thumbnail, ImageFormat::Jpeg);
+        assert_eq!((thumbnail.width, thumbnail.height), (200, 150));
+        assert_eq!(
+            image::guess_format(&thumbnail.bytes).unwrap(),
+            EncodedFormat::Jpeg
+        );
+    }
+
+    #[test]
+    fn test_render_does_not_enlarge_small_images() {
+        let upload = encoded(
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(120, 80, Rgba([0, 0, 0, 128]))),
+            EncodedFormat::Png,
+        );
+
+        let rendered = render_image(&upload).unwrap();
+        assert!(
+            rendered
+                .iter()
+                .all(|image| (image.width, image.height) == (120, 80))
+        );
+        assert!(
+            rendered
+                .iter()
+                .all(|image| matches!(image.format, ImageFormat::Png | ImageFormat::Webp))
+        );
+    }
+
+    #[test]
+    fn test_render_transparent_webp_falls_back_to_png() {
+        let upload = encoded(
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(300, 300, Rgba([0, 120, 0, 100]))),
+            EncodedFormat::WebP,
+        );
+
+        let rendered = render_image(&upload).unwrap();
+        let original_webp = rendered
+            .iter()
+            .find(|image| image.size == ImageSize::Original && image.format == ImageFormat::Webp)
+            .unwrap();
+        assert_eq!(original_webp.bytes, upload);
+        assert!(
+            rendered
+                .iter()
+                .any(|image| image.size == ImageSize::Thumbnail && image.format == ImageFormat::Png)
+        );
+    }
+
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for byte in data {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    #[test]
+    fn test_render_rejects_huge_dimensions_before_decoding() {
+        // A PNG claiming 100000x100000 pixels without any image data: decoding it would
+        // need tens of gigabytes, so it must be refused on the header alone
+        let mut ihdr = b"IHDR".to_vec();
+        ihdr.extend_from_slice(&100_000u32.to_be_bytes());
+        ihdr.extend_from_slice(&100_000u32.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut upload = b"\x89PNG\r\n\x1a\n".to_vec();
+        for chunk in [ihdr, b"IDAT".to_vec(), b"IEND".to_vec()] {
+            upload.extend_from_slice(&(chunk.len() as u32 - 4).to_be_bytes());
+            upload.extend_from_slice(&chunk);
+            upload.extend_from_slice(&crc32(&chunk).to_be_bytes());
+        }
+
+        let error = render_image(&upload).unwrap_err();
+        assert!(error.contains("at most"), "{}", error);
+    }
+
+    #[test]
+    fn test_render_rejects_other_files() {
+        assert!(render_image(b"GIF89a not really an image").is_err());
+        assert!(render_image(b"just some text").is_err());
+    }
+
+    #[test]
+    fn test_update_request_keeps_a_primary_image() {
+        let request = UpdateImageRequest {
+            alt_text: Some("Boeket rode rozen".to_string()),
+            is_primary: Some(true),
+        };
+        assert!(request.validate().is_ok());
+
+        let request = UpdateImageRequest {
+            alt_text: None,
+            is_primary: Some(false),
+        };
+        assert!(request.validate().is_err());
+
+        let request = UpdateImageRequest {
+            alt_text: Some("x".repeat(251)),
+            is_primary: None,
+        };
+        assert!(request.validate().is_err());
+    }
+}
+
+// Tests for product reviews in structs::review
+mod review_tests {
+    use chrono::Utc;
+    use mamabloemetjes_backend::actions::get::search::{SearchParams, parse_search_params};
+    use mamabloemetjes_backend::services::search::SearchSortField;
+    use mamabloemetjes_backend::structs::review::{
+        ModerateReviewRequest, PublicReview, PublicReviewRow, ReviewStatus, SubmitReviewRequest,
+        author_display_name,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn test_submit_request_validation() {
+        let request = SubmitReviewRequest {
+            rating: 5,
+            title: Some("Prachtig".to_string()),
+            body: "Het boeket was nog een week mooi.".to_string(),
+        };
+        assert!(request.validate().is_ok());
+
+        for rating in [0, 6, -1] {
+            let request = SubmitReviewRequest {
+                rating,
+                title: None,
+                body: "Mooi".to_string(),
+            };
+            assert!(request.validate().is_err());
+        }
+
+        let request = SubmitReviewRequest {
+            rating: 4,
+            title: None,
+            body: "   ".to_string(),
+        };
+        assert!(request.validate().is_err());
+
+        let request = SubmitReviewRequest {
+            rating: 4,
+            title: Some("x".repeat(121)),
+            body: "Mooi".to_string(),
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_moderation_must_decide() {
+        let approve = ModerateReviewRequest {
+            status: ReviewStatus::Approved,
+            note: None,
+        };
+        assert!(approve.validate().is_ok());
+
+        let back_to_pending = ModerateReviewRequest {
+            status: ReviewStatus::Pending,
+            note: None,
+        };
+        assert!(back_to_pending.validate().is_err());
+    }
+
+    #[test]
+    fn test_author_display_name() {
+        assert_eq!(author_display_name("Anna", "jansen"), "Anna J.");
+        assert_eq!(author_display_name(" Piet ", ""), "Piet");
+        assert_eq!(author_display_name("", "Jansen"), "Customer");
+    }
+
+    #[test]
+    fn test_public_review_hides_the_author() {
+        let review = PublicReview::from(PublicReviewRow {
+            id: Uuid::new_v4(),
+            rating: 4,
+            title: None,
+            body: "Mooi boeket".to_string(),
+            first_name: "Anna".to_string(),
+            last_name: "de Vries".to_string(),
+            order_id: Some(Uuid::new_v4()),
+            created_at: Utc::now(),
+        });
+
+        assert_eq!(review.author, "Anna D.");
+        assert!(review.is_verified_purchase);
+
+        let json = serde_json::to_value(&review).unwrap();
+        assert!(json.get("last_name").is_none());
+        assert!(json.get("order_id").is_none());
+    }
+
+    #[test]
+    fn test_sort_by_rating() {
+        let params = SearchParams {
+            q: None,
+            product_type: None,
+            colors: None,
+            size: None,
+            price_min: None,
+            price_max: None,
+            in_stock: None,
+            category_id: None,
+            occasion_id: None,
+            sort_by: Some("rating".to_string()),
+            sort_direction: Some("desc".to_string()),
+            page: None,
+            per_page: None,
+        };
+
+        let query = parse_search_params(params).unwrap();
+        assert!(matches!(query.sort.unwrap().field, SearchSortField::Rating));
+    }
+}
+
+// Tests for product slugs in utils::slug
+mod slug_tests {
+    use mamabloemetjes_backend::utils::slug::{first_free_slug, is_slug_of, slugify};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_slugify_folds_dutch_diacritics() {
+        assert_eq!(slugify("Crème brûlée-rozen"), "creme-brulee-rozen");
+        assert_eq!(slugify("Ideeën voor België"), "ideeen-voor-belgie");
+        assert_eq!(slugify("Café Ĳsselmeer"), "cafe-ijsselmeer");
+        assert_eq!(slugify("Rozen & Tulpen"), "rozen-en-tulpen");
+    }
+
+    #[test]
+    fn test_slugify_cleans_up_separators() {
+        assert_eq!(slugify("  Boeket   (groot)!! "), "boeket-groot");
+        assert_eq!(slugify("Boeket #12"), "boeket-12");
+        assert_eq!(slugify("!!!"), "product");
+        assert_eq!(slugify(""), "product");
+    }
+
+    #[test]
+    fn test_slugify_limits_length_at_a_word() {
+        let slug = slugify(&"zonnebloem ".repeat(20));
+        assert!(slug.len() <= 80);
+        assert!(slug.ends_with("zonnebloem"));
+    }
+
+    #[test]
+    fn test_first_free_slug() {
+        let mut taken = HashSet::new();
+        assert_eq!(first_free_slug("rozen", &taken), "rozen");
+
+        taken.insert("rozen".to_string());
+        taken.insert("rozen-2".to_string());
+        assert_eq!(first_free_slug("rozen", &taken), "rozen-3");
+    }
+
+    #[test]
+    fn test_is_slug_of() {
+        assert!(is_slug_of("rozen", "rozen"));
+        assert!(is_slug_of("rozen-3", "rozen"));
+        assert!(!is_slug_of("rozen-boeket", "rozen"));
+        assert!(!is_slug_of("rozen-", "rozen"));
+        assert!(!is_slug_of("tulpen", "rozen"));
+    }
+}
+
+// Tests for locale negotiation and product translations
+mod locale_tests {
+    use mamabloemetjes_backend::structs::locale::Locale;
+    use mamabloemetjes_backend::structs::product::ProductTranslationRequest;
+
+    #[test]
+    fn test_from_tag_ignores_region_and_case() {
+        assert_eq!(Locale::from_tag("en"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("en-GB"), Some(Locale::En));
+        assert_eq!(Locale::from_tag(" NL_be "), Some(Locale::Nl));
+        assert_eq!(Locale::from_tag("fr"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_accept_language_picks_highest_supported_quality() {
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, en;q=0.8, nl;q=0.5"),
+            Some(Locale::En)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.4, nl-NL;q=0.9"),
+            Some(Locale::Nl)
+        );
+        assert_eq!(Locale::from_accept_language("nl, en"), Some(Locale::Nl));
+        assert_eq!(Locale::from_accept_language("en;q=0, de"), None);
+        assert_eq!(Locale::from_accept_language("*"), None);
+    }
+
+    #[test]
+    fn test_lang_parameter_wins_and_default_is_dutch() {
+        assert_eq!(Locale::negotiate(Some("en"), Some("nl")), Locale::En);
+        assert_eq!(Locale::negotiate(Some("xx"), Some("en-US")), Locale::En);
+        assert_eq!(Locale::negotiate(None, Some("de, fr")), Locale::Nl);
+        assert_eq!(Locale::negotiate(None, None), Locale::DEFAULT);
+        assert!(Locale::DEFAULT.is_default());
+    }
+
+    #[test]
+    fn test_translation_request_validation() {
+        let request = ProductTranslationRequest {
+            name: "Summer bouquet".to_string(),
+            description: "Bright flowers for sunny days".to_string(),
+        };
+        assert!(request.validate(Locale::En).is_ok());
+        // The default locale is the product's own name and description
+        assert!(request.validate(Locale::Nl).is_err());
+
+        let nameless = ProductTranslationRequest {
+            name: "  ".to_string(),
+            description: String::new(),
+        };
+        assert!(nameless.validate(Locale::En).is_err());
+    }
+}
+
+// Tests for featured product scheduling
+mod featured_tests {
+    use chrono::{Duration, Utc};
+    use mamabloemetjes_backend::structs::featured::{FeaturedQuery, FeaturedRequest, FeaturedType};
+    use uuid::Uuid;
+
+    fn request() -> FeaturedRequest {
+        FeaturedRequest {
+            product_id: Uuid::new_v4(),
+            featured_type: FeaturedType::Seasonal,
+            position: 1,
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn test_open_ended_placement_is_valid() {
+        assert!(request().validate().is_ok());
+
+        let starts_later = FeaturedRequest {
+            start_date: Some(Utc::now() + Duration::days(7)),
+            ..request()
+        };
+        assert!(starts_later.validate().is_ok());
+    }
+
+    #[test]
+    fn test_window_must_end_after_it_starts() {
+        let start = Utc::now();
+        let backwards = FeaturedRequest {
+            start_date: Some(start),
+            end_date: Some(start - Duration::days(1)),
+            ..request()
+        };
+        assert!(backwards.validate().is_err());
+
+        let empty = FeaturedRequest {
+            start_date: Some(start),
+            end_date: Some(start),
+            ..request()
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_position_cannot_be_negative() {
+        let request = FeaturedRequest {
+            position: -1,
+            ..request()
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_type_defaults_to_homepage_and_parses_from_query() {
+        let request: FeaturedRequest =
+            serde_json::from_str(&format!(r#"{{"product_id": "{}"}}"#, Uuid::new_v4())).unwrap();
+        assert_eq!(request.featured_type, FeaturedType::Homepage);
+        assert_eq!(request.position, 0);
+
+        let query: FeaturedQuery = serde_json::from_str(r#"{"type": "banner"}"#).unwrap();
+        assert_eq!(query.featured_type, Some(FeaturedType::Banner));
+        assert!(serde_json::from_str::<FeaturedQuery>(r#"{"type": "popup"}"#).is_err());
+    }
+}
+
+// Tests for product recommendations
+mod recommendation_tests {
+    use mamabloemetjes_backend::structs::recommendation::{
+        CartRecommendationsRequest, RecommendationQuery,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn test_limit_defaults_and_is_clamped() {
+        assert_eq!(RecommendationQuery::default().limit(), 4);
+        assert_eq!(RecommendationQuery { limit: Some(0) }.limit(), 1);
+        assert_eq!(RecommendationQuery { limit: Some(-5) }.limit(), 1);
+        assert_eq!(RecommendationQuery { limit: Some(12) }.limit(), 12);
+        assert_eq!(RecommendationQuery { limit: Some(500) }.limit(), 20);
+    }
+
+    #[test]
+    fn test_guest_cart_size_is_limited() {
+        let request = CartRecommendationsRequest {
+            product_ids: vec![Uuid::new_v4(); 3],
+        };
+        assert!(request.validate().is_ok());
+
+        let request = CartRecommendationsRequest {
+            product_ids: vec![Uuid::new_v4(); 101],
+        };
+        assert!(request.validate().is_err());
+    }
+}
+
+// Tests for price history and scheduled price changes
+mod price_history_tests {
+    use chrono::{Duration, Utc};
+    use mamabloemetjes_backend::structs::price_history::{
+        PriceChange, PriceChangeSource, SchedulePriceChangeRequest, lowest_price_since, was_price,
+    };
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn change(
+        old_price: Option<rust_decimal::Decimal>,
+        new_price: rust_decimal::Decimal,
+        days_ago: i64,
+    ) -> PriceChange {
+        PriceChange {
+            id: Uuid::new_v4(),
+            product_id: Uuid::new_v4(),
+            old_price,
+            new_price,
+            source: PriceChangeSource::Manual,
+            changed_by: None,
+            changed_by_email: None,
+            scheduled_change_id: None,
+            changed_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_without_recent_changes_the_current_price_is_the_reference() {
+        let since = Utc::now() - Duration::days(30);
+        assert_eq!(lowest_price_since(dec!(24.95), &[], since), dec!(24.95));
+
+        let old = [change(Some(dec!(9.95)), dec!(24.95), 60)];
+        assert_eq!(lowest_price_since(dec!(24.95), &old, since), dec!(24.95));
+    }
+
+    #[test]
+    fn test_price_before_a_recent_raise_counts() {
+        // Raised from 19.95 ten days ago, so 19.95 was charged within the period
+        let since = Utc::now() - Duration::days(30);
+        let changes = [change(Some(dec!(19.95)), dec!(29.95), 10)];
+        assert_eq!(
+            lowest_price_since(dec!(29.95), &changes, since),
+            dec!(19.95)
+        );
+    }
+
+    #[test]
+    fn test_lowest_of_several_changes() {
+        let since = Utc::now() - Duration::days(30);
+        let changes = [
+            change(Some(dec!(17.50)), dec!(22.50), 2),
+            change(Some(dec!(21.00)), dec!(17.50), 12),
+            change(None, dec!(21.00), 20),
+        ];
+        assert_eq!(
+            lowest_price_since(dec!(22.50), &changes, since),
+            dec!(17.50)
+        );
+    }
+
+    #[test]
+    fn test_was_price_of_a_promotion() {
+        // Raised from 19.95 just before a 20% promotion: the promotion shows against 19.95
+        assert_eq!(was_price(dec!(19.95), dec!(14.95)), dec!(19.95));
+        assert_eq!(was_price(dec!(19.95), dec!(23.96)), dec!(23.96));
+    }
+
+    #[test]
+    fn test_schedule_request_validation() {
+        let now = Utc::now();
+        let request = SchedulePriceChangeRequest {
+            new_price: dec!(34.95),
+            effective_at: now + Duration::hours(2),
+        };
+        assert!(request.validate(now).is_ok());
+
+        let in_the_past = SchedulePriceChangeRequest {
+            new_price: dec!(34.95),
+            effective_at: now - Duration::minutes(1),
+        };
+        assert!(in_the_past.validate(now).is_err());
+
+        let too_precise = SchedulePriceChangeRequest {
+            new_price: dec!(34.955),
+            effective_at: now + Duration::hours(2),
+        };
+        assert!(too_precise.validate(now).is_err());
+
+        let free = SchedulePriceChangeRequest {
+            new_price: dec!(0),
+            effective_at: now + Duration::hours(2),
+        };
+        assert!(free.validate(now).is_err());
+    }
+}
@@ -32,6 +32,8 @@ fn create_test_order() -> IncomingOrder {
         shipping_address: create_valid_address(),
         billing_address: create_valid_address(),
         notes: Some("Test order".to_string()),
+        delivery_date: None,
+        pickup_location_id: None,
     }
 }
 
@@ -518,6 +520,8 @@ fn test_validated_incoming_order_success() {
             province: "Noord-Holland".to_string(),
         },
         notes: Some("Test order".to_string()),
+        delivery_date: None,
+        pickup_location_id: None,
     };
     assert!(order.validate().is_ok());
 }